@@ -1,12 +1,31 @@
 use anyhow::{Context, Error, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::{
     ffi::OsStr,
+    fs::File,
+    io,
     net::SocketAddr,
     path::{Path, PathBuf},
     str::FromStr,
 };
-use wasmi_wasi::{ambient_authority, Dir, TcpListener, WasiCtx, WasiCtxBuilder};
+use wasmi::{
+    Config,
+    FuelConsumptionMode,
+    InstanceAllocationStrategy,
+    PoolingAllocationConfig,
+    StackLimits,
+};
+use wasmi_wasi::{
+    ambient_authority,
+    DeterministicWasiCtxBuilder,
+    Dir,
+    DirCaps,
+    FileCaps,
+    ReadPipe,
+    TcpListener,
+    WasiCtx,
+    WritePipe,
+};
 
 /// A CLI flag value key-value argument.
 #[derive(Debug, Clone)]
@@ -28,7 +47,7 @@ impl FromStr for KeyValue {
             .find('=')
             .ok_or_else(|| anyhow::anyhow!("invalid KEY=value: no `=` found in `{}`", s))?;
         let (key, eq_value) = s.split_at(eq_pos);
-        assert!(s.starts_with('='));
+        assert!(eq_value.starts_with('='));
         let value = &eq_value[1..];
         let key = key.to_string();
         let value = value.to_string();
@@ -36,10 +55,460 @@ impl FromStr for KeyValue {
     }
 }
 
-/// Simple program to greet a person
+/// A CLI flag value mapping a guest directory to a host directory.
+///
+/// Parsed from `GUEST::HOST` or from `DIRECTORY` which maps the directory to itself.
+#[derive(Debug, Clone)]
+struct DirMapping {
+    /// The directory path under which the guest sees the directory.
+    guest: PathBuf,
+    /// The directory path on the host.
+    host: PathBuf,
+}
+
+impl FromStr for DirMapping {
+    type Err = Error;
+
+    /// Parses a CLI flag value as [`DirMapping`] type.
+    ///
+    /// # Errors
+    ///
+    /// If either the guest or the host directory of a `GUEST::HOST` pair is empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (guest, host) = s.split_once("::").unwrap_or((s, s));
+        if guest.is_empty() || host.is_empty() {
+            anyhow::bail!("invalid GUEST::HOST directory mapping: `{s}`")
+        }
+        Ok(DirMapping {
+            guest: guest.into(),
+            host: host.into(),
+        })
+    }
+}
+
+/// A directory pre-opened for the guest.
+struct PreopenDir {
+    /// The directory path under which the guest sees the directory.
+    guest: PathBuf,
+    /// The opened host directory.
+    dir: Dir,
+    /// Whether the guest is only allowed to read from the directory.
+    readonly: bool,
+}
+
+/// Command line interface of the `wasmi` WebAssembly interpreter.
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None, trailing_var_arg = true)]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+)]
 pub struct Args {
+    /// The subcommand to execute.
+    ///
+    /// Defaults to `run` if no subcommand is given.
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// The arguments of the default `run` subcommand.
+    #[clap(flatten)]
+    run: RunArgs,
+}
+
+impl Args {
+    /// Returns the subcommand given to the CLI app.
+    ///
+    /// Falls back to the `run` subcommand if no subcommand was given.
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Run(self.run))
+    }
+}
+
+/// The subcommands of the `wasmi` CLI application.
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // parsed only once per CLI invocation
+pub enum Command {
+    /// Runs a function of a Wasm module.
+    Run(RunArgs),
+    /// Prints the imports, exports and other contents of a Wasm module.
+    Inspect(InspectArgs),
+    /// Validates a Wasm module without compiling it.
+    Validate(ModuleArgs),
+    /// Compiles a Wasm module to `wasmi` bytecode and prints the compilation time.
+    ///
+    /// Useful to check that a Wasm module compiles and to benchmark compilation
+    /// with a given engine configuration. The compiled bytecode is discarded.
+    Compile(ModuleArgs),
+    /// Runs the directives of `.wast` script files and reports their outcome.
+    Wast(WastArgs),
+    /// Starts an interactive session on an instantiated Wasm module.
+    Repl(ReplArgs),
+}
+
+/// The format in which the CLI app prints its output.
+#[derive(ValueEnum, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Human,
+    /// A single JSON document.
+    Json,
+}
+
+/// The arguments of subcommands operating on a single Wasm module.
+#[derive(clap::Args, Debug)]
+pub struct ModuleArgs {
+    /// The file containing the WebAssembly module.
+    #[clap(
+        value_name = "MODULE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    wasm_file: PathBuf,
+
+    /// The configuration of the `wasmi` engine.
+    #[clap(flatten)]
+    engine: EngineArgs,
+}
+
+impl ModuleArgs {
+    /// Returns the Wasm file path given to the CLI app.
+    pub fn wasm_file(&self) -> &Path {
+        &self.wasm_file
+    }
+
+    /// Returns the [`Config`] of the `wasmi` engine given to the CLI app.
+    ///
+    /// # Errors
+    ///
+    /// If the given engine configuration is invalid.
+    pub fn config(&self) -> Result<Config> {
+        self.engine.config(Config::default())
+    }
+}
+
+/// A Wasm proposal or `wasmi` feature that can be enabled or disabled.
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WasmFeature {
+    /// The Wasm [`mutable-global`] proposal.
+    ///
+    /// [`mutable-global`]: https://github.com/WebAssembly/mutable-global
+    MutableGlobal,
+    /// The Wasm [`sign-extension`] proposal.
+    ///
+    /// [`sign-extension`]: https://github.com/WebAssembly/sign-extension-ops
+    SignExtension,
+    /// The Wasm [`saturating-float-to-int`] proposal.
+    ///
+    /// [`saturating-float-to-int`]: https://github.com/WebAssembly/nontrapping-float-to-int-conversions
+    SaturatingFloatToInt,
+    /// The Wasm [`multi-value`] proposal.
+    ///
+    /// [`multi-value`]: https://github.com/WebAssembly/multi-value
+    MultiValue,
+    /// The Wasm [`bulk-memory`] proposal.
+    ///
+    /// [`bulk-memory`]: https://github.com/WebAssembly/bulk-memory-operations
+    BulkMemory,
+    /// The Wasm [`reference-types`] proposal.
+    ///
+    /// [`reference-types`]: https://github.com/WebAssembly/reference-types
+    ReferenceTypes,
+    /// The Wasm [`tail-call`] proposal.
+    ///
+    /// [`tail-call`]: https://github.com/WebAssembly/tail-call
+    TailCall,
+    /// The Wasm [`extended-const`] proposal.
+    ///
+    /// [`extended-const`]: https://github.com/WebAssembly/extended-const
+    ExtendedConst,
+    /// Floating point types and operations.
+    Floats,
+}
+
+impl WasmFeature {
+    /// Enables or disables the [`WasmFeature`] for the `config`.
+    fn apply(self, config: &mut Config, enable: bool) {
+        match self {
+            Self::MutableGlobal => config.wasm_mutable_global(enable),
+            Self::SignExtension => config.wasm_sign_extension(enable),
+            Self::SaturatingFloatToInt => config.wasm_saturating_float_to_int(enable),
+            Self::MultiValue => config.wasm_multi_value(enable),
+            Self::BulkMemory => config.wasm_bulk_memory(enable),
+            Self::ReferenceTypes => config.wasm_reference_types(enable),
+            Self::TailCall => config.wasm_tail_call(enable),
+            Self::ExtendedConst => config.wasm_extended_const(enable),
+            Self::Floats => config.floats(enable),
+        };
+    }
+}
+
+/// The fuel consumption mode of bulk-operations.
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FuelMode {
+    /// Fuel for bulk-operations is only consumed if they succeed.
+    Lazy,
+    /// Fuel for bulk-operations is always consumed before they execute.
+    Eager,
+}
+
+impl From<FuelMode> for FuelConsumptionMode {
+    fn from(mode: FuelMode) -> Self {
+        match mode {
+            FuelMode::Lazy => Self::Lazy,
+            FuelMode::Eager => Self::Eager,
+        }
+    }
+}
+
+/// The strategy used to allocate Wasm instance entities.
+#[derive(ValueEnum, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum AllocationStrategy {
+    /// Linear memories, tables and stacks are allocated whenever they are needed.
+    #[default]
+    OnDemand,
+    /// Linear memories, tables and stacks are taken from pre-reserved pools.
+    Pooling,
+}
+
+/// The arguments configuring the `wasmi` engine.
+#[derive(clap::Args, Debug)]
+pub struct EngineArgs {
+    /// Enable the given Wasm proposals in addition to the default ones.
+    #[clap(
+        long = "wasm-features",
+        value_name = "FEATURES",
+        value_enum,
+        value_delimiter = ',',
+        action = clap::ArgAction::Append,
+    )]
+    enabled_features: Vec<WasmFeature>,
+
+    /// Disable the given Wasm proposal.
+    ///
+    /// Takes precedence over `--wasm-features`.
+    #[clap(
+        long = "disable-feature",
+        value_name = "FEATURE",
+        value_enum,
+        action = clap::ArgAction::Append,
+    )]
+    disabled_features: Vec<WasmFeature>,
+
+    /// The fuel consumption mode of bulk-operations if fuel metering is enabled.
+    #[clap(long = "fuel-mode", value_name = "MODE", value_enum)]
+    fuel_mode: Option<FuelMode>,
+
+    /// The maximum value stack height in number of values.
+    #[clap(long = "max-stack", value_name = "N")]
+    max_stack: Option<usize>,
+
+    /// The maximum number of nested function calls.
+    #[clap(long = "max-recursion-depth", value_name = "N")]
+    max_recursion_depth: Option<usize>,
+
+    /// Execute Wasm deterministically with canonical NaNs and the default Wasm proposals only.
+    #[clap(long = "deterministic")]
+    deterministic: bool,
+
+    /// Initialize linear memories from pre-computed copy-on-write images.
+    #[clap(long = "memory-init-cow")]
+    memory_init_cow: bool,
+
+    /// The strategy used to allocate linear memories, tables and stacks.
+    #[clap(
+        long = "allocation-strategy",
+        value_name = "STRATEGY",
+        value_enum,
+        default_value_t
+    )]
+    allocation_strategy: AllocationStrategy,
+
+    /// The total number of linear memory slots of the pooling allocator.
+    #[clap(long = "pool-total-memories", value_name = "N")]
+    pool_total_memories: Option<u32>,
+
+    /// The maximum number of Wasm pages of each linear memory slot of the pooling allocator.
+    #[clap(long = "pool-memory-pages", value_name = "N")]
+    pool_memory_pages: Option<u32>,
+
+    /// The total number of table slots of the pooling allocator.
+    #[clap(long = "pool-total-tables", value_name = "N")]
+    pool_total_tables: Option<u32>,
+
+    /// The maximum number of elements of each table slot of the pooling allocator.
+    #[clap(long = "pool-table-elements", value_name = "N")]
+    pool_table_elements: Option<u32>,
+}
+
+impl EngineArgs {
+    /// Applies the [`EngineArgs`] on top of the given `config` and returns it.
+    ///
+    /// # Errors
+    ///
+    /// - If the given stack limits are invalid.
+    /// - If pooling allocator limits are given without the pooling allocation strategy.
+    pub fn config(&self, mut config: Config) -> Result<Config> {
+        for feature in &self.enabled_features {
+            feature.apply(&mut config, true);
+        }
+        for feature in &self.disabled_features {
+            feature.apply(&mut config, false);
+        }
+        if let Some(mode) = self.fuel_mode {
+            config.fuel_consumption_mode(mode.into());
+        }
+        if self.max_stack.is_some() || self.max_recursion_depth.is_some() {
+            let defaults = StackLimits::default();
+            let max_stack = self
+                .max_stack
+                .unwrap_or(defaults.maximum_value_stack_height());
+            let max_recursion_depth = self
+                .max_recursion_depth
+                .unwrap_or(defaults.maximum_recursion_depth());
+            let initial_stack = defaults.initial_value_stack_height().min(max_stack);
+            let limits = StackLimits::new(initial_stack, max_stack, max_recursion_depth)
+                .map_err(|error| anyhow::anyhow!("invalid stack limits: {error}"))?;
+            config.set_stack_limits(limits);
+        }
+        config
+            .deterministic(self.deterministic)
+            .memory_init_cow(self.memory_init_cow);
+        if let Some(strategy) = self.allocation_strategy()? {
+            config.allocation_strategy(strategy);
+        }
+        Ok(config)
+    }
+
+    /// Returns the [`InstanceAllocationStrategy`] if one other than the default was given.
+    ///
+    /// # Errors
+    ///
+    /// If pooling allocator limits are given without the pooling allocation strategy.
+    fn allocation_strategy(&self) -> Result<Option<InstanceAllocationStrategy>> {
+        let limits = [
+            self.pool_total_memories,
+            self.pool_memory_pages,
+            self.pool_total_tables,
+            self.pool_table_elements,
+        ];
+        if self.allocation_strategy != AllocationStrategy::Pooling {
+            if limits.iter().any(Option::is_some) {
+                anyhow::bail!("`--pool-*` options require `--allocation-strategy pooling`")
+            }
+            return Ok(None);
+        }
+        let mut pooling = PoolingAllocationConfig::default();
+        if let Some(amount) = self.pool_total_memories {
+            pooling.total_memories(amount);
+        }
+        if let Some(amount) = self.pool_memory_pages {
+            pooling.memory_pages(amount);
+        }
+        if let Some(amount) = self.pool_total_tables {
+            pooling.total_tables(amount);
+        }
+        if let Some(amount) = self.pool_table_elements {
+            pooling.table_elements(amount);
+        }
+        Ok(Some(InstanceAllocationStrategy::Pooling(pooling)))
+    }
+}
+
+/// The arguments of the `inspect` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct InspectArgs {
+    /// The inspected Wasm module.
+    #[clap(flatten)]
+    module: ModuleArgs,
+
+    /// The format in which the contents of the Wasm module are printed.
+    #[clap(long = "output", value_name = "FORMAT", value_enum, default_value_t)]
+    output: OutputFormat,
+
+    /// Also print the translated `wasmi` bytecode of all internal functions.
+    #[clap(long = "bytecode")]
+    bytecode: bool,
+
+    /// Translate the Wasm module with fuel metering enabled.
+    ///
+    /// The printed bytecode then contains the instructions consuming fuel
+    /// whose amounts depend on the given `--fuel-mode`.
+    #[clap(long = "fuel-metering", requires = "bytecode")]
+    fuel_metering: bool,
+}
+
+impl InspectArgs {
+    /// Returns the Wasm file path given to the CLI app.
+    pub fn wasm_file(&self) -> &Path {
+        self.module.wasm_file()
+    }
+
+    /// Returns the [`Config`] of the `wasmi` engine given to the CLI app.
+    ///
+    /// Enables fuel metering if `--fuel-metering` was given.
+    ///
+    /// # Errors
+    ///
+    /// If the given engine configuration is invalid.
+    pub fn config(&self) -> Result<Config> {
+        let mut config = Config::default();
+        config.consume_fuel(self.fuel_metering);
+        self.module.engine.config(config)
+    }
+
+    /// Returns the format in which the contents of the Wasm module are printed.
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
+
+    /// Returns `true` if the translated `wasmi` bytecode shall be printed.
+    pub fn bytecode(&self) -> bool {
+        self.bytecode
+    }
+}
+
+/// The arguments of the `wast` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct WastArgs {
+    /// The `.wast` script files to run.
+    #[clap(
+        value_name = "FILES",
+        value_hint = clap::ValueHint::FilePath,
+        required = true,
+    )]
+    files: Vec<PathBuf>,
+
+    /// The configuration of the `wasmi` engine.
+    #[clap(flatten)]
+    engine: EngineArgs,
+}
+
+impl WastArgs {
+    /// Returns the `.wast` script files to run.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Returns the [`Config`] of the `wasmi` engine given to the CLI app.
+    ///
+    /// Enables the Wasm `tail-call` and `extended-const` proposals by default.
+    ///
+    /// # Errors
+    ///
+    /// If the given engine configuration is invalid.
+    pub fn config(&self) -> Result<Config> {
+        let mut config = Config::default();
+        config.wasm_tail_call(true).wasm_extended_const(true);
+        self.engine.config(config)
+    }
+}
+
+/// The `WASI` arguments of subcommands instantiating a Wasm module.
+#[derive(clap::Args, Debug)]
+pub struct WasiArgs {
     /// The host directory to pre-open for the `guest` to use.
     #[clap(
         long = "dir",
@@ -49,7 +518,29 @@ pub struct Args {
     )]
     dirs: Vec<PathBuf>,
 
+    /// The host directory to pre-open for the `guest` under a different guest path.
+    #[clap(
+        long = "mapdir",
+        value_name = "GUEST::HOST",
+        value_parser(DirMapping::from_str),
+        action = clap::ArgAction::Append,
+    )]
+    mapdirs: Vec<DirMapping>,
+
+    /// The host directory to pre-open for the `guest` to only read from.
+    ///
+    /// Optionally maps the host directory to a different guest path via `GUEST::HOST`.
+    #[clap(
+        long = "dir-readonly",
+        value_name = "DIRECTORY",
+        value_parser(DirMapping::from_str),
+        action = clap::ArgAction::Append,
+    )]
+    readonly_dirs: Vec<DirMapping>,
+
     /// The socket address provided to the module. Allows it to perform socket-related `WASI` ops.
+    ///
+    /// No sockets are made available to the module unless given via this option.
     #[clap(
         long = "tcplisten",
         value_name = "SOCKET ADDRESS",
@@ -66,12 +557,190 @@ pub struct Args {
     )]
     envs: Vec<KeyValue>,
 
+    /// Make all environment variables of the host available for the program.
+    ///
+    /// Variables given via `--env` take precedence over inherited ones.
+    #[clap(long = "inherit-env")]
+    inherit_env: bool,
+
+    /// Make the WASI clocks and randomness deterministic for reproducible runs.
+    ///
+    /// The clocks start at the Unix epoch and advance by one millisecond per read
+    /// and the random number generator uses a fixed seed.
+    #[clap(long = "deterministic-wasi")]
+    deterministic_wasi: bool,
+
+    /// The file that is read as standard input of the program instead of the host's.
+    #[clap(
+        long = "stdin",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    stdin: Option<PathBuf>,
+
+    /// The file that is written as standard output of the program instead of the host's.
+    #[clap(
+        long = "stdout",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    stdout: Option<PathBuf>,
+}
+
+impl WasiArgs {
+    /// Pre-opens all directories given in `--dir`, `--mapdir` and `--dir-readonly`.
+    ///
+    /// # Errors
+    ///
+    /// If any of the given directories cannot be opened.
+    fn preopen_dirs(&self) -> Result<Vec<PreopenDir>> {
+        let dirs = self.dirs.iter().map(|path| (path, path, false));
+        let mapdirs = self
+            .mapdirs
+            .iter()
+            .map(|mapping| (&mapping.guest, &mapping.host, false));
+        let readonly_dirs = self
+            .readonly_dirs
+            .iter()
+            .map(|mapping| (&mapping.guest, &mapping.host, true));
+        dirs.chain(mapdirs)
+            .chain(readonly_dirs)
+            .map(|(guest, host, readonly)| {
+                let dir = Dir::open_ambient_dir(host, ambient_authority()).with_context(|| {
+                    format!("failed to open directory '{host:?}' with ambient authority")
+                })?;
+                Ok(PreopenDir {
+                    guest: guest.clone(),
+                    dir,
+                    readonly,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    }
+
+    /// Opens sockets given in `--tcplisten` and returns them for use by the [`WasiCtx`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the given socket addresses in `--tcplisten` cannot be listened to.
+    fn preopen_sockets(&self) -> Result<Vec<TcpListener>> {
+        self.tcplisten
+            .iter()
+            .map(|addr| {
+                let std_tcp_listener = std::net::TcpListener::bind(addr)
+                    .with_context(|| format!("failed to bind to tcp address '{addr}'"))?;
+                std_tcp_listener.set_nonblocking(true)?;
+                Ok(TcpListener::from_std(std_tcp_listener))
+            })
+            .collect::<Result<Vec<_>>>()
+    }
+
+    /// Returns the environment variables made available to the program.
+    ///
+    /// Includes the inherited environment variables of the host if `--inherit-env` was given.
+    fn envs(&self) -> Vec<(String, String)> {
+        let mut envs = self
+            .envs
+            .iter()
+            .map(|KeyValue { key, value }| (key.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        if self.inherit_env {
+            let inherited = std::env::vars()
+                .filter(|(key, _)| !self.envs.iter().any(|env| &env.key == key))
+                .collect::<Vec<_>>();
+            envs.extend(inherited);
+        }
+        envs
+    }
+
+    /// Creates the [`WasiCtx`] receiving the arguments `argv`.
+    ///
+    /// The guest stdout is redirected to the host stderr if `stdout_to_stderr` is `true`
+    /// and no `--stdout` file was given.
+    ///
+    /// # Errors
+    ///
+    /// - If any of the pre-opened directories or sockets cannot be opened.
+    /// - If the files given in `--stdin` or `--stdout` cannot be opened.
+    fn wasi_context(&self, argv: &[String], stdout_to_stderr: bool) -> Result<WasiCtx, Error> {
+        let mut wasi_builder = DeterministicWasiCtxBuilder::new();
+        wasi_builder = wasi_builder.envs(&self.envs())?;
+        wasi_builder = wasi_builder.args(argv)?;
+        wasi_builder = wasi_builder.inherit_stdio();
+        if self.deterministic_wasi {
+            wasi_builder = wasi_builder.deterministic(0);
+        }
+        if let Some(path) = &self.stdin {
+            let file = File::open(path)
+                .with_context(|| format!("failed to open stdin file '{path:?}'"))?;
+            wasi_builder = wasi_builder.stdin(Box::new(ReadPipe::new(file)));
+        }
+        if let Some(path) = &self.stdout {
+            let file = File::create(path)
+                .with_context(|| format!("failed to create stdout file '{path:?}'"))?;
+            wasi_builder = wasi_builder.stdout(Box::new(WritePipe::new(file)));
+        } else if stdout_to_stderr {
+            // The guest must not interleave its output with the JSON document on stdout.
+            wasi_builder = wasi_builder.stdout(Box::new(WritePipe::new(io::stderr())));
+        }
+        // Add pre-opened TCP sockets.
+        //
+        // Note that `num_fd` starts at 3 because the inherited `stdin`, `stdout` and `stderr`
+        // are already mapped to `0, 1, 2` respectively.
+        let mut num_fd = 3;
+        for socket in self.preopen_sockets()? {
+            wasi_builder = wasi_builder.preopened_socket(num_fd, socket)?;
+            num_fd += 1;
+        }
+        let mut wasi_ctx = wasi_builder.build();
+        // Add pre-opened directories with the capabilities granted to them.
+        for PreopenDir {
+            guest,
+            dir,
+            readonly,
+        } in self.preopen_dirs()?
+        {
+            let (dir_caps, file_caps) = match readonly {
+                true => (readonly_dir_caps(), readonly_file_caps()),
+                false => (DirCaps::all(), FileCaps::all()),
+            };
+            let dir = Box::new(wasmi_wasi::dir::Dir::from_cap_std(dir));
+            wasi_ctx.insert_dir(num_fd, dir, dir_caps, file_caps, guest);
+            num_fd += 1;
+        }
+        Ok(wasi_ctx)
+    }
+}
+
+/// The arguments of the `run` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct RunArgs {
+    /// The `WASI` capabilities granted to the Wasm module.
+    #[clap(flatten)]
+    wasi: WasiArgs,
+
+    /// The Wasm module to instantiate and register under `NAME` before the main module.
+    ///
+    /// The exports of the preloaded module can be imported by the main module
+    /// and all subsequently preloaded modules using `NAME` as import module name.
+    #[clap(
+        long = "preload",
+        value_name = "NAME=MODULE",
+        value_parser(KeyValue::from_str),
+        action = clap::ArgAction::Append,
+    )]
+    preloads: Vec<KeyValue>,
+
     /// The file containing the WebAssembly module to execute.
+    //
+    // Note: this is only optional so that the `run` arguments
+    //       can be omitted when another subcommand is given.
     #[clap(
         value_name = "MODULE",
         value_hint = clap::ValueHint::FilePath,
+        required = true,
     )]
-    wasm_file: PathBuf,
+    wasm_file: Option<PathBuf>,
 
     /// The function to invoke.
     ///
@@ -88,15 +757,60 @@ pub struct Args {
     #[clap(long = "fuel", value_name = "N")]
     fuel: Option<u64>,
 
+    /// Print the fuel consumed by every Wasm function after the execution.
+    #[clap(long = "fuel-report", requires = "fuel")]
+    fuel_report: bool,
+
+    /// The configuration of the `wasmi` engine.
+    #[clap(flatten)]
+    engine: EngineArgs,
+
+    /// The format in which the outcome of the execution is printed.
+    ///
+    /// With `json` the WASI guest writes its stdout to the stderr of the host
+    /// unless `--stdout` is given so that stdout only contains the JSON document.
+    #[clap(long = "output", value_name = "FORMAT", value_enum, default_value_t)]
+    output: OutputFormat,
+
+    /// Profile the execution and write its call stacks to FILE.
+    ///
+    /// The call stacks are written in the collapsed stack format of flamegraph tools
+    /// and weighted by the number of executed `wasmi` bytecode instructions.
+    #[clap(long = "profile", value_name = "FILE")]
+    profile: Option<PathBuf>,
+
+    /// Record the host function calls of the execution to FILE.
+    ///
+    /// The recording contains the parameters, results and memory changes of
+    /// every host call and can be replayed via `--replay-host-calls`.
+    #[clap(
+        long = "record-host-calls",
+        value_name = "FILE",
+        conflicts_with = "replay_host_calls"
+    )]
+    record_host_calls: Option<PathBuf>,
+
+    /// Replay the host function calls recorded to FILE instead of calling the host.
+    ///
+    /// The execution fails if it issues host calls that differ from the recorded ones.
+    #[clap(long = "replay-host-calls", value_name = "FILE")]
+    replay_host_calls: Option<PathBuf>,
+
     /// Arguments given to the Wasm module or the invoked function.
-    #[clap(value_name = "ARGS")]
+    #[clap(value_name = "ARGS", trailing_var_arg = true)]
     func_args: Vec<String>,
 }
 
-impl Args {
+impl RunArgs {
     /// Returns the Wasm file path given to the CLI app.
+    ///
+    /// # Panics
+    ///
+    /// If no Wasm file was given to the CLI app which is prevented by `clap`.
     pub fn wasm_file(&self) -> &Path {
-        &self.wasm_file
+        self.wasm_file
+            .as_deref()
+            .expect("the Wasm file is a required argument")
     }
 
     /// Returns the name of the invoked function if any.
@@ -109,43 +823,66 @@ impl Args {
         &self.func_args[..]
     }
 
+    /// Returns the format in which the outcome of the execution is printed.
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
+
+    /// Returns the file to which the profile of the execution is written if any.
+    pub fn profile(&self) -> Option<&Path> {
+        self.profile.as_deref()
+    }
+
+    /// Returns the file to which the host calls of the execution are recorded if any.
+    pub fn record_host_calls(&self) -> Option<&Path> {
+        self.record_host_calls.as_deref()
+    }
+
+    /// Returns the file from which the host calls of the execution are replayed if any.
+    pub fn replay_host_calls(&self) -> Option<&Path> {
+        self.replay_host_calls.as_deref()
+    }
+
+    /// Returns the names and Wasm file paths of the modules given in `--preload`.
+    ///
+    /// The modules are returned in the order in which they were given to the CLI app.
+    pub fn preloads(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.preloads
+            .iter()
+            .map(|KeyValue { key, value }| (key.as_str(), Path::new(value)))
+    }
+
     /// Returns the amount of fuel given to the CLI app if any.
     pub fn fuel(&self) -> Option<u64> {
         self.fuel
     }
 
-    /// Pre-opens all directories given in `--dir` and returns them for use by the [`WasiCtx`].
-    ///
-    /// # Errors
-    ///
-    /// If any of the given directions in `--dir` cannot be opened.
-    fn preopen_dirs(&self) -> Result<Vec<(&Path, Dir)>> {
-        self.dirs
-            .iter()
-            .map(|path| {
-                let dir = Dir::open_ambient_dir(path, ambient_authority()).with_context(|| {
-                    format!("failed to open directory '{path:?}' with ambient authority")
-                })?;
-                Ok((path.as_ref(), dir))
-            })
-            .collect::<Result<Vec<_>>>()
+    /// Returns `true` if `--fuel-report` was given to the CLI app.
+    pub fn fuel_report(&self) -> bool {
+        self.fuel_report
     }
 
-    /// Opens sockets given in `--tcplisten` and returns them for use by the [`WasiCtx`].
+    /// Returns the [`Config`] of the `wasmi` engine given to the CLI app.
+    ///
+    /// - Enables fuel metering if `--fuel` was given.
+    /// - Enables per-function fuel accounting if `--fuel-report` was given.
+    /// - Enables profiling if `--profile` was given.
     ///
     /// # Errors
     ///
-    /// If any of the given socket addresses in `--tcplisten` cannot be listened to.
-    fn preopen_sockets(&self) -> Result<Vec<TcpListener>> {
-        self.tcplisten
-            .iter()
-            .map(|addr| {
-                let std_tcp_listener = std::net::TcpListener::bind(addr)
-                    .with_context(|| format!("failed to bind to tcp address '{addr}'"))?;
-                std_tcp_listener.set_nonblocking(true)?;
-                Ok(TcpListener::from_std(std_tcp_listener))
-            })
-            .collect::<Result<Vec<_>>>()
+    /// If the given engine configuration is invalid.
+    pub fn config(&self) -> Result<Config> {
+        let mut config = Config::default();
+        if self.fuel.is_some() {
+            config.consume_fuel(true);
+        }
+        if self.fuel_report {
+            config.fuel_accounting(true);
+        }
+        if self.profile.is_some() {
+            config.profiling(true);
+        }
+        self.engine.config(config)
     }
 
     /// Returns the arguments that the WASI invokation expects to receive.
@@ -159,7 +896,7 @@ impl Args {
         // The WebAssembly filename is expected to be the first argument to WASI.
         // Note that the module name still has it's `.wasm` file extension.
         let module_name = self
-            .wasm_file
+            .wasm_file()
             .file_name()
             .and_then(OsStr::to_str)
             .unwrap_or("")
@@ -170,24 +907,125 @@ impl Args {
     }
 
     /// Creates the [`WasiCtx`] for this session.
+    ///
+    /// The guest stdout is redirected to the host stderr if `--output json` was given.
+    ///
+    /// # Errors
+    ///
+    /// - If any of the pre-opened directories or sockets cannot be opened.
+    /// - If the files given in `--stdin` or `--stdout` cannot be opened.
     pub fn wasi_context(&self) -> Result<WasiCtx, Error> {
-        let mut wasi_builder = WasiCtxBuilder::new();
-        for KeyValue { key, value } in &self.envs {
-            wasi_builder = wasi_builder.env(key, value)?;
-        }
-        wasi_builder = wasi_builder.args(&self.argv())?;
-        // Add pre-opened TCP sockets.
-        //
-        // Note that `num_fd` starts at 3 because the inherited `stdin`, `stdout` and `stderr`
-        // are already mapped to `0, 1, 2` respectively.
-        wasi_builder = wasi_builder.inherit_stdio();
-        for (socket, num_fd) in self.preopen_sockets()?.into_iter().zip(3..) {
-            wasi_builder = wasi_builder.preopened_socket(num_fd, socket)?;
-        }
-        // Add pre-opened directories.
-        for (dir_name, dir) in self.preopen_dirs()? {
-            wasi_builder = wasi_builder.preopened_dir(dir, dir_name)?;
+        self.wasi
+            .wasi_context(&self.argv(), self.output == OutputFormat::Json)
+    }
+}
+
+/// The arguments of the `repl` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct ReplArgs {
+    /// The `WASI` capabilities granted to the Wasm module.
+    #[clap(flatten)]
+    wasi: WasiArgs,
+
+    /// The Wasm module to instantiate and register under `NAME` before the main module.
+    ///
+    /// The exports of the preloaded module can be imported by the main module
+    /// and all subsequently preloaded modules using `NAME` as import module name.
+    #[clap(
+        long = "preload",
+        value_name = "NAME=MODULE",
+        value_parser(KeyValue::from_str),
+        action = clap::ArgAction::Append,
+    )]
+    preloads: Vec<KeyValue>,
+
+    /// The file containing the WebAssembly module to instantiate.
+    #[clap(
+        value_name = "MODULE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    wasm_file: PathBuf,
+
+    /// Enable execution fuel metering with N units of fuel.
+    ///
+    /// The execution of a command traps after running out of the N units of fuel.
+    #[clap(long = "fuel", value_name = "N")]
+    fuel: Option<u64>,
+
+    /// The configuration of the `wasmi` engine.
+    #[clap(flatten)]
+    engine: EngineArgs,
+}
+
+impl ReplArgs {
+    /// Returns the Wasm file path given to the CLI app.
+    pub fn wasm_file(&self) -> &Path {
+        &self.wasm_file
+    }
+
+    /// Returns the names and Wasm file paths of the modules given in `--preload`.
+    ///
+    /// The modules are returned in the order in which they were given to the CLI app.
+    pub fn preloads(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.preloads
+            .iter()
+            .map(|KeyValue { key, value }| (key.as_str(), Path::new(value)))
+    }
+
+    /// Returns the amount of fuel given to the CLI app if any.
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Returns the [`Config`] of the `wasmi` engine given to the CLI app.
+    ///
+    /// Enables fuel metering if `--fuel` was given.
+    ///
+    /// # Errors
+    ///
+    /// If the given engine configuration is invalid.
+    pub fn config(&self) -> Result<Config> {
+        let mut config = Config::default();
+        if self.fuel.is_some() {
+            config.consume_fuel(true);
         }
-        Ok(wasi_builder.build())
+        self.engine.config(config)
+    }
+
+    /// Creates the [`WasiCtx`] for this session.
+    ///
+    /// The only argument the guest receives is the module file name itself.
+    ///
+    /// # Errors
+    ///
+    /// - If any of the pre-opened directories or sockets cannot be opened.
+    /// - If the files given in `--stdin` or `--stdout` cannot be opened.
+    pub fn wasi_context(&self) -> Result<WasiCtx, Error> {
+        let module_name = self
+            .wasm_file
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or("")
+            .into();
+        self.wasi.wasi_context(&[module_name], false)
     }
 }
+
+/// Returns the [`DirCaps`] of directories pre-opened via `--dir-readonly`.
+fn readonly_dir_caps() -> DirCaps {
+    DirCaps::OPEN
+        | DirCaps::READDIR
+        | DirCaps::READLINK
+        | DirCaps::PATH_FILESTAT_GET
+        | DirCaps::FILESTAT_GET
+}
+
+/// Returns the [`FileCaps`] of files within directories pre-opened via `--dir-readonly`.
+fn readonly_file_caps() -> FileCaps {
+    FileCaps::READ
+        | FileCaps::SEEK
+        | FileCaps::TELL
+        | FileCaps::ADVISE
+        | FileCaps::FILESTAT_GET
+        | FileCaps::POLL_READWRITE
+}
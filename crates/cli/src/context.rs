@@ -1,7 +1,7 @@
 use crate::utils;
 use anyhow::{anyhow, Error};
 use std::path::Path;
-use wasmi::{Config, ExternType, Func, FuncType, Instance, Module, Store};
+use wasmi::{Config, Engine, Func, Global, Instance, Linker, Memory, Module, Store};
 use wasmi_wasi::WasiCtx;
 
 /// The [`Context`] for the `wasmi` CLI application.
@@ -22,17 +22,17 @@ impl Context {
     /// # Errors
     ///
     /// - If parsing, validating, compiling or instantiating the Wasm module failed.
+    /// - If parsing, validating, compiling or instantiating any of the `preloads` failed.
     /// - If adding WASI defintions to the linker failed.
-    pub fn new(wasm_file: &Path, wasi_ctx: WasiCtx, fuel: Option<u64>) -> Result<Self, Error> {
-        let mut config = Config::default();
-        if fuel.is_some() {
-            config.consume_fuel(true);
-        }
-        let engine = wasmi::Engine::new(&config);
-        let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
-        let module = wasmi::Module::new(&engine, &mut &wasm_bytes[..]).map_err(|error| {
-            anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}")
-        })?;
+    pub fn new<'a>(
+        wasm_file: &Path,
+        preloads: impl IntoIterator<Item = (&'a str, &'a Path)>,
+        config: &Config,
+        wasi_ctx: WasiCtx,
+        fuel: Option<u64>,
+    ) -> Result<Self, Error> {
+        let engine = wasmi::Engine::new(config);
+        let module = compile_module(&engine, wasm_file)?;
         let mut store = wasmi::Store::new(&engine, wasi_ctx);
         if let Some(fuel) = fuel {
             store.add_fuel(fuel).unwrap_or_else(|error| {
@@ -42,6 +42,9 @@ impl Context {
         let mut linker = <wasmi::Linker<WasiCtx>>::new(&engine);
         wasmi_wasi::add_to_linker(&mut linker, |ctx| ctx)
             .map_err(|error| anyhow!("failed to add WASI definitions to the linker: {error}"))?;
+        for (name, preload_file) in preloads {
+            preload_module(&mut linker, &mut store, name, preload_file)?;
+        }
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre| pre.start(&mut store))
@@ -53,17 +56,9 @@ impl Context {
         })
     }
 
-    /// Returns the exported named functions of the Wasm [`Module`].
-    ///
-    /// [`Module`]: wasmi::Module
-    pub fn exported_funcs(&self) -> impl Iterator<Item = (&str, FuncType)> {
-        self.module.exports().filter_map(|export| {
-            let name = export.name();
-            match export.ty() {
-                ExternType::Func(func_type) => Some((name, func_type.clone())),
-                _ => None,
-            }
-        })
+    /// Returns the Wasm [`Module`] of the [`Context`].
+    pub fn module(&self) -> &Module {
+        &self.module
     }
 
     /// Returns a shared reference to the [`Store`] of the [`Context`].
@@ -76,6 +71,16 @@ impl Context {
         &mut self.store
     }
 
+    /// Returns the exported linear memory named `name` if any.
+    pub fn get_memory(&self, name: &str) -> Option<Memory> {
+        self.instance.get_memory(&self.store, name)
+    }
+
+    /// Returns the exported global variable named `name` if any.
+    pub fn get_global(&self, name: &str) -> Option<Global> {
+        self.instance.get_global(&self.store, name)
+    }
+
     /// Returns the exported function named `name` if any.
     pub fn get_func(&self, name: &str) -> Result<Func, Error> {
         self.instance
@@ -83,3 +88,44 @@ impl Context {
             .ok_or_else(|| anyhow!("failed to find function named {name:?} in the Wasm module"))
     }
 }
+
+/// Parses, validates and compiles the Wasm module stored in `wasm_file`.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read, parsed, validated or compiled.
+fn compile_module(engine: &Engine, wasm_file: &Path) -> Result<Module, Error> {
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    Module::new(engine, &mut &wasm_bytes[..])
+        .map_err(|error| anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}"))
+}
+
+/// Instantiates the Wasm module stored in `wasm_file` and registers its exports under `name`.
+///
+/// # Errors
+///
+/// - If the Wasm module cannot be compiled, linked, instantiated or started.
+/// - If any of its exports clashes with an existing definition of the `linker`.
+fn preload_module(
+    linker: &mut Linker<WasiCtx>,
+    store: &mut Store<WasiCtx>,
+    name: &str,
+    wasm_file: &Path,
+) -> Result<(), Error> {
+    let module = compile_module(linker.engine(), wasm_file)?;
+    let instance = linker
+        .instantiate(&mut *store, &module)
+        .and_then(|pre| pre.start(&mut *store))
+        .map_err(|error| {
+            anyhow!("failed to instantiate preloaded module {name:?} from {wasm_file:?}: {error}")
+        })?;
+    for export in instance.exports(&*store) {
+        let export_name = export.name();
+        linker
+            .define(name, export_name, export.into_extern())
+            .map_err(|error| {
+                anyhow!("failed to register export {export_name:?} of preloaded module {name:?}: {error}")
+            })?;
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::context::Context;
 use std::fmt::{self, Display};
-use wasmi::{core::ValueType, FuncType, Value};
+use wasmi::{core::ValueType, ExternType, FuncType, Module, Mutability, Value};
 
 /// [`Display`]-wrapper type for [`ValueType`].
 pub struct DisplayValueType<'a>(&'a ValueType);
@@ -104,6 +104,52 @@ impl fmt::Display for DisplayFuncType<'_> {
     }
 }
 
+/// [`Display`]-wrapper type around [`ExternType`].
+pub struct DisplayExternType<'a>(&'a ExternType);
+
+impl<'a> From<&'a ExternType> for DisplayExternType<'a> {
+    fn from(extern_type: &'a ExternType) -> Self {
+        Self(extern_type)
+    }
+}
+
+impl Display for DisplayExternType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ExternType::Func(func_type) => write!(f, "{}", DisplayFuncType::from(func_type)),
+            ExternType::Global(global_type) => {
+                let mutability = match global_type.mutability() {
+                    Mutability::Const => "",
+                    Mutability::Var => "mut ",
+                };
+                let content = global_type.content();
+                write!(f, "global {mutability}{}", DisplayValueType::from(&content))
+            }
+            ExternType::Memory(memory_type) => {
+                let initial = u32::from(memory_type.initial_pages());
+                write!(f, "memory {initial}")?;
+                if let Some(maximum) = memory_type.maximum_pages() {
+                    write!(f, "..={}", u32::from(maximum))?;
+                }
+                write!(f, " pages")
+            }
+            ExternType::Table(table_type) => {
+                let element = table_type.element();
+                write!(
+                    f,
+                    "table {} {}",
+                    DisplayValueType::from(&element),
+                    table_type.minimum()
+                )?;
+                if let Some(maximum) = table_type.maximum() {
+                    write!(f, "..={maximum}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// [`Display`]-wrapper for generic sequences with separators.
 #[derive(Debug)]
 pub struct DisplaySequence<'a, T> {
@@ -143,18 +189,31 @@ where
     }
 }
 
-/// [`Display`]-wrapper for exported functions of a [`Context`].
-pub struct DisplayExportedFuncs<'a>(&'a Context);
+/// [`Display`]-wrapper for exported functions of a [`Module`].
+pub struct DisplayExportedFuncs<'a>(&'a Module);
 
 impl<'a> From<&'a Context> for DisplayExportedFuncs<'a> {
     fn from(ctx: &'a Context) -> Self {
-        Self(ctx)
+        Self(ctx.module())
+    }
+}
+
+impl<'a> From<&'a Module> for DisplayExportedFuncs<'a> {
+    fn from(module: &'a Module) -> Self {
+        Self(module)
     }
 }
 
 impl Display for DisplayExportedFuncs<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let exported_funcs = self.0.exported_funcs().collect::<Box<[_]>>();
+        let exported_funcs = self
+            .0
+            .exports()
+            .filter_map(|export| match export.ty() {
+                ExternType::Func(func_type) => Some((export.name(), func_type.clone())),
+                _ => None,
+            })
+            .collect::<Box<[_]>>();
         if exported_funcs.is_empty() {
             return write!(f, "No exported functions found for the Wasm module.");
         }
@@ -0,0 +1,352 @@
+use crate::display::{DisplayExternType, DisplayFuncType, DisplayValueType};
+use anyhow::{anyhow, bail, Error};
+use serde_json::{json, Value as JsonValue};
+use std::fmt::{self, Display};
+use wasmi::{
+    core::ValueType,
+    Disassembly,
+    ExternType,
+    GlobalType,
+    MemoryType,
+    Module,
+    Mutability,
+    TableType,
+};
+use wasmparser::{DataKind, ElementItems, ElementKind, Parser, Payload, ValType};
+
+/// The contents of a Wasm module as printed by the `inspect` subcommand.
+///
+/// # Note
+///
+/// Imports and exports are queried from the compiled [`Module`] while
+/// all other contents are read from the Wasm binary since `wasmi` does
+/// not keep them around after compilation.
+pub struct ModuleInfo<'a> {
+    /// The compiled Wasm module.
+    module: &'a Module,
+    /// The internal linear memories of the Wasm module.
+    memories: Vec<MemoryType>,
+    /// The internal tables of the Wasm module.
+    tables: Vec<TableType>,
+    /// The internal global variables of the Wasm module.
+    globals: Vec<GlobalType>,
+    /// The custom sections of the Wasm module.
+    custom_sections: Vec<CustomSectionInfo>,
+    /// The data segments of the Wasm module.
+    data_segments: Vec<SegmentInfo>,
+    /// The element segments of the Wasm module.
+    element_segments: Vec<SegmentInfo>,
+    /// The disassembled bytecode of the internal functions if requested.
+    bytecode: Option<Vec<Disassembly>>,
+}
+
+/// The name and size of a custom section.
+struct CustomSectionInfo {
+    /// The name of the custom section.
+    name: String,
+    /// The size of the custom section payload in bytes.
+    len: usize,
+}
+
+/// The kind and size of a data or element segment.
+struct SegmentInfo {
+    /// The kind of the segment: `active`, `passive` or `declared`.
+    kind: &'static str,
+    /// The number of bytes or items of the segment.
+    len: usize,
+}
+
+impl<'a> ModuleInfo<'a> {
+    /// Collects the [`ModuleInfo`] of the compiled `module` from its `wasm` binary.
+    ///
+    /// # Errors
+    ///
+    /// If the `wasm` binary cannot be parsed.
+    pub fn new(module: &'a Module, wasm: &[u8]) -> Result<Self, Error> {
+        let mut info = Self {
+            module,
+            memories: Vec::new(),
+            tables: Vec::new(),
+            globals: Vec::new(),
+            custom_sections: Vec::new(),
+            data_segments: Vec::new(),
+            element_segments: Vec::new(),
+            bytecode: None,
+        };
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload? {
+                Payload::MemorySection(section) => {
+                    for memory in section {
+                        let memory = memory?;
+                        let initial = u32::try_from(memory.initial)?;
+                        let maximum = memory.maximum.map(u32::try_from).transpose()?;
+                        let memory_type = MemoryType::new(initial, maximum)
+                            .map_err(|error| anyhow!("invalid linear memory type: {error}"))?;
+                        info.memories.push(memory_type);
+                    }
+                }
+                Payload::TableSection(section) => {
+                    for table in section {
+                        let table = table?;
+                        let element = value_type(table.element_type)?;
+                        info.tables
+                            .push(TableType::new(element, table.initial, table.maximum));
+                    }
+                }
+                Payload::GlobalSection(section) => {
+                    for global in section {
+                        let global = global?.ty;
+                        let mutability = match global.mutable {
+                            true => Mutability::Var,
+                            false => Mutability::Const,
+                        };
+                        let content = value_type(global.content_type)?;
+                        info.globals.push(GlobalType::new(content, mutability));
+                    }
+                }
+                Payload::CustomSection(section) => {
+                    info.custom_sections.push(CustomSectionInfo {
+                        name: section.name().to_string(),
+                        len: section.data().len(),
+                    });
+                }
+                Payload::DataSection(section) => {
+                    for data in section {
+                        let data = data?;
+                        let kind = match data.kind {
+                            DataKind::Active { .. } => "active",
+                            DataKind::Passive => "passive",
+                        };
+                        info.data_segments.push(SegmentInfo {
+                            kind,
+                            len: data.data.len(),
+                        });
+                    }
+                }
+                Payload::ElementSection(section) => {
+                    for element in section {
+                        let element = element?;
+                        let kind = match element.kind {
+                            ElementKind::Active { .. } => "active",
+                            ElementKind::Passive => "passive",
+                            ElementKind::Declared => "declared",
+                        };
+                        let len = match element.items {
+                            ElementItems::Functions(items) => items.count(),
+                            ElementItems::Expressions(items) => items.count(),
+                        };
+                        info.element_segments.push(SegmentInfo {
+                            kind,
+                            len: len as usize,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// Disassembles the translated `wasmi` bytecode of all internal functions.
+    pub fn disassemble(&mut self) {
+        let len_imported = self.len_imported(|ty| matches!(ty, ExternType::Func(_)));
+        let engine = self.module.engine();
+        let bytecode = (len_imported..)
+            .map_while(|index| engine.disassemble(self.module, index as u32))
+            .collect();
+        self.bytecode = Some(bytecode);
+    }
+
+    /// Returns the number of imported items of the Wasm module that satisfy `filter`.
+    fn len_imported(&self, filter: fn(&ExternType) -> bool) -> usize {
+        self.module
+            .imports()
+            .filter(|import| filter(import.ty()))
+            .count()
+    }
+
+    /// Returns the [`ModuleInfo`] as JSON document.
+    pub fn to_json(&self) -> JsonValue {
+        let imports = self
+            .module
+            .imports()
+            .map(|import| {
+                json!({
+                    "module": import.module(),
+                    "name": import.name(),
+                    "type": extern_type_to_json(import.ty()),
+                })
+            })
+            .collect::<Vec<_>>();
+        let exports = self
+            .module
+            .exports()
+            .map(|export| {
+                json!({
+                    "name": export.name(),
+                    "type": extern_type_to_json(export.ty()),
+                })
+            })
+            .collect::<Vec<_>>();
+        let memories = self
+            .memories
+            .iter()
+            .map(|memory| extern_type_to_json(&ExternType::Memory(*memory)))
+            .collect::<Vec<_>>();
+        let tables = self
+            .tables
+            .iter()
+            .map(|table| extern_type_to_json(&ExternType::Table(*table)))
+            .collect::<Vec<_>>();
+        let globals = self
+            .globals
+            .iter()
+            .map(|global| extern_type_to_json(&ExternType::Global(*global)))
+            .collect::<Vec<_>>();
+        let custom_sections = self
+            .custom_sections
+            .iter()
+            .map(|section| json!({ "name": section.name, "size": section.len }))
+            .collect::<Vec<_>>();
+        let data_segments = self
+            .data_segments
+            .iter()
+            .map(|segment| json!({ "kind": segment.kind, "size": segment.len }))
+            .collect::<Vec<_>>();
+        let element_segments = self
+            .element_segments
+            .iter()
+            .map(|segment| json!({ "kind": segment.kind, "items": segment.len }))
+            .collect::<Vec<_>>();
+        json!({
+            "imports": imports,
+            "exports": exports,
+            "memories": memories,
+            "tables": tables,
+            "globals": globals,
+            "custom_sections": custom_sections,
+            "data_segments": data_segments,
+            "element_segments": element_segments,
+            "bytecode": self.bytecode.as_ref().map(|bytecode| {
+                bytecode
+                    .iter()
+                    .map(|disassembly| {
+                        json!({
+                            "func_index": disassembly.func_index(),
+                            "instrs": disassembly.instrs().collect::<Vec<_>>(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }),
+        })
+    }
+}
+
+impl Display for ModuleInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "imports:")?;
+        for import in self.module.imports() {
+            let ty = DisplayExternType::from(import.ty());
+            writeln!(f, " - {}::{}: {ty}", import.module(), import.name())?;
+        }
+        writeln!(f, "exports:")?;
+        for export in self.module.exports() {
+            match export.ty() {
+                ExternType::Func(func_type) => {
+                    writeln!(f, " - {}", DisplayFuncType::new(export.name(), func_type))?
+                }
+                ty => writeln!(f, " - {}: {}", export.name(), DisplayExternType::from(ty))?,
+            }
+        }
+        writeln!(f, "memories:")?;
+        let len_imported = self.len_imported(|ty| matches!(ty, ExternType::Memory(_)));
+        for (index, memory) in self.memories.iter().enumerate() {
+            let ty = ExternType::Memory(*memory);
+            let index = len_imported + index;
+            writeln!(f, " - [{index}] {}", DisplayExternType::from(&ty))?;
+        }
+        writeln!(f, "tables:")?;
+        let len_imported = self.len_imported(|ty| matches!(ty, ExternType::Table(_)));
+        for (index, table) in self.tables.iter().enumerate() {
+            let ty = ExternType::Table(*table);
+            let index = len_imported + index;
+            writeln!(f, " - [{index}] {}", DisplayExternType::from(&ty))?;
+        }
+        writeln!(f, "globals:")?;
+        let len_imported = self.len_imported(|ty| matches!(ty, ExternType::Global(_)));
+        for (index, global) in self.globals.iter().enumerate() {
+            let ty = ExternType::Global(*global);
+            let index = len_imported + index;
+            writeln!(f, " - [{index}] {}", DisplayExternType::from(&ty))?;
+        }
+        writeln!(f, "custom sections:")?;
+        for section in &self.custom_sections {
+            writeln!(f, " - {:?}: {} bytes", section.name, section.len)?;
+        }
+        writeln!(f, "data segments:")?;
+        for (index, segment) in self.data_segments.iter().enumerate() {
+            writeln!(f, " - [{index}] {}: {} bytes", segment.kind, segment.len)?;
+        }
+        writeln!(f, "element segments:")?;
+        for (index, segment) in self.element_segments.iter().enumerate() {
+            writeln!(f, " - [{index}] {}: {} items", segment.kind, segment.len)?;
+        }
+        if let Some(bytecode) = &self.bytecode {
+            writeln!(f, "bytecode:")?;
+            for disassembly in bytecode {
+                write!(f, "{disassembly}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts the [`ValType`] of `wasmparser` into a [`ValueType`].
+///
+/// # Errors
+///
+/// If the [`ValType`] is not supported by `wasmi`.
+fn value_type(value_type: ValType) -> Result<ValueType, Error> {
+    let value_type = match value_type {
+        ValType::I32 => ValueType::I32,
+        ValType::I64 => ValueType::I64,
+        ValType::F32 => ValueType::F32,
+        ValType::F64 => ValueType::F64,
+        ValType::FuncRef => ValueType::FuncRef,
+        ValType::ExternRef => ValueType::ExternRef,
+        ValType::V128 => bail!("the Wasm `simd` proposal is not supported by wasmi"),
+    };
+    Ok(value_type)
+}
+
+/// Returns the name of the [`ValueType`] as JSON string.
+fn value_type_to_json(value_type: &ValueType) -> JsonValue {
+    JsonValue::from(DisplayValueType::from(value_type).to_string())
+}
+
+/// Returns the [`ExternType`] as JSON document.
+fn extern_type_to_json(extern_type: &ExternType) -> JsonValue {
+    match extern_type {
+        ExternType::Func(func_type) => json!({
+            "kind": "func",
+            "params": func_type.params().iter().map(value_type_to_json).collect::<Vec<_>>(),
+            "results": func_type.results().iter().map(value_type_to_json).collect::<Vec<_>>(),
+        }),
+        ExternType::Global(global_type) => json!({
+            "kind": "global",
+            "content": value_type_to_json(&global_type.content()),
+            "mutable": global_type.mutability().is_mut(),
+        }),
+        ExternType::Memory(memory_type) => json!({
+            "kind": "memory",
+            "initial_pages": u32::from(memory_type.initial_pages()),
+            "maximum_pages": memory_type.maximum_pages().map(u32::from),
+        }),
+        ExternType::Table(table_type) => json!({
+            "kind": "table",
+            "element": value_type_to_json(&table_type.element()),
+            "minimum": table_type.minimum(),
+            "maximum": table_type.maximum(),
+        }),
+    }
+}
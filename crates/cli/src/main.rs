@@ -1,45 +1,113 @@
 use crate::{
-    args::Args,
+    args::{Args, Command, InspectArgs, ModuleArgs, OutputFormat, ReplArgs, RunArgs, WastArgs},
     display::{DisplayExportedFuncs, DisplayFuncType, DisplaySequence, DisplayValue},
+    inspect::ModuleInfo,
+    profile::FuncNames,
+    repl::Repl,
+    report::{ExecutionReport, SetupErrorReport},
 };
 use anyhow::{anyhow, bail, Error, Result};
 use clap::Parser;
 use context::Context;
-use std::{path::Path, process};
-use wasmi::{Func, FuncType, Value};
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+    process,
+    time::Instant,
+};
+use wasmi::{Engine, Func, FuncType, HostCallLog, Module, Value};
+use wasmi_wast::{DirectiveOutcome, TestDescriptor, TestProfile};
 
 mod args;
 mod context;
 mod display;
+mod inspect;
+mod profile;
+mod repl;
+mod report;
 mod utils;
 
 #[cfg(test)]
 mod tests;
 
 fn main() -> Result<()> {
-    let args = Args::parse();
-    let wasm_file = args.wasm_file();
-    let wasi_ctx = args.wasi_context()?;
-    let mut ctx = Context::new(wasm_file, wasi_ctx, args.fuel())?;
-    let (func_name, func) = get_invoked_func(&args, &ctx)?;
-    let ty = func.ty(ctx.store());
-    let func_args = utils::decode_func_args(&ty, args.func_args())?;
-    let mut func_results = utils::prepare_func_results(&ty);
-    typecheck_args(&func_name, &ty, &func_args)?;
+    match Args::parse().into_command() {
+        Command::Run(args) => run(&args),
+        Command::Inspect(args) => inspect(&args),
+        Command::Validate(args) => validate(&args),
+        Command::Compile(args) => compile(&args),
+        Command::Wast(args) => run_wast_scripts(&args),
+        Command::Repl(args) => repl(&args),
+    }
+}
 
-    print_execution_start(args.wasm_file(), &func_name, &func_args);
-    if args.invoked().is_some() && ty.params().len() != args.func_args().len() {
-        bail!(
-            "invalid amount of arguments given to function {}. expected {} but received {}",
-            DisplayFuncType::new(&func_name, &ty),
-            ty.params().len(),
-            args.func_args().len()
-        )
+/// Runs the invoked function of the Wasm module given to the `run` subcommand.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be instantiated or the function execution failed.
+fn run(args: &RunArgs) -> Result<()> {
+    let wasm_file = args.wasm_file();
+    let invocation = match prepare_invocation(args) {
+        Ok(invocation) => invocation,
+        Err(error) if args.output() == OutputFormat::Json => {
+            let report = SetupErrorReport::new(wasm_file, args.invoked(), &error);
+            println!("{:#}", report.to_json());
+            return Err(error);
+        }
+        Err(error) => return Err(error),
+    };
+    let Invocation {
+        mut ctx,
+        func_name,
+        func,
+        func_args,
+        mut func_results,
+    } = invocation;
+    if args.output() == OutputFormat::Json {
+        let started = Instant::now();
+        let outcome = func.call(ctx.store_mut(), &func_args, &mut func_results);
+        let wall_time = started.elapsed();
+        finish_host_calls(args, &mut ctx, outcome.is_ok())?;
+        write_profile(args, &ctx)?;
+        let fuel_report = func_fuel_report(args, &ctx)?;
+        let mut report = ExecutionReport::new(
+            wasm_file,
+            &func_name,
+            &func_args,
+            &func_results,
+            &outcome,
+            wall_time,
+        );
+        if let Some(total_fuel) = args.fuel() {
+            let consumed = consumed_fuel(&ctx);
+            report = report.with_fuel(consumed, total_fuel - consumed);
+        }
+        if let Some(fuel_report) = &fuel_report {
+            report = report.with_fuel_report(fuel_report);
+        }
+        println!("{:#}", report.to_json());
+        if let Err(error) = outcome {
+            if let wasmi::Error::Trap(trap) = &error {
+                if let Some(exit_code) = trap.i32_exit_status() {
+                    process::exit(exit_code)
+                }
+            }
+            bail!("failed during execution of {func_name}: {error}")
+        }
+        return Ok(());
     }
 
-    match func.call(ctx.store_mut(), &func_args, &mut func_results) {
+    print_execution_start(args.wasm_file(), &func_name, &func_args);
+    let outcome = func.call(ctx.store_mut(), &func_args, &mut func_results);
+    finish_host_calls(args, &mut ctx, outcome.is_ok())?;
+    write_profile(args, &ctx)?;
+    let fuel_report = func_fuel_report(args, &ctx)?;
+    match outcome {
         Ok(()) => {
-            print_remaining_fuel(&args, &ctx);
+            print_remaining_fuel(args, &ctx);
+            print_fuel_report(fuel_report.as_deref());
             print_pretty_results(&func_results);
             Ok(())
         }
@@ -49,7 +117,8 @@ fn main() -> Result<()> {
                     // We received an exit code from the WASI program,
                     // therefore we exit with the same exit code after
                     // pretty printing the results.
-                    print_remaining_fuel(&args, &ctx);
+                    print_remaining_fuel(args, &ctx);
+                    print_fuel_report(fuel_report.as_deref());
                     print_pretty_results(&func_results);
                     process::exit(exit_code)
                 }
@@ -59,12 +128,312 @@ fn main() -> Result<()> {
     }
 }
 
+/// The invoked function of the `run` subcommand ready to be called.
+struct Invocation {
+    /// The context holding the instantiated Wasm module.
+    ctx: Context,
+    /// The name of the invoked function.
+    func_name: String,
+    /// The invoked function.
+    func: Func,
+    /// The decoded arguments of the invoked function.
+    func_args: Box<[Value]>,
+    /// The buffer for the results of the invoked function.
+    func_results: Box<[Value]>,
+}
+
+/// Instantiates the Wasm module given to the `run` subcommand and prepares the function invocation.
+///
+/// # Errors
+///
+/// - If the Wasm module cannot be compiled, linked or instantiated.
+/// - If the invoked function does not exist or the given arguments do not match its type.
+/// - If the recording or replaying of host calls cannot be started.
+fn prepare_invocation(args: &RunArgs) -> Result<Invocation> {
+    let wasi_ctx = args.wasi_context()?;
+    let config = args.config()?;
+    let mut ctx = Context::new(
+        args.wasm_file(),
+        args.preloads(),
+        &config,
+        wasi_ctx,
+        args.fuel(),
+    )?;
+    let (func_name, func) = get_invoked_func(args, &ctx)?;
+    let ty = func.ty(ctx.store());
+    let func_args = utils::decode_func_args(&ty, args.func_args())?;
+    let func_results = utils::prepare_func_results(&ty);
+    typecheck_args(&func_name, &ty, &func_args)?;
+
+    if args.invoked().is_some() && ty.params().len() != args.func_args().len() {
+        bail!(
+            "invalid amount of arguments given to function {}. expected {} but received {}",
+            DisplayFuncType::new(&func_name, &ty),
+            ty.params().len(),
+            args.func_args().len()
+        )
+    }
+
+    start_host_calls(args, &mut ctx)?;
+    Ok(Invocation {
+        ctx,
+        func_name,
+        func,
+        func_args,
+        func_results,
+    })
+}
+
+/// Starts an interactive session on the Wasm module given to the `repl` subcommand.
+///
+/// # Errors
+///
+/// - If the Wasm module cannot be instantiated.
+/// - If reading from standard input or writing to standard output failed.
+fn repl(args: &ReplArgs) -> Result<()> {
+    let wasi_ctx = args.wasi_context()?;
+    let config = args.config()?;
+    let ctx = Context::new(
+        args.wasm_file(),
+        args.preloads(),
+        &config,
+        wasi_ctx,
+        args.fuel(),
+    )?;
+    let mut repl = Repl::new(ctx);
+    repl.run(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Prints the contents of the Wasm module given to the `inspect` subcommand.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read, parsed or validated.
+fn inspect(args: &InspectArgs) -> Result<()> {
+    let wasm_file = args.wasm_file();
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    let engine = Engine::new(&args.config()?);
+    let module = Module::new(&engine, &wasm_bytes[..]).map_err(|error| {
+        anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}")
+    })?;
+    let mut info = ModuleInfo::new(&module, &wasm_bytes)?;
+    if args.bytecode() {
+        info.disassemble();
+    }
+    match args.output() {
+        OutputFormat::Human => print!("{info}"),
+        OutputFormat::Json => println!("{:#}", info.to_json()),
+    }
+    Ok(())
+}
+
+/// Validates the Wasm module given to the `validate` subcommand.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read or is invalid.
+fn validate(args: &ModuleArgs) -> Result<()> {
+    let wasm_file = args.wasm_file();
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    let engine = Engine::new(&args.config()?);
+    Module::validate(&engine, &wasm_bytes)
+        .map_err(|error| anyhow!("failed to validate Wasm module {wasm_file:?}: {error}"))?;
+    println!("{wasm_file:?} is a valid Wasm module");
+    Ok(())
+}
+
+/// Compiles the Wasm module given to the `compile` subcommand to `wasmi` bytecode.
+///
+/// Prints the time it took to compile the Wasm module which includes its validation
+/// but excludes reading and parsing the Wasm file. The compiled bytecode is discarded
+/// since the `compile` subcommand serves as validation and compilation benchmark only.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read, parsed, validated or compiled.
+fn compile(args: &ModuleArgs) -> Result<()> {
+    let wasm_file = args.wasm_file();
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    let engine = Engine::new(&args.config()?);
+    let started = Instant::now();
+    Module::new(&engine, &wasm_bytes[..])
+        .map_err(|error| anyhow!("failed to compile Wasm module {wasm_file:?}: {error}"))?;
+    let elapsed = started.elapsed();
+    println!("compiled {wasm_file:?} in {elapsed:?}");
+    Ok(())
+}
+
+/// Runs all `.wast` script files given to the `wast` subcommand.
+///
+/// Prints the outcome of every executed directive followed by a summary profile.
+///
+/// # Errors
+///
+/// If any `.wast` script file could not be run or any of its directives failed.
+fn run_wast_scripts(args: &WastArgs) -> Result<()> {
+    let config = args.config()?;
+    let mut summary = TestProfile::default();
+    let mut broken_files = 0;
+    for path in args.files() {
+        let report = TestDescriptor::new(path).and_then(|test| wasmi_wast::run_wast(&test, config));
+        let report = match report {
+            Ok(report) => report,
+            Err(error) => {
+                println!("FAIL {}: {error}", path.display());
+                broken_files += 1;
+                continue;
+            }
+        };
+        for directive in report.directives() {
+            let location = directive.location();
+            let kind = directive.kind();
+            match directive.outcome() {
+                DirectiveOutcome::Passed => println!("PASS {location} {kind}"),
+                DirectiveOutcome::Skipped => println!("SKIP {location} {kind}"),
+                DirectiveOutcome::Failed(error) => println!("FAIL {location} {kind}: {error}"),
+            }
+        }
+        summary.merge(report.profile());
+    }
+    println!();
+    print!("{summary}");
+    if broken_files != 0 {
+        bail!(
+            "{broken_files} of {} `.wast` files could not be run",
+            args.files().len()
+        )
+    }
+    if summary.failed() != 0 {
+        bail!(
+            "{} of {} `.wast` directives failed",
+            summary.failed(),
+            summary.directives()
+        )
+    }
+    Ok(())
+}
+
+/// Starts recording or replaying host calls if `--record-host-calls` or `--replay-host-calls` was given.
+///
+/// # Errors
+///
+/// If the host calls to replay cannot be read or parsed.
+fn start_host_calls(args: &RunArgs, ctx: &mut Context) -> Result<()> {
+    if args.record_host_calls().is_some() {
+        ctx.store_mut().record_host_calls();
+    }
+    if let Some(replay_file) = args.replay_host_calls() {
+        let log = fs::read_to_string(replay_file)
+            .map_err(|error| anyhow!("failed to read host calls file {replay_file:?}: {error}"))?
+            .parse::<HostCallLog>()
+            .map_err(|error| anyhow!("failed to parse host calls file {replay_file:?}: {error}"))?;
+        ctx.store_mut().replay_host_calls(log);
+    }
+    Ok(())
+}
+
+/// Finishes recording or replaying host calls.
+///
+/// Writes the recorded host calls to the file given in `--record-host-calls` if any.
+///
+/// # Errors
+///
+/// - If the recorded host calls cannot be written.
+/// - If the execution `succeeded` without replaying all host calls given in `--replay-host-calls`.
+fn finish_host_calls(args: &RunArgs, ctx: &mut Context, succeeded: bool) -> Result<()> {
+    let log = match ctx.store_mut().finish_host_calls() {
+        Ok(log) => log,
+        // A failed execution reports its own error which likely caused the missing host calls.
+        Err(_) if !succeeded => None,
+        Err(error) => bail!("failed to replay host calls: {error}"),
+    };
+    if let (Some(record_file), Some(log)) = (args.record_host_calls(), log) {
+        fs::write(record_file, log.to_string())
+            .map_err(|error| anyhow!("failed to write host calls file {record_file:?}: {error}"))?;
+    }
+    Ok(())
+}
+
+/// Writes the profile of all executions so far to the file given in `--profile` if any.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read again or the profile cannot be written.
+fn write_profile(args: &RunArgs, ctx: &Context) -> Result<()> {
+    let Some(profile_file) = args.profile() else {
+        return Ok(());
+    };
+    let profile = ctx
+        .store()
+        .profile()
+        .unwrap_or_else(|| panic!("profiling is enabled but could not query the profile"));
+    let wasm_bytes = utils::read_wasm_or_wat(args.wasm_file())?;
+    let names = FuncNames::new(&wasm_bytes).map_err(|error| {
+        anyhow!("failed to read the function names of the Wasm module: {error}")
+    })?;
+    let file = File::create(profile_file)
+        .map_err(|error| anyhow!("failed to create profile file {profile_file:?}: {error}"))?;
+    profile::write_collapsed_stacks(profile, ctx.module(), &names, io::BufWriter::new(file))
+}
+
+/// Returns the names of the Wasm functions and the fuel they consumed if `--fuel-report` was given.
+///
+/// The functions are ordered by their consumed fuel starting with the function that consumed the most.
+/// Functions of other Wasm modules, such as preloaded ones, are named `<external>`.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read again.
+fn func_fuel_report(args: &RunArgs, ctx: &Context) -> Result<Option<Vec<(String, u64)>>> {
+    if !args.fuel_report() {
+        return Ok(None);
+    }
+    let fuel_report = ctx.store().fuel_report().unwrap_or_else(|| {
+        panic!("fuel accounting is enabled but could not query the fuel report")
+    });
+    let wasm_bytes = utils::read_wasm_or_wat(args.wasm_file())?;
+    let names = FuncNames::new(&wasm_bytes).map_err(|error| {
+        anyhow!("failed to read the function names of the Wasm module: {error}")
+    })?;
+    let funcs = fuel_report
+        .funcs()
+        .iter()
+        .map(|func| {
+            let name = match func.func_index(ctx.module()) {
+                Some(func_index) => names.get(func_index),
+                None => String::from("<external>"),
+            };
+            (name, func.consumed())
+        })
+        .collect();
+    Ok(Some(funcs))
+}
+
+/// Prints the fuel consumed per Wasm function if `--fuel-report` was given.
+fn print_fuel_report(fuel_report: Option<&[(String, u64)]>) {
+    if let Some(fuel_report) = fuel_report {
+        println!("fuel report:");
+        for (name, consumed) in fuel_report {
+            println!(" - {name}: {consumed}");
+        }
+    }
+}
+
+/// Returns the fuel consumed so far.
+///
+/// # Panics
+///
+/// If fuel metering is not enabled.
+fn consumed_fuel(ctx: &Context) -> u64 {
+    ctx.store()
+        .fuel_consumed()
+        .unwrap_or_else(|| panic!("fuel metering is enabled but could not query consumed fuel"))
+}
+
 /// Prints the remaining fuel so far if fuel metering was enabled.
-fn print_remaining_fuel(args: &Args, ctx: &Context) {
+fn print_remaining_fuel(args: &RunArgs, ctx: &Context) {
     if let Some(total_fuel) = args.fuel() {
-        let consumed = ctx.store().fuel_consumed().unwrap_or_else(|| {
-            panic!("fuel metering is enabled but could not query consumed fuel")
-        });
+        let consumed = consumed_fuel(ctx);
         let remaining = total_fuel - consumed;
         println!("fuel consumed: {consumed}, fuel remaining: {remaining}");
     }
@@ -97,7 +466,7 @@ fn typecheck_args(func_name: &str, func_ty: &FuncType, args: &[Value]) -> Result
 ///
 /// - If the function given via `--invoke` could not be found in the Wasm module.
 /// - If `--invoke` was not given and no WASI entry points were exported.
-fn get_invoked_func(args: &Args, ctx: &Context) -> Result<(String, Func), Error> {
+fn get_invoked_func(args: &RunArgs, ctx: &Context) -> Result<(String, Func), Error> {
     match args.invoked() {
         Some(func_name) => {
             let func = ctx
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Error};
+use std::{collections::BTreeMap, io::Write};
+use wasmi::{Module, Profile, ProfiledFunc};
+use wasmparser::{Name, NameSectionReader, Parser, Payload};
+
+/// The function names of a Wasm module as found in its `name` custom section.
+#[derive(Debug, Default)]
+pub struct FuncNames {
+    /// The names of all named functions by their function index.
+    names: BTreeMap<u32, String>,
+}
+
+impl FuncNames {
+    /// Reads the function names from the `name` custom section of the `wasm_bytes`.
+    ///
+    /// Returns no names if the Wasm module has no `name` custom section.
+    ///
+    /// # Errors
+    ///
+    /// If the Wasm module or its `name` custom section are malformed.
+    pub fn new(wasm_bytes: &[u8]) -> Result<Self, Error> {
+        let mut names = BTreeMap::new();
+        for payload in Parser::new(0).parse_all(wasm_bytes) {
+            let Payload::CustomSection(section) = payload? else {
+                continue;
+            };
+            if section.name() != "name" {
+                continue;
+            }
+            for name in NameSectionReader::new(section.data(), section.data_offset()) {
+                let Name::Function(func_names) = name? else {
+                    continue;
+                };
+                for naming in func_names {
+                    let naming = naming?;
+                    names.insert(naming.index, naming.name.to_string());
+                }
+            }
+        }
+        Ok(Self { names })
+    }
+
+    /// Returns the name of the function at `func_index`.
+    ///
+    /// Falls back to `func[N]` for functions without a name.
+    pub fn get(&self, func_index: u32) -> String {
+        match self.names.get(&func_index) {
+            Some(name) => name.clone(),
+            None => format!("func[{func_index}]"),
+        }
+    }
+}
+
+/// Writes the call stacks of the `profile` in the collapsed stack format to `output`.
+///
+/// Every line holds the `;` separated function names of a call stack starting with
+/// the outermost caller followed by the number of instructions executed by the innermost
+/// function with this exact call stack. This is the input format expected by flamegraph tools.
+///
+/// Functions are named after the `names` of the `module`. Functions of other Wasm modules,
+/// such as preloaded ones, are named `<external>`.
+///
+/// # Errors
+///
+/// If writing to `output` failed.
+pub fn write_collapsed_stacks(
+    profile: &Profile,
+    module: &Module,
+    names: &FuncNames,
+    mut output: impl Write,
+) -> Result<(), Error> {
+    let func_name = |func: &ProfiledFunc| match func.func_index(module) {
+        // Collapsed stacks use `;` to separate frames and a space before the count.
+        Some(func_index) => names.get(func_index).replace([';', ' '], "_"),
+        None => String::from("<external>"),
+    };
+    for (stack, instrs) in profile.stacks() {
+        let stack = stack.iter().map(func_name).collect::<Vec<_>>().join(";");
+        writeln!(output, "{stack} {instrs}")
+            .map_err(|error| anyhow!("failed to write profile: {error}"))?;
+    }
+    output
+        .flush()
+        .map_err(|error| anyhow!("failed to write profile: {error}"))
+}
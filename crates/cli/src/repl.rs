@@ -0,0 +1,255 @@
+use crate::{
+    context::Context,
+    display::{DisplayExportedFuncs, DisplayFuncType, DisplaySequence, DisplayValue},
+    utils,
+};
+use anyhow::{anyhow, bail, Error};
+use std::io::{BufRead, Write};
+use wasmi::{Global, Memory, Value};
+
+/// The help text printed by the `help` command of the REPL.
+const HELP: &str = "\
+commands:
+  call FUNC [ARGS...]          calls the exported function FUNC with ARGS
+  exports                      lists the exported functions
+  read MEMORY OFFSET LEN       prints LEN bytes of the exported memory at OFFSET as hex
+  write MEMORY OFFSET HEX      writes the HEX encoded bytes to the exported memory at OFFSET
+  get GLOBAL                   prints the value of the exported global variable
+  set GLOBAL VALUE             sets the value of the exported mutable global variable
+  fuel                         prints the consumed fuel if fuel metering is enabled
+  help                         prints this help text
+  exit                         exits the REPL";
+
+/// An interactive session operating on a single instantiated Wasm module.
+///
+/// The [`Store`] of the [`Context`] is kept alive between commands so that
+/// state changes of one command are visible to all subsequent commands.
+///
+/// [`Store`]: wasmi::Store
+pub struct Repl {
+    /// The context holding the instantiated Wasm module.
+    ctx: Context,
+}
+
+/// Tells the REPL whether to continue after executing a command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReplFlow {
+    /// Continue reading commands.
+    Continue,
+    /// Exit the REPL.
+    Exit,
+}
+
+impl Repl {
+    /// Creates a new [`Repl`] operating on the [`Context`].
+    pub fn new(ctx: Context) -> Self {
+        Self { ctx }
+    }
+
+    /// Reads commands line by line from `input` and executes them until `exit` or end of input.
+    ///
+    /// Errors of individual commands are printed and do not end the session.
+    ///
+    /// # Errors
+    ///
+    /// If reading from `input` or writing to `output` failed.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<(), Error> {
+        write!(output, "> ")?;
+        output.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?, &mut output) {
+                Ok(ReplFlow::Exit) => return Ok(()),
+                Ok(ReplFlow::Continue) => {}
+                Err(error) => writeln!(output, "error: {error}")?,
+            }
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        writeln!(output)?;
+        Ok(())
+    }
+
+    /// Executes a single REPL command `line` and writes its results to `output`.
+    ///
+    /// # Errors
+    ///
+    /// If the command is unknown, malformed or failed to execute.
+    pub fn execute(&mut self, line: &str, mut output: impl Write) -> Result<ReplFlow, Error> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(ReplFlow::Continue);
+        };
+        let args = words.collect::<Vec<_>>();
+        match (command, &args[..]) {
+            ("call", [func_name, func_args @ ..]) => self.call(func_name, func_args, output)?,
+            ("exports", []) => write!(output, "{}", DisplayExportedFuncs::from(&self.ctx))?,
+            ("read", [memory, offset, len]) => self.read_memory(memory, offset, len, output)?,
+            ("write", [memory, offset, bytes]) => self.write_memory(memory, offset, bytes)?,
+            ("get", [global]) => {
+                let value = self.get_global(global)?.get(self.ctx.store());
+                writeln!(output, "{}", display_value(&value))?
+            }
+            ("set", [global, value]) => self.set_global(global, value)?,
+            ("fuel", []) => match self.ctx.store().fuel_consumed() {
+                Some(consumed) => writeln!(output, "fuel consumed: {consumed}")?,
+                None => bail!("fuel metering is not enabled, use `--fuel` to enable it"),
+            },
+            ("help", []) => writeln!(output, "{HELP}")?,
+            ("exit" | "quit", []) => return Ok(ReplFlow::Exit),
+            (
+                "call" | "exports" | "read" | "write" | "get" | "set" | "fuel" | "help" | "exit"
+                | "quit",
+                _,
+            ) => bail!("invalid arguments for `{command}`, see `help` for its usage"),
+            _ => bail!("unknown command `{command}`, see `help` for all commands"),
+        }
+        Ok(ReplFlow::Continue)
+    }
+
+    /// Calls the exported function `func_name` with the given `func_args`.
+    ///
+    /// Writes the results and the fuel consumed by the call if fuel metering is enabled.
+    fn call(
+        &mut self,
+        func_name: &str,
+        func_args: &[&str],
+        mut output: impl Write,
+    ) -> Result<(), Error> {
+        let func = self
+            .ctx
+            .get_func(func_name)
+            .map_err(|error| anyhow!("{error}\n\n{}", DisplayExportedFuncs::from(&self.ctx)))?;
+        let ty = func.ty(self.ctx.store());
+        if ty.params().len() != func_args.len() {
+            bail!(
+                "invalid amount of arguments given to function {}. expected {} but received {}",
+                DisplayFuncType::new(func_name, &ty),
+                ty.params().len(),
+                func_args.len()
+            )
+        }
+        let func_args = func_args
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+        let func_args = utils::decode_func_args(&ty, &func_args)?;
+        let mut func_results = utils::prepare_func_results(&ty);
+        let fuel_before = self.ctx.store().fuel_consumed();
+        func.call(self.ctx.store_mut(), &func_args, &mut func_results)
+            .map_err(|error| anyhow!("failed during execution of {func_name}: {error}"))?;
+        if !func_results.is_empty() {
+            writeln!(
+                output,
+                "{}",
+                DisplaySequence::new(", ", func_results.iter().map(display_value))
+            )?;
+        }
+        if let (Some(before), Some(after)) = (fuel_before, self.ctx.store().fuel_consumed()) {
+            writeln!(output, "fuel consumed: {}", after - before)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `len` bytes of the exported `memory` at `offset` as hex to `output`.
+    fn read_memory(
+        &self,
+        memory: &str,
+        offset: &str,
+        len: &str,
+        mut output: impl Write,
+    ) -> Result<(), Error> {
+        let memory = self.get_memory(memory)?;
+        let offset = parse_usize("offset", offset)?;
+        let len = parse_usize("length", len)?;
+        self.check_bounds(&memory, offset, len)?;
+        let mut buffer = vec![0x00_u8; len];
+        memory
+            .read(self.ctx.store(), offset, &mut buffer)
+            .map_err(|error| anyhow!("failed to read memory: {error}"))?;
+        let hex = buffer.iter().map(|byte| format!("{byte:02x}"));
+        writeln!(output, "{}", DisplaySequence::new(" ", hex))?;
+        Ok(())
+    }
+
+    /// Writes the hex encoded `bytes` to the exported `memory` at `offset`.
+    fn write_memory(&mut self, memory: &str, offset: &str, bytes: &str) -> Result<(), Error> {
+        let memory = self.get_memory(memory)?;
+        let offset = parse_usize("offset", offset)?;
+        let bytes = decode_hex(bytes)?;
+        self.check_bounds(&memory, offset, bytes.len())?;
+        memory
+            .write(self.ctx.store_mut(), offset, &bytes)
+            .map_err(|error| anyhow!("failed to write memory: {error}"))
+    }
+
+    /// Returns an error if `len` bytes at `offset` are out of bounds of the `memory`.
+    ///
+    /// This is checked before allocating any buffers for the accessed bytes.
+    fn check_bounds(&self, memory: &Memory, offset: usize, len: usize) -> Result<(), Error> {
+        let size = memory.data(self.ctx.store()).len();
+        match offset.checked_add(len) {
+            Some(end) if end <= size => Ok(()),
+            _ => bail!(
+                "out of bounds memory access: {len} bytes at offset {offset} of memory with {size} bytes"
+            ),
+        }
+    }
+
+    /// Sets the exported `global` variable to the decoded `value`.
+    fn set_global(&mut self, global: &str, value: &str) -> Result<(), Error> {
+        let global = self.get_global(global)?;
+        let content = global.ty(self.ctx.store()).content();
+        let value = utils::decode_value(&content, value)?;
+        global
+            .set(self.ctx.store_mut(), value)
+            .map_err(|error| anyhow!("failed to set global variable: {error}"))
+    }
+
+    /// Returns the exported [`Memory`] named `name`.
+    fn get_memory(&self, name: &str) -> Result<Memory, Error> {
+        self.ctx
+            .get_memory(name)
+            .ok_or_else(|| anyhow!("failed to find memory named {name:?} in the Wasm module"))
+    }
+
+    /// Returns the exported [`Global`] named `name`.
+    fn get_global(&self, name: &str) -> Result<Global, Error> {
+        self.ctx
+            .get_global(name)
+            .ok_or_else(|| anyhow!("failed to find global named {name:?} in the Wasm module"))
+    }
+}
+
+/// Renders the [`Value`] for the REPL.
+///
+/// Unlike [`DisplayValue`] this does not panic on reference types.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::FuncRef(func) if func.is_null() => "funcref null".into(),
+        Value::FuncRef(_) => "funcref".into(),
+        Value::ExternRef(extern_ref) if extern_ref.is_null() => "externref null".into(),
+        Value::ExternRef(_) => "externref".into(),
+        value => DisplayValue::from(value).to_string(),
+    }
+}
+
+/// Parses the REPL argument `arg` named `what` as `usize`.
+fn parse_usize(what: &str, arg: &str) -> Result<usize, Error> {
+    arg.parse::<usize>()
+        .map_err(|error| anyhow!("failed to parse {what} {arg:?}: {error}"))
+}
+
+/// Decodes the hex encoded `hex` string into bytes.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        bail!("hex encoded bytes must have an even number of digits but found {hex:?}")
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|start| {
+            hex.get(start..start + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| anyhow!("invalid hex encoded bytes: {hex:?}"))
+        })
+        .collect()
+}
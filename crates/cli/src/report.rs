@@ -0,0 +1,187 @@
+use crate::display::DisplayValueType;
+use serde_json::{json, Value as JsonValue};
+use std::{path::Path, time::Duration};
+use wasmi::Value;
+
+/// The outcome of a function execution as printed by `run --output json`.
+pub struct ExecutionReport<'a> {
+    /// The executed Wasm module.
+    wasm_file: &'a Path,
+    /// The name of the invoked function.
+    func_name: &'a str,
+    /// The arguments given to the invoked function.
+    func_args: &'a [Value],
+    /// The results of the invoked function if the execution succeeded.
+    func_results: Option<&'a [Value]>,
+    /// The error of the execution if any.
+    error: Option<&'a wasmi::Error>,
+    /// The consumed and remaining fuel if fuel metering was enabled.
+    fuel: Option<(u64, u64)>,
+    /// The fuel consumed per Wasm function if `--fuel-report` was given.
+    fuel_report: Option<&'a [(String, u64)]>,
+    /// The time it took to execute the invoked function.
+    wall_time: Duration,
+}
+
+impl<'a> ExecutionReport<'a> {
+    /// Creates a new [`ExecutionReport`] for the execution of `func_name` with `func_args`.
+    ///
+    /// The `func_results` are only reported if `outcome` is `Ok`.
+    pub fn new(
+        wasm_file: &'a Path,
+        func_name: &'a str,
+        func_args: &'a [Value],
+        func_results: &'a [Value],
+        outcome: &'a Result<(), wasmi::Error>,
+        wall_time: Duration,
+    ) -> Self {
+        let (func_results, error) = match outcome {
+            Ok(()) => (Some(func_results), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            wasm_file,
+            func_name,
+            func_args,
+            func_results,
+            error,
+            fuel: None,
+            fuel_report: None,
+            wall_time,
+        }
+    }
+
+    /// Reports the `consumed` and `remaining` fuel of the execution.
+    pub fn with_fuel(mut self, consumed: u64, remaining: u64) -> Self {
+        self.fuel = Some((consumed, remaining));
+        self
+    }
+
+    /// Reports the fuel consumed by every Wasm function of the execution.
+    pub fn with_fuel_report(mut self, fuel_report: &'a [(String, u64)]) -> Self {
+        self.fuel_report = Some(fuel_report);
+        self
+    }
+
+    /// Returns the [`ExecutionReport`] as JSON document.
+    pub fn to_json(&self) -> JsonValue {
+        let error = self.error.map(|error| {
+            let trap = match error {
+                wasmi::Error::Trap(trap) => Some(trap),
+                _ => None,
+            };
+            json!({
+                "message": error.to_string(),
+                "trap_code": trap
+                    .and_then(|trap| trap.trap_code())
+                    .map(|code| format!("{code:?}")),
+                "exit_status": trap.and_then(|trap| trap.i32_exit_status()),
+            })
+        });
+        let fuel = self.fuel.map(|(consumed, remaining)| {
+            let mut fuel = json!({ "consumed": consumed, "remaining": remaining });
+            if let Some(fuel_report) = self.fuel_report {
+                fuel["functions"] = fuel_report
+                    .iter()
+                    .map(|(name, consumed)| json!({ "name": name, "consumed": consumed }))
+                    .collect();
+            }
+            fuel
+        });
+        json!({
+            "module": self.wasm_file.display().to_string(),
+            "function": self.func_name,
+            "args": self.func_args.iter().map(value_to_json).collect::<Vec<_>>(),
+            "results": self
+                .func_results
+                .map(|results| results.iter().map(value_to_json).collect::<Vec<_>>()),
+            "error": error,
+            "fuel": fuel,
+            "wall_time_ns": u64::try_from(self.wall_time.as_nanos()).unwrap_or(u64::MAX),
+        })
+    }
+}
+
+/// The failure to prepare a function execution as printed by `run --output json`.
+///
+/// # Note
+///
+/// This uses the same JSON layout as the [`ExecutionReport`] so that consumers
+/// only need to check its `error` field. Fields that are unknown since the
+/// function was never called are `null`.
+pub struct SetupErrorReport<'a> {
+    /// The Wasm module that failed to be prepared.
+    wasm_file: &'a Path,
+    /// The name of the function given via `--invoke` if any.
+    func_name: Option<&'a str>,
+    /// The error that occurred while compiling, linking or instantiating the Wasm module.
+    error: &'a anyhow::Error,
+}
+
+impl<'a> SetupErrorReport<'a> {
+    /// Creates a new [`SetupErrorReport`] for the `error` that occurred before calling `func_name`.
+    pub fn new(wasm_file: &'a Path, func_name: Option<&'a str>, error: &'a anyhow::Error) -> Self {
+        Self {
+            wasm_file,
+            func_name,
+            error,
+        }
+    }
+
+    /// Returns the [`SetupErrorReport`] as JSON document.
+    pub fn to_json(&self) -> JsonValue {
+        let trap = self
+            .error
+            .chain()
+            .find_map(|error| error.downcast_ref::<wasmi::Error>())
+            .and_then(|error| match error {
+                wasmi::Error::Trap(trap) => Some(trap),
+                _ => None,
+            });
+        json!({
+            "module": self.wasm_file.display().to_string(),
+            "function": self.func_name,
+            "args": JsonValue::Null,
+            "results": JsonValue::Null,
+            "error": {
+                "message": format!("{:#}", self.error),
+                "trap_code": trap
+                    .and_then(|trap| trap.trap_code())
+                    .map(|code| format!("{code:?}")),
+                "exit_status": trap.and_then(|trap| trap.i32_exit_status()),
+            },
+            "fuel": JsonValue::Null,
+            "wall_time_ns": JsonValue::Null,
+        })
+    }
+}
+
+/// Returns the [`Value`] as JSON document of its type and value.
+///
+/// # Note
+///
+/// Non-finite floating point values are encoded as strings since JSON cannot represent them.
+fn value_to_json(value: &Value) -> JsonValue {
+    let ty = DisplayValueType::from(&value.ty()).to_string();
+    let value = match value {
+        Value::I32(value) => json!(value),
+        Value::I64(value) => json!(value),
+        Value::F32(value) => match value.to_float() {
+            value if value.is_finite() => json!(value),
+            value => json!(value.to_string()),
+        },
+        Value::F64(value) => match value.to_float() {
+            value if value.is_finite() => json!(value),
+            value => json!(value.to_string()),
+        },
+        Value::FuncRef(value) => match value.is_null() {
+            true => JsonValue::Null,
+            false => json!("funcref"),
+        },
+        Value::ExternRef(value) => match value.is_null() {
+            true => JsonValue::Null,
+            false => json!("externref"),
+        },
+    };
+    json!({ "type": ty, "value": value })
+}
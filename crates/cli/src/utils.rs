@@ -37,6 +37,35 @@ pub fn prepare_func_results(ty: &FuncType) -> Box<[Value]> {
     ty.results().iter().copied().map(Value::default).collect()
 }
 
+/// Decodes the given `arg` as [`Value`] of type `ty`.
+///
+/// # Errors
+///
+/// - If `arg` cannot be parsed as a value of type `ty`.
+/// - If `ty` is an unsupported [`ExternRef`] or [`FuncRef`] type.
+///
+/// [`FuncRef`]: wasmi::FuncRef
+/// [`ExternRef`]: wasmi::ExternRef
+pub fn decode_value(ty: &ValueType, arg: &str) -> Result<Value, Error> {
+    let make_err = || anyhow!("failed to parse {arg} as {}", DisplayValueType::from(ty));
+    match ty {
+        ValueType::I32 => arg.parse::<i32>().map(Value::from).map_err(|_| make_err()),
+        ValueType::I64 => arg.parse::<i64>().map(Value::from).map_err(|_| make_err()),
+        ValueType::F32 => arg
+            .parse::<f32>()
+            .map(F32::from)
+            .map(Value::from)
+            .map_err(|_| make_err()),
+        ValueType::F64 => arg
+            .parse::<f64>()
+            .map(F64::from)
+            .map(Value::from)
+            .map_err(|_| make_err()),
+        ValueType::FuncRef => bail!("the wasmi CLI cannot take arguments of type funcref"),
+        ValueType::ExternRef => bail!("the wasmi CLI cannot take arguments of type externref"),
+    }
+}
+
 /// Decode the given `args` for the [`FuncType`] `ty`.
 ///
 /// Returns the decoded `args` as a slice of [`Value`] which can be used
@@ -55,38 +84,15 @@ pub fn decode_func_args(ty: &FuncType, args: &[String]) -> Result<Box<[Value]>,
         .iter()
         .zip(args)
         .enumerate()
-        .map(|(n, (param_type, arg))| {
-            macro_rules! make_err {
-                () => {
-                    |_| {
-                        anyhow!(
-                            "failed to parse function argument \
-                            {arg} at index {n} as {}",
-                            DisplayValueType::from(param_type)
-                        )
-                    }
-                };
-            }
-            match param_type {
-                ValueType::I32 => arg.parse::<i32>().map(Value::from).map_err(make_err!()),
-                ValueType::I64 => arg.parse::<i64>().map(Value::from).map_err(make_err!()),
-                ValueType::F32 => arg
-                    .parse::<f32>()
-                    .map(F32::from)
-                    .map(Value::from)
-                    .map_err(make_err!()),
-                ValueType::F64 => arg
-                    .parse::<f64>()
-                    .map(F64::from)
-                    .map(Value::from)
-                    .map_err(make_err!()),
-                ValueType::FuncRef => {
-                    bail!("the wasmi CLI cannot take arguments of type funcref")
-                }
-                ValueType::ExternRef => {
-                    bail!("the wasmi CLI cannot take arguments of type externref")
-                }
-            }
+        .map(|(n, (param_type, arg))| match param_type {
+            ValueType::FuncRef | ValueType::ExternRef => decode_value(param_type, arg),
+            _ => decode_value(param_type, arg).map_err(|_| {
+                anyhow!(
+                    "failed to parse function argument \
+                    {arg} at index {n} as {}",
+                    DisplayValueType::from(param_type)
+                )
+            }),
         })
         .collect::<Result<Box<[_]>, _>>()
 }
@@ -30,6 +30,695 @@ fn test_proc_exit() {
     assert.failure().code(1);
 }
 
+#[test]
+fn test_run_subcommand() {
+    let mut cmd = get_cmd();
+    let assert = cmd.arg("run").arg(get_bin_path("simple_print")).assert();
+    let output = assert.get_output();
+    assert!(contains_slice(&output.stdout, b"Hello World"));
+}
+
+#[test]
+fn test_stdio_redirection() {
+    let dir = get_temp_dir("stdio_redirection");
+    let stdin = dir.join("stdin.txt");
+    let stdout = dir.join("stdout.txt");
+    std::fs::write(&stdin, "Hello from a file\n").unwrap();
+    let mut cmd = get_cmd();
+    cmd.arg("--stdin")
+        .arg(&stdin)
+        .arg("--stdout")
+        .arg(&stdout)
+        .args(["--invoke", "echo"])
+        .arg(get_bin_path("wasi_io"))
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(&stdout).unwrap(), b"Hello from a file\n");
+}
+
+#[test]
+fn test_dir_readonly() {
+    let dir = get_temp_dir("dir_readonly");
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--dir-readonly")
+        .arg(&dir)
+        .args(["--invoke", "create"])
+        .arg(get_bin_path("wasi_io"))
+        .assert();
+    assert!(!contains_slice(&assert.get_output().stdout, b"\n0\n"));
+    assert.success();
+    assert!(!dir.join("created.txt").exists());
+}
+
+#[test]
+fn test_mapdir() {
+    let dir = get_temp_dir("mapdir");
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--mapdir")
+        .arg(format!("/sandbox::{}", dir.display()))
+        .args(["--invoke", "create"])
+        .arg(get_bin_path("wasi_io"))
+        .assert();
+    assert!(contains_slice(&assert.get_output().stdout, b"\n0\n"));
+    assert.success();
+    assert!(dir.join("created.txt").exists());
+}
+
+#[test]
+fn test_inherit_env() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--invoke", "env_count"])
+        .arg(get_bin_path("wasi_io"))
+        .env("WASMI_TEST_ENV", "1")
+        .assert();
+    assert!(contains_slice(&assert.get_output().stdout, b"\n0\n"));
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--inherit-env", "--invoke", "env_count"])
+        .arg(get_bin_path("wasi_io"))
+        .env("WASMI_TEST_ENV", "1")
+        .assert();
+    assert!(!contains_slice(&assert.get_output().stdout, b"\n0\n"));
+    assert.success();
+}
+
+#[test]
+fn test_deterministic_wasi() {
+    let run = |func: &str| {
+        let mut cmd = get_cmd();
+        let assert = cmd
+            .args(["--deterministic-wasi", "--invoke", func])
+            .arg(get_bin_path("wasi_io"))
+            .assert()
+            .success();
+        assert.get_output().stdout.clone()
+    };
+    assert!(contains_slice(&run("clock"), b"\n0\n"));
+    assert_eq!(run("random"), run("random"));
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--invoke", "clock"])
+        .arg(get_bin_path("wasi_io"))
+        .assert()
+        .success();
+    assert!(!contains_slice(&assert.get_output().stdout, b"\n0\n"));
+}
+
+#[test]
+fn test_record_replay_host_calls() {
+    let dir = get_temp_dir("record_replay_host_calls");
+    let host_calls = dir.join("host_calls.log");
+    let mut cmd = get_cmd();
+    let recorded = cmd
+        .arg("--record-host-calls")
+        .arg(&host_calls)
+        .args(["--invoke", "random"])
+        .arg(get_bin_path("wasi_io"))
+        .assert()
+        .success();
+    let log = std::fs::read_to_string(&host_calls).unwrap();
+    assert!(log.starts_with("call "));
+    let mut cmd = get_cmd();
+    let replayed = cmd
+        .arg("--replay-host-calls")
+        .arg(&host_calls)
+        .args(["--invoke", "random"])
+        .arg(get_bin_path("wasi_io"))
+        .assert()
+        .success();
+    assert_eq!(recorded.get_output().stdout, replayed.get_output().stdout);
+    let mut cmd = get_cmd();
+    let diverged = cmd
+        .arg("--replay-host-calls")
+        .arg(&host_calls)
+        .args(["--invoke", "clock"])
+        .arg(get_bin_path("wasi_io"))
+        .assert()
+        .failure();
+    assert!(contains_slice(
+        &diverged.get_output().stderr,
+        b"diverged from the recording"
+    ));
+}
+
+#[test]
+fn test_preload() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--preload")
+        .arg(format!("lib={}", get_bin_path("preload_lib").display()))
+        .args(["--invoke", "add_offset"])
+        .arg(get_bin_path("preload_main"))
+        .arg("5")
+        .assert();
+    assert!(contains_slice(&assert.get_output().stdout, b"15"));
+    assert.success();
+}
+
+#[test]
+fn test_preload_unresolved_import() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--invoke", "add_offset"])
+        .arg(get_bin_path("preload_main"))
+        .arg("5")
+        .assert();
+    let stderr = &assert.get_output().stderr;
+    assert!(contains_slice(
+        stderr,
+        b"cannot find definition for import lib::add"
+    ));
+    assert.failure();
+}
+
+#[test]
+fn test_preload_mistyped_import() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--preload")
+        .arg(format!("lib={}", get_bin_path("preload_lib").display()))
+        .arg(get_bin_path("preload_mistyped"))
+        .assert();
+    let stderr = &assert.get_output().stderr;
+    assert!(contains_slice(
+        stderr,
+        b"function type mismatch for import lib::add"
+    ));
+    assert.failure();
+}
+
+#[test]
+fn test_wasm_features() {
+    let mut cmd = get_cmd();
+    cmd.args(["validate", "--wasm-features", "tail-call"])
+        .arg(get_bin_path("config"))
+        .assert()
+        .success();
+    let mut cmd = get_cmd();
+    cmd.arg("validate")
+        .arg(get_bin_path("config"))
+        .assert()
+        .failure();
+    let mut cmd = get_cmd();
+    cmd.args(["validate", "--wasm-features", "tail-call,multi-value"])
+        .args(["--disable-feature", "tail-call"])
+        .arg(get_bin_path("config"))
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_max_recursion_depth() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--wasm-features", "tail-call", "--invoke", "recurse"])
+        .arg(get_bin_path("config"))
+        .arg("100")
+        .assert();
+    assert!(contains_slice(&assert.get_output().stdout, b"100"));
+    assert.success();
+    let mut cmd = get_cmd();
+    cmd.args([
+        "--wasm-features",
+        "tail-call",
+        "--max-recursion-depth",
+        "10",
+    ])
+    .args(["--invoke", "recurse"])
+    .arg(get_bin_path("config"))
+    .arg("100")
+    .assert()
+    .failure();
+    let mut cmd = get_cmd();
+    cmd.args([
+        "--wasm-features",
+        "tail-call",
+        "--max-recursion-depth",
+        "10",
+    ])
+    .args(["--invoke", "count"])
+    .arg(get_bin_path("config"))
+    .arg("100")
+    .assert()
+    .success();
+}
+
+#[test]
+fn test_max_stack() {
+    let mut cmd = get_cmd();
+    cmd.args(["--wasm-features", "tail-call", "--max-stack", "16"])
+        .args(["--invoke", "recurse"])
+        .arg(get_bin_path("config"))
+        .arg("100")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_fuel_mode() {
+    // Returns the fuel consumed by a `memory.fill` of 6400 bytes that traps out of bounds.
+    let consumed_fuel = |mode: &str| {
+        let mut cmd = get_cmd();
+        let assert = cmd
+            .args(["--output", "json", "--fuel", "1000", "--fuel-mode", mode])
+            .args(["--invoke", "fill"])
+            .arg(get_bin_path("engine"))
+            .args(["65000", "6400"])
+            .assert();
+        let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+        assert_eq!(json["error"]["trap_code"], "MemoryOutOfBounds");
+        assert.failure();
+        json["fuel"]["consumed"].as_u64().unwrap()
+    };
+    let lazy = consumed_fuel("lazy");
+    let eager = consumed_fuel("eager");
+    // Only the eager mode charges the 100 fuel for the 6400 bytes of the failed bulk-operation.
+    assert_eq!(eager - lazy, 100);
+}
+
+#[test]
+fn test_deterministic() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--deterministic", "--invoke", "nan"])
+        .arg(get_bin_path("engine"))
+        .arg("0")
+        .assert();
+    // The canonical `f32` NaN `0x7FC0_0000` regardless of the host.
+    assert!(contains_slice(
+        &assert.get_output().stdout,
+        b"\n2143289344\n"
+    ));
+    assert.success();
+}
+
+#[test]
+fn test_allocation_strategy() {
+    let grow = |args: &[&str]| {
+        let mut cmd = get_cmd();
+        let assert = cmd
+            .args(args)
+            .args(["--invoke", "grow"])
+            .arg(get_bin_path("engine"))
+            .arg("1")
+            .assert()
+            .success();
+        assert.get_output().stdout.clone()
+    };
+    assert!(contains_slice(&grow(&[]), b"\n1\n"));
+    assert!(contains_slice(&grow(&["--memory-init-cow"]), b"\n1\n"));
+    let pooling = [
+        "--allocation-strategy",
+        "pooling",
+        "--pool-memory-pages",
+        "1",
+    ];
+    assert!(contains_slice(&grow(&pooling), b"\n-1\n"));
+    let mut cmd = get_cmd();
+    cmd.args(["--pool-memory-pages", "1", "--invoke", "grow"])
+        .arg(get_bin_path("engine"))
+        .arg("1")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_repl() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["repl", "--fuel", "1000"])
+        .arg(get_bin_path("repl"))
+        .write_stdin(
+            "call bump 5\n\
+            call bump 2\n\
+            set counter 40\n\
+            get counter\n\
+            write memory 8 2a2b\n\
+            read memory 7 4\n\
+            call load 9\n\
+            call bump x\n\
+            unknown\n\
+            exit\n\
+            call bump 1\n",
+        )
+        .assert();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(stdout, b"> 5\nfuel consumed: 7\n"));
+    assert!(contains_slice(stdout, b"> 7\nfuel consumed: 7\n"));
+    assert!(contains_slice(stdout, b"> 40\n"));
+    assert!(contains_slice(stdout, b"> 00 2a 2b 00\n"));
+    assert!(contains_slice(stdout, b"> 43\n"));
+    assert!(contains_slice(
+        stdout,
+        b"> error: failed to parse function argument x at index 0 as i32\n"
+    ));
+    assert!(contains_slice(
+        stdout,
+        b"> error: unknown command `unknown`"
+    ));
+    assert!(!contains_slice(stdout, b"> 41\n"));
+    assert.success();
+}
+
+#[test]
+fn test_repl_out_of_bounds_memory_access() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("repl")
+        .arg(get_bin_path("repl"))
+        .write_stdin(
+            "read memory 0 99999999999999999\n\
+            read memory 18446744073709551615 1\n\
+            write memory 18446744073709551615 2a\n\
+            read memory 0 1\n",
+        )
+        .assert();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(
+        stdout,
+        b"> error: out of bounds memory access: 99999999999999999 bytes at offset 0"
+    ));
+    assert!(contains_slice(
+        stdout,
+        b"> error: out of bounds memory access: 1 bytes at offset 18446744073709551615"
+    ));
+    assert!(contains_slice(stdout, b"> 00\n"));
+    assert.success();
+}
+
+#[test]
+fn test_repl_rejects_run_options() {
+    for option in [
+        &["--invoke", "bump"][..],
+        &["--output", "json"],
+        &["--profile", "profile.txt"],
+        &["--record-host-calls", "calls.log"],
+    ] {
+        let mut cmd = get_cmd();
+        cmd.arg("repl")
+            .args(option)
+            .arg(get_bin_path("repl"))
+            .write_stdin("exit\n")
+            .assert()
+            .failure();
+    }
+}
+
+#[test]
+fn test_run_json_redirects_guest_stdout() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json"])
+        .arg(get_bin_path("simple_print"))
+        .assert();
+    let output = assert.get_output();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["function"], "");
+    assert_eq!(json["error"], serde_json::Value::Null);
+    assert!(contains_slice(&output.stderr, b"Hello World\n"));
+    assert.success();
+}
+
+#[test]
+fn test_run_json_setup_error() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json", "--invoke", "main"])
+        .arg(get_bin_path("invalid"))
+        .assert();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(json["function"], "main");
+    assert_eq!(json["results"], serde_json::Value::Null);
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("failed to parse and validate Wasm module"),
+        "{message}"
+    );
+    assert.failure();
+}
+
+#[test]
+fn test_run_json() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json", "--fuel", "100", "--invoke", "bump"])
+        .arg(get_bin_path("repl"))
+        .arg("3")
+        .assert();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(json["function"], "bump");
+    assert_eq!(json["args"][0]["type"], "i32");
+    assert_eq!(json["args"][0]["value"], 3);
+    assert_eq!(json["results"][0]["value"], 3);
+    assert_eq!(json["error"], serde_json::Value::Null);
+    assert_eq!(json["fuel"]["consumed"], 7);
+    assert_eq!(json["fuel"]["remaining"], 93);
+    assert!(json["wall_time_ns"].is_u64());
+    assert.success();
+}
+
+#[test]
+fn test_run_json_trap() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json", "--invoke", "load"])
+        .arg(get_bin_path("repl"))
+        .arg("70000")
+        .assert();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(json["results"], serde_json::Value::Null);
+    assert_eq!(json["error"]["trap_code"], "MemoryOutOfBounds");
+    assert_eq!(json["error"]["exit_status"], serde_json::Value::Null);
+    assert_eq!(json["fuel"], serde_json::Value::Null);
+    assert.failure();
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json"])
+        .arg(get_bin_path("proc_exit"))
+        .assert();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["exit_status"], 1);
+    assert.failure().code(1);
+}
+
+#[test]
+fn test_run_profile() {
+    let dir = get_temp_dir("profile");
+    let profile_file = dir.join("out.folded");
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--profile")
+        .arg(&profile_file)
+        .args(["--invoke", "sum_of_squares"])
+        .arg(get_bin_path("profile"))
+        .arg("3")
+        .assert();
+    assert!(contains_slice(&assert.get_output().stdout, b"14"));
+    assert.success();
+    let profile = std::fs::read_to_string(&profile_file).unwrap();
+    let stacks = profile
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(stacks.len(), 2);
+    assert_eq!(stacks[0].0, "sum_of_squares");
+    assert_eq!(stacks[1].0, "sum_of_squares;square");
+    assert!(stacks
+        .iter()
+        .all(|(_, count)| count.parse::<u64>().unwrap() != 0));
+    let mut cmd = get_cmd();
+    cmd.arg("--profile")
+        .arg(&profile_file)
+        .args(["--invoke", "unnamed"])
+        .arg(get_bin_path("profile"))
+        .assert()
+        .success();
+    let profile = std::fs::read_to_string(&profile_file).unwrap();
+    assert!(profile.starts_with("func[2] "));
+    assert_eq!(profile.lines().count(), 1);
+}
+
+#[test]
+fn test_run_fuel_report() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args([
+            "--fuel",
+            "1000",
+            "--fuel-report",
+            "--invoke",
+            "sum_of_squares",
+        ])
+        .arg(get_bin_path("profile"))
+        .arg("3")
+        .assert();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(
+        stdout,
+        b"fuel consumed: 74, fuel remaining: 926\nfuel report:\n - sum_of_squares: 59\n - square: 15\n"
+    ));
+    assert.success();
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["--output", "json", "--fuel", "1000", "--fuel-report"])
+        .args(["--invoke", "sum_of_squares"])
+        .arg(get_bin_path("profile"))
+        .arg("3")
+        .assert();
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(json["fuel"]["functions"][0]["name"], "sum_of_squares");
+    assert_eq!(json["fuel"]["functions"][0]["consumed"], 59);
+    assert_eq!(json["fuel"]["functions"][1]["name"], "square");
+    assert_eq!(json["fuel"]["functions"][1]["consumed"], 15);
+    assert.success();
+    let mut cmd = get_cmd();
+    cmd.args(["--fuel-report", "--invoke", "sum_of_squares"])
+        .arg(get_bin_path("profile"))
+        .arg("3")
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_inspect() {
+    let mut cmd = get_cmd();
+    let assert = cmd.arg("inspect").arg(get_bin_path("inspect")).assert();
+    let output = assert.get_output();
+    let stdout = &output.stdout;
+    assert!(contains_slice(stdout, b" - env::log: fn(i32)\n"));
+    assert!(contains_slice(stdout, b" - env::memory: memory 1 pages\n"));
+    assert!(contains_slice(stdout, b" - fn add(i32, i32) -> i32\n"));
+    assert!(contains_slice(stdout, b" - counter: global mut i32\n"));
+    assert!(contains_slice(stdout, b" - [0] table funcref 2\n"));
+    assert!(contains_slice(stdout, b" - [0] active: 5 bytes\n"));
+    assert!(contains_slice(stdout, b" - [0] active: 2 items\n"));
+    assert.success();
+}
+
+#[test]
+fn test_inspect_json() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["inspect", "--output", "json"])
+        .arg(get_bin_path("inspect"))
+        .assert();
+    let output = assert.get_output();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["imports"][0]["module"], "env");
+    assert_eq!(json["imports"][0]["type"]["params"][0], "i32");
+    assert_eq!(json["exports"][0]["name"], "add");
+    assert_eq!(json["exports"][1]["type"]["mutable"], true);
+    assert_eq!(json["tables"][0]["minimum"], 2);
+    assert_eq!(json["data_segments"][0]["size"], 5);
+    assert_eq!(json["element_segments"][0]["items"], 2);
+}
+
+#[test]
+fn test_inspect_bytecode() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["inspect", "--bytecode"])
+        .arg(get_bin_path("inspect"))
+        .assert();
+    let output = assert.get_output();
+    let stdout = &output.stdout;
+    assert!(contains_slice(stdout, b"bytecode:\nfunc[1]: "));
+    assert!(contains_slice(stdout, b": LocalGet depth=2\n"));
+    assert!(contains_slice(stdout, b": I32Add\n"));
+    assert.success();
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["inspect", "--bytecode", "--output", "json"])
+        .arg(get_bin_path("inspect"))
+        .assert();
+    let output = assert.get_output();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["bytecode"][0]["func_index"], 1);
+    assert_eq!(json["bytecode"][0]["instrs"][0], "LocalGet depth=2");
+    assert!(!contains_slice(&output.stdout, b"ConsumeFuel"));
+}
+
+#[test]
+fn test_inspect_bytecode_fuel_metering() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .args(["inspect", "--bytecode", "--fuel-metering"])
+        .arg(get_bin_path("inspect"))
+        .assert();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(stdout, b": ConsumeFuel "));
+    assert.success();
+    let mut cmd = get_cmd();
+    cmd.args(["inspect", "--fuel-metering"])
+        .arg(get_bin_path("inspect"))
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_validate() {
+    let mut cmd = get_cmd();
+    cmd.arg("validate")
+        .arg(get_bin_path("inspect"))
+        .assert()
+        .success();
+    let mut cmd = get_cmd();
+    cmd.arg("validate")
+        .arg(get_bin_path("invalid"))
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_compile() {
+    let mut cmd = get_cmd();
+    let assert = cmd.arg("compile").arg(get_bin_path("inspect")).assert();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(stdout.starts_with("compiled "), "{stdout}");
+    assert.success();
+    let mut cmd = get_cmd();
+    cmd.arg("compile")
+        .arg(get_bin_path("invalid"))
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_wast_register() {
+    let mut cmd = get_cmd();
+    let assert = cmd.arg("wast").arg(get_wast_path("register")).assert();
+    let output = assert.get_output();
+    let stdout = &output.stdout;
+    assert!(contains_slice(
+        stdout,
+        b"PASS tests/wasts/register.wast:6:2 register"
+    ));
+    assert!(contains_slice(stdout, b"(7 passed, 0 failed, 0 skipped)"));
+    assert.success();
+}
+
+#[test]
+fn test_wast_failure() {
+    let mut cmd = get_cmd();
+    let assert = cmd.arg("wast").arg(get_wast_path("failure")).assert();
+    let output = assert.get_output();
+    let stdout = &output.stdout;
+    assert!(contains_slice(
+        stdout,
+        b"PASS tests/wasts/failure.wast:5:2 assert_return"
+    ));
+    assert!(contains_slice(
+        stdout,
+        b"FAIL tests/wasts/failure.wast:6:2 assert_return"
+    ));
+    assert!(contains_slice(stdout, b"(2 passed, 1 failed, 0 skipped)"));
+    assert.failure().code(1);
+}
+
 /// UTILS
 
 /// gets the path to a wasm binary given it's name
@@ -41,6 +730,25 @@ fn get_bin_path(name: &str) -> PathBuf {
     path
 }
 
+/// gets the path to a `.wast` script given it's name
+fn get_wast_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    path.push("tests");
+    path.push("wasts");
+    path.push(format!("{name}.wast"));
+    path
+}
+
 fn get_cmd() -> assert_cmd::Command {
     Command::cargo_bin("wasmi_cli").expect("could not create wasmi_cli command")
 }
+
+/// Returns an empty temporary directory unique to the test named `name`.
+fn get_temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wasmi_cli-{}-{name}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,25 @@
+(module $math
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+)
+(register "math" $math)
+
+(module
+  (import "math" "add" (func $add (param i32 i32) (result i32)))
+  (func (export "double") (param i32) (result i32)
+    (call $add (local.get 0) (local.get 0))
+  )
+  (func (export "trap") (unreachable))
+)
+
+(assert_return (invoke "double" (i32.const 21)) (i32.const 42))
+(assert_trap (invoke "trap") "unreachable")
+(assert_invalid
+  (module (func (result i32) (i64.const 0)))
+  "type mismatch"
+)
+(assert_unlinkable
+  (module (import "math" "sub" (func (param i32 i32) (result i32))))
+  "unknown import"
+)
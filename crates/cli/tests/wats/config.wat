@@ -0,0 +1,21 @@
+(module
+  (func $count (export "count") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else
+        (return_call $count (i32.sub (local.get 0) (i32.const 1)))
+      )
+    )
+  )
+  (func $recurse (export "recurse") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else
+        (i32.add
+          (call $recurse (i32.sub (local.get 0) (i32.const 1)))
+          (i32.const 1)
+        )
+      )
+    )
+  )
+)
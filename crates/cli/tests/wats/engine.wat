@@ -0,0 +1,12 @@
+(module
+  (memory 1)
+  (func (export "fill") (param i32 i32)
+    (memory.fill (local.get 0) (i32.const 0) (local.get 1))
+  )
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0))
+  )
+  (func (export "nan") (param f32) (result i32)
+    (i32.reinterpret_f32 (f32.div (local.get 0) (local.get 0)))
+  )
+)
@@ -0,0 +1,12 @@
+(module
+  (import "env" "log" (func $log (param i32)))
+  (import "env" "memory" (memory 1))
+  (table 2 funcref)
+  (global $counter (mut i32) (i32.const 0))
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+  (export "counter" (global $counter))
+  (elem (i32.const 0) func 1 1)
+  (data (i32.const 0) "Hello")
+)
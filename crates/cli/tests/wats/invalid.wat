@@ -0,0 +1,5 @@
+(module
+  (func (export "invalid") (result i32)
+    (i64.const 0)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (global (export "offset") i32 (i32.const 10))
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+)
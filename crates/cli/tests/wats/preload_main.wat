@@ -0,0 +1,7 @@
+(module
+  (import "lib" "add" (func $add (param i32 i32) (result i32)))
+  (import "lib" "offset" (global $offset i32))
+  (func (export "add_offset") (param i32) (result i32)
+    (call $add (local.get 0) (global.get $offset))
+  )
+)
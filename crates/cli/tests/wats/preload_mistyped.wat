@@ -0,0 +1,3 @@
+(module
+  (import "lib" "add" (func $add (param i64 i64) (result i64)))
+)
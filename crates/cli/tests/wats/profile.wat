@@ -0,0 +1,20 @@
+(module
+    (func $square (param i32) (result i32)
+        (i32.mul (local.get 0) (local.get 0))
+    )
+    (func $sum_of_squares (export "sum_of_squares") (param i32) (result i32)
+        (local $sum i32)
+        (block $exit
+            (loop $continue
+                (br_if $exit (i32.eqz (local.get 0)))
+                (local.set $sum (i32.add (local.get $sum) (call $square (local.get 0))))
+                (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+                (br $continue)
+            )
+        )
+        (local.get $sum)
+    )
+    (func (export "unnamed") (result i32)
+        (i32.const 42)
+    )
+)
@@ -0,0 +1,76 @@
+(module
+    (import "wasi_snapshot_preview1" "fd_read" (func $fd_read
+        (param $fd i32) (param $iovec i32) (param $iovec_len i32) (param $nread i32)
+        (result i32))
+    )
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write
+        (param $fd i32) (param $iovec i32) (param $iovec_len i32) (param $nwritten i32)
+        (result i32))
+    )
+    (import "wasi_snapshot_preview1" "path_open" (func $path_open
+        (param $fd i32) (param $dirflags i32) (param $path i32) (param $path_len i32)
+        (param $oflags i32) (param $rights_base i64) (param $rights_inheriting i64)
+        (param $fdflags i32) (param $opened_fd i32)
+        (result i32))
+    )
+    (import "wasi_snapshot_preview1" "environ_sizes_get" (func $environ_sizes_get
+        (param $count i32) (param $buf_size i32)
+        (result i32))
+    )
+    (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get
+        (param $id i32) (param $precision i64) (param $time i32)
+        (result i32))
+    )
+    (import "wasi_snapshot_preview1" "random_get" (func $random_get
+        (param $buf i32) (param $buf_len i32)
+        (result i32))
+    )
+
+    (memory (export "memory") 1)
+
+    (data (i32.const 16) "created.txt")
+
+    ;; Copies up to 1024 bytes from stdin to stdout.
+    (func (export "echo") (result i32)
+        (i32.store (i32.const 0) (i32.const 1024)) ;; iovec base
+        (i32.store (i32.const 4) (i32.const 1024)) ;; iovec length
+        (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (i32.store (i32.const 4) (i32.load (i32.const 8))) ;; iovec length = bytes read
+        (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8))
+    )
+
+    ;; Creates `created.txt` for writing in the first pre-opened directory.
+    ;;
+    ;; Returns the WASI errno of the operation.
+    (func (export "create") (result i32)
+        (call $path_open
+            (i32.const 3)  ;; fd of the first pre-opened directory
+            (i32.const 0)  ;; dirflags
+            (i32.const 16) ;; path
+            (i32.const 11) ;; path length
+            (i32.const 1)  ;; oflags: create
+            (i64.const 64) ;; rights base: fd_write
+            (i64.const 0)  ;; rights inheriting
+            (i32.const 0)  ;; fdflags
+            (i32.const 8)  ;; opened fd
+        )
+    )
+
+    ;; Returns the number of environment variables available to the program.
+    (func (export "env_count") (result i32)
+        (drop (call $environ_sizes_get (i32.const 0) (i32.const 4)))
+        (i32.load (i32.const 0))
+    )
+
+    ;; Returns the current realtime clock in nanoseconds since the Unix epoch.
+    (func (export "clock") (result i64)
+        (drop (call $clock_time_get (i32.const 0) (i64.const 1) (i32.const 0)))
+        (i64.load (i32.const 0))
+    )
+
+    ;; Returns 8 random bytes.
+    (func (export "random") (result i64)
+        (drop (call $random_get (i32.const 0) (i32.const 8)))
+        (i64.load (i32.const 0))
+    )
+)
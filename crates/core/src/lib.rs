@@ -48,4 +48,4 @@ pub use self::{
 };
 
 #[cfg(feature = "virtual_memory")]
-pub use self::vmem::{VirtualMemory, VirtualMemoryError};
+pub use self::vmem::{MemoryImage, VirtualMemory, VirtualMemoryError};
@@ -172,6 +172,33 @@ float! {
     struct F64(u64 as f64);
 }
 
+macro_rules! impl_canonicalize_nan {
+    ( $( impl $for:ident($rep:ty) = $canonical_nan:literal; )* ) => {
+        $(
+            impl $for {
+                /// The bits of the canonical NaN as defined by the WebAssembly specification.
+                ///
+                /// This is the positive quiet NaN with an otherwise empty payload.
+                pub const CANONICAL_NAN_BITS: $rep = $canonical_nan;
+
+                /// Returns the canonical NaN if `self` is NaN and `self` otherwise.
+                #[must_use]
+                #[inline]
+                pub fn canonicalize_nan(self) -> Self {
+                    if self.is_nan() {
+                        return Self::from_bits(Self::CANONICAL_NAN_BITS);
+                    }
+                    self
+                }
+            }
+        )*
+    };
+}
+impl_canonicalize_nan! {
+    impl F32(u32) = 0x7FC0_0000;
+    impl F64(u64) = 0x7FF8_0000_0000_0000;
+}
+
 impl From<u32> for F32 {
     #[inline]
     fn from(other: u32) -> Self {
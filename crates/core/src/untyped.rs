@@ -949,6 +949,24 @@ impl UntypedValue {
         self.execute_unary(<F32 as Float<F32>>::sqrt)
     }
 
+    /// Canonicalizes the `f32` value if it is NaN.
+    ///
+    /// # Note
+    ///
+    /// This is not a Wasm operation but used to make float operations deterministic.
+    pub fn f32_canonicalize_nan(self) -> Self {
+        self.execute_unary(F32::canonicalize_nan)
+    }
+
+    /// Canonicalizes the `f64` value if it is NaN.
+    ///
+    /// # Note
+    ///
+    /// This is not a Wasm operation but used to make float operations deterministic.
+    pub fn f64_canonicalize_nan(self) -> Self {
+        self.execute_unary(F64::canonicalize_nan)
+    }
+
     /// Execute `f32.min` Wasm operation.
     pub fn f32_min(self, other: Self) -> Self {
         self.execute_binary(other, <F32 as Float<F32>>::min)
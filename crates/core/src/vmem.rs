@@ -4,6 +4,11 @@
 //! There are possibilities to improve the performance for the reallocating case by reserving
 //! memory up to maximum. This might be a problem for systems that don't have a lot of virtual
 //! memory (i.e. 32-bit platforms).
+//!
+//! Virtual memory allocations may be initialized from a [`MemoryImage`].
+//! On Linux the image is backed by an anonymous `memfd` file that is mapped
+//! privately into each allocation so that pages are shared copy-on-write.
+//! On other platforms the image bytes are copied into the allocation instead.
 
 use core::{
     fmt,
@@ -11,11 +16,14 @@ use core::{
     slice,
 };
 use region::{Allocation, Protection};
+#[cfg(target_os = "linux")]
+use std::{fs::File, io::Write as _, os::unix::io::AsRawFd as _};
 
 /// Dummy error for fallible `Vec`-based virtual memory operations.
 #[derive(Debug)]
 pub enum VirtualMemoryError {
     Region(region::Error),
+    Io(std::io::Error),
     AllocationOutOfBounds,
 }
 
@@ -26,6 +34,13 @@ impl From<region::Error> for VirtualMemoryError {
     }
 }
 
+impl From<std::io::Error> for VirtualMemoryError {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
 impl Display for VirtualMemoryError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -34,6 +49,11 @@ impl Display for VirtualMemoryError {
                 "encountered failure while operating with virtual memory: {}",
                 error
             ),
+            Self::Io(error) => write!(
+                f,
+                "encountered I/O failure while operating with virtual memory: {}",
+                error
+            ),
             Self::AllocationOutOfBounds => write!(f, "virtual memory allocation is too big"),
         }
     }
@@ -81,6 +101,80 @@ impl VirtualMemory {
         Ok(Self { allocation })
     }
 
+    /// Create a new virtual memory allocation initialized with the contents of `image`.
+    ///
+    /// # Note
+    ///
+    /// - On Linux the `image` is mapped privately over the start of the allocation.
+    ///   Its pages are shared between all allocations created from the same `image`
+    ///   until they are written to, which makes this operation independent of the
+    ///   size of the `image`.
+    /// - On other platforms the bytes of the `image` are copied into the allocation.
+    ///
+    /// # Errors
+    ///
+    /// - If `len` is smaller than the length of the `image`.
+    /// - If the same conditions as for [`VirtualMemory::new`] are met.
+    /// - If the operating system returns an error upon mapping the `image`.
+    pub fn with_image(len: usize, image: &MemoryImage) -> Result<Self, VirtualMemoryError> {
+        if image.len() > len {
+            return Err(VirtualMemoryError::AllocationOutOfBounds);
+        }
+        let mut memory = Self::new(len)?;
+        image.map_into(&mut memory)?;
+        Ok(memory)
+    }
+
+    /// Resets the first `len` bytes of the virtual memory allocation to zero.
+    ///
+    /// # Note
+    ///
+    /// On Linux the physical pages of the reset range are released back to the
+    /// operating system via `madvise` so that they are lazily zeroed upon next use.
+    /// This must not be used on allocations created via [`VirtualMemory::with_image`]
+    /// since their image pages would be restored instead.
+    ///
+    /// # Panics
+    ///
+    /// If `len` exceeds the length of the virtual memory allocation.
+    pub fn reset(&mut self, len: usize) {
+        assert!(len <= self.allocation.len());
+        self.reset_impl(len)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn reset_impl(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let page_size = region::page::size();
+        let len_pages = match len % page_size {
+            0 => len,
+            rest => len + (page_size - rest),
+        };
+        // # SAFETY
+        //
+        // The range starts at the page aligned start of the allocation and its
+        // length is rounded up to the host page size which cannot exceed the
+        // page aligned length of the allocation.
+        let result = unsafe {
+            libc::madvise(
+                self.allocation.as_mut_ptr::<u8>().cast(),
+                len_pages,
+                libc::MADV_DONTNEED,
+            )
+        };
+        if result != 0 {
+            // Note: `madvise` is just a hint so we fall back to zeroing manually.
+            self.data_mut()[..len].fill(0x00_u8);
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn reset_impl(&mut self, len: usize) {
+        self.data_mut()[..len].fill(0x00_u8);
+    }
+
     /// Returns a shared slice over the bytes of the virtual memory allocation.
     #[inline]
     pub fn data(&self) -> &[u8] {
@@ -104,3 +198,130 @@ impl VirtualMemory {
         unsafe { slice::from_raw_parts_mut(self.allocation.as_mut_ptr(), self.allocation.len()) }
     }
 }
+
+/// A pre-initialized image of virtual memory contents.
+///
+/// Used to efficiently create many [`VirtualMemory`] allocations that share
+/// the same initial contents via [`VirtualMemory::with_image`].
+pub struct MemoryImage {
+    /// The anonymous file backing the image.
+    #[cfg(target_os = "linux")]
+    file: File,
+    /// The image bytes that are copied upon use.
+    #[cfg(not(target_os = "linux"))]
+    bytes: Box<[u8]>,
+    /// The length of the image in bytes.
+    ///
+    /// # Note
+    ///
+    /// This is always a multiple of the host page size.
+    len: usize,
+}
+
+impl Debug for MemoryImage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryImage")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl MemoryImage {
+    /// Creates a new [`MemoryImage`] from the given `bytes`.
+    ///
+    /// # Note
+    ///
+    /// The length of the image is rounded up to the host page size
+    /// and the padding bytes are initialized to zero.
+    ///
+    /// # Errors
+    ///
+    /// - If the length of the image exceeds the maximum allocation size.
+    /// - If the operating system returns an error upon creating the image.
+    pub fn new(bytes: &[u8]) -> Result<Self, VirtualMemoryError> {
+        if bytes.len() > VirtualMemory::MAX_ALLOCATION_SIZE {
+            return Err(VirtualMemoryError::AllocationOutOfBounds);
+        }
+        let page_size = region::page::size();
+        let len = match bytes.len() % page_size {
+            0 => bytes.len(),
+            rest => bytes.len() + (page_size - rest),
+        };
+        Self::new_impl(bytes, len)
+    }
+
+    /// Returns the length of the [`MemoryImage`] in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the [`MemoryImage`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[cfg(target_os = "linux")]
+    fn new_impl(bytes: &[u8], len: usize) -> Result<Self, VirtualMemoryError> {
+        // # SAFETY
+        //
+        // The name is a valid nul-terminated C string and `memfd_create`
+        // does not retain the pointer beyond the call.
+        let fd = unsafe { libc::memfd_create(c"wasmi_memory_image".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // # SAFETY
+        //
+        // The file descriptor has just been created and is exclusively owned by us.
+        let mut file = unsafe { <File as std::os::unix::io::FromRawFd>::from_raw_fd(fd) };
+        file.write_all(bytes)?;
+        file.set_len(len as u64)?;
+        Ok(Self { file, len })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn new_impl(bytes: &[u8], len: usize) -> Result<Self, VirtualMemoryError> {
+        let mut buffer = vec![0x00_u8; len];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        Ok(Self {
+            bytes: buffer.into(),
+            len,
+        })
+    }
+
+    /// Initializes the start of the `memory` allocation with the [`MemoryImage`].
+    #[cfg(target_os = "linux")]
+    fn map_into(&self, memory: &mut VirtualMemory) -> Result<(), VirtualMemoryError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        // # SAFETY
+        //
+        // - The mapped range lies within the `memory` allocation since `with_image`
+        //   asserts that the allocation is at least as large as the image.
+        // - `MAP_FIXED` replaces the anonymous pages of that range and the mapping
+        //   is released together with the rest of the allocation when it is dropped.
+        // - `MAP_PRIVATE` makes writes copy-on-write so the image is never modified.
+        let ptr = unsafe {
+            libc::mmap(
+                memory.allocation.as_mut_ptr::<u8>().cast(),
+                self.len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_FIXED,
+                self.file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Initializes the start of the `memory` allocation with the [`MemoryImage`].
+    #[cfg(not(target_os = "linux"))]
+    fn map_into(&self, memory: &mut VirtualMemory) -> Result<(), VirtualMemoryError> {
+        memory.data_mut()[..self.len].copy_from_slice(&self.bytes);
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+//! Procedural macros for the `wasmi` WebAssembly interpreter.
+//!
+//! Use them through the `derive` crate feature of `wasmi`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    Attribute,
+    Error,
+    FnArg,
+    Ident,
+    ImplItem,
+    ImplItemFn,
+    ItemImpl,
+    LitInt,
+    LitStr,
+    Result,
+    ReturnType,
+    Token,
+    Type,
+};
+
+/// Registers the methods of a host type as host functions of a Wasm module.
+///
+/// The attribute is applied to an `impl` block and takes the name of the
+/// imported Wasm module as its first argument. It generates an associated
+/// `add_to_linker` function that defines every method with a `&self` or
+/// `&mut self` receiver as host function named after the method.
+///
+/// # Parameters and Results
+///
+/// - Parameters and results are converted via `wasmi::WasmType`, so host methods
+///   may use all Wasm value types and may return `Result<_, Trap>`.
+/// - Pointers into the linear memory may be typed as `wasmi::WasmPtr<T>`.
+/// - A host method may take a `wasmi::GuestMemory` as first parameter after its
+///   receiver for bounds checked access to the linear memory of the calling Wasm guest.
+///   The linear memory is looked up by its export name which defaults to `"memory"`
+///   and can be configured via `memory = "name"`. Alternatively `memory_index = n` looks
+///   up the linear memory at index `n` of the memory index space of the Wasm guest
+///   which also finds imported linear memories that are not re-exported.
+///
+/// # Method Attributes
+///
+/// - `#[host_func(name = "name")]`: Registers the method under the given name.
+/// - `#[host_func(skip)]`: Does not register the method as host function.
+///
+/// # Example
+///
+/// ```ignore
+/// use wasmi::{core::Trap, GuestMemory, WasmPtr};
+///
+/// #[derive(Default)]
+/// struct Logger {
+///     lines: Vec<String>,
+/// }
+///
+/// #[wasmi::host_module("env")]
+/// impl Logger {
+///     fn log(&mut self, memory: GuestMemory, ptr: WasmPtr<u8>, len: u32) -> Result<(), Trap> {
+///         let line = memory.read_str(ptr.slice(len), 1024)?;
+///         self.lines.push(line.into());
+///         Ok(())
+///     }
+/// }
+///
+/// // Defines `env.log` for a `Linker<Logger>`.
+/// Logger::add_to_linker(&mut linker, |logger| logger)?;
+/// ```
+#[proc_macro_attribute]
+pub fn host_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ModuleArgs);
+    let item = parse_macro_input!(input as ItemImpl);
+    expand_host_module(args, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The arguments of the `host_module` attribute.
+struct ModuleArgs {
+    /// The name of the imported Wasm module.
+    module: LitStr,
+    /// The linear memory used by the host functions.
+    memory: MemoryArg,
+}
+
+/// The linear memory of the calling Wasm guest passed as `GuestMemory` to host methods.
+enum MemoryArg {
+    /// The linear memory exported under the given name.
+    Export(LitStr),
+    /// The linear memory at the given index of the memory index space of the Wasm guest.
+    Index(LitInt),
+}
+
+impl Parse for ModuleArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let module = input.parse::<LitStr>()?;
+        let mut memory = MemoryArg::Export(LitStr::new("memory", Span::call_site()));
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            memory = if key == "memory" {
+                MemoryArg::Export(input.parse::<LitStr>()?)
+            } else if key == "memory_index" {
+                let index = input.parse::<LitInt>()?;
+                index.base10_parse::<u32>()?;
+                MemoryArg::Index(index)
+            } else {
+                return Err(Error::new(
+                    key.span(),
+                    "expected `memory = \"name\"` or `memory_index = n`",
+                ));
+            };
+        }
+        Ok(Self { module, memory })
+    }
+}
+
+/// The options of a host method given by its `host_func` attribute.
+#[derive(Default)]
+struct FuncOptions {
+    /// The name of the host function if it differs from the method name.
+    name: Option<LitStr>,
+    /// Whether the method is not registered as host function.
+    skip: bool,
+}
+
+impl FuncOptions {
+    /// Removes the `host_func` attributes from `attrs` and returns their options.
+    fn take(attrs: &mut Vec<Attribute>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("host_func") {
+                return true;
+            }
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    return Ok(());
+                }
+                Err(meta.error("expected `name = \"name\"` or `skip`"))
+            });
+            if let Err(error) = parsed {
+                result = Err(error);
+            }
+            false
+        });
+        result.map(|_| options)
+    }
+}
+
+/// Expands the `host_module` attribute applied to the `item`.
+fn expand_host_module(args: ModuleArgs, mut item: ItemImpl) -> Result<TokenStream2> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "`host_module` cannot be applied to trait implementations",
+        ));
+    }
+    let mut registrations = Vec::new();
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            continue;
+        };
+        let options = FuncOptions::take(&mut method.attrs)?;
+        if options.skip || method.sig.receiver().is_none() {
+            continue;
+        }
+        registrations.push(expand_host_func(&args, options, method)?);
+    }
+    let doc = format!(
+        " Adds the host functions of the `{}` module to the `linker`.",
+        args.module.value()
+    );
+    let add_to_linker = quote! {
+        #[doc = #doc]
+        ///
+        /// The host functions operate on the host type returned by `get` for the data of the `Store`.
+        ///
+        /// # Errors
+        ///
+        /// If any of the host functions is already defined in the `linker`.
+        pub fn add_to_linker<T>(
+            linker: &mut ::wasmi::Linker<T>,
+            get: impl ::core::ops::Fn(&mut T) -> &mut Self
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + ::core::marker::Copy
+                + 'static,
+        ) -> ::core::result::Result<(), ::wasmi::errors::LinkerError> {
+            #( #registrations )*
+            ::core::result::Result::Ok(())
+        }
+    };
+    item.items.push(ImplItem::Verbatim(add_to_linker));
+    Ok(quote! { #item })
+}
+
+/// Expands the registration of the host `method` within the generated `add_to_linker`.
+fn expand_host_func(
+    args: &ModuleArgs,
+    options: FuncOptions,
+    method: &ImplItemFn,
+) -> Result<TokenStream2> {
+    let sig = &method.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "host functions cannot have generic parameters",
+        ));
+    }
+    if let Some(receiver) = sig.receiver() {
+        if receiver.reference.is_none() {
+            return Err(Error::new_spanned(
+                receiver,
+                "host functions must take `&self` or `&mut self`",
+            ));
+        }
+    }
+    let mut uses_memory = false;
+    let mut params = Vec::new();
+    let mut param_types = Vec::new();
+    for (n, input) in sig.inputs.iter().enumerate() {
+        let FnArg::Typed(input) = input else {
+            continue;
+        };
+        if is_guest_memory(&input.ty) {
+            if n != 1 {
+                return Err(Error::new_spanned(
+                    input,
+                    "`GuestMemory` must be the first parameter after the receiver",
+                ));
+            }
+            uses_memory = true;
+            continue;
+        }
+        params.push(format_ident!("__wasmi_param_{}", params.len()));
+        param_types.push(&*input.ty);
+    }
+    let ident = &sig.ident;
+    let module = &args.module;
+    let name = options
+        .name
+        .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+    let result = match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+    let call = if uses_memory {
+        let (lookup, missing) = match &args.memory {
+            MemoryArg::Export(name) => (
+                quote! {
+                    match caller.get_export(#name) {
+                        ::core::option::Option::Some(::wasmi::Extern::Memory(memory)) => {
+                            ::core::option::Option::Some(memory)
+                        }
+                        _ => ::core::option::Option::None,
+                    }
+                },
+                format!("missing required memory export `{}`", name.value()),
+            ),
+            MemoryArg::Index(index) => (
+                quote! { caller.get_memory(#index) },
+                format!("missing required memory at index {}", index.base10_digits()),
+            ),
+        };
+        quote! {
+            let memory = match #lookup {
+                ::core::option::Option::Some(memory) => memory,
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(::wasmi::core::Trap::new(#missing))
+                }
+            };
+            let (memory, state) = caller.guest_memory_and_data(memory);
+            get(state).#ident(memory, #( #params ),*)
+        }
+    } else {
+        quote! {
+            get(caller.data_mut()).#ident(#( #params ),*)
+        }
+    };
+    Ok(quote! {
+        linker.func_wrap(
+            #module,
+            #name,
+            move |mut caller: ::wasmi::Caller<'_, T>, #( #params: #param_types ),*|
+                -> ::core::result::Result<<#result as ::wasmi::WasmRet>::Ok, ::wasmi::core::Trap>
+            {
+                let result: #result = { #call };
+                ::wasmi::WasmRet::into_fallible(result)
+            },
+        )?;
+    })
+}
+
+/// Returns `true` if `ty` refers to the `GuestMemory` type.
+fn is_guest_memory(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "GuestMemory"),
+        _ => false,
+    }
+}
@@ -0,0 +1,260 @@
+use wasmi::{core::Trap, Engine, GuestMemory, Instance, Linker, Module, Store, WasmPtr};
+
+/// The maximum length of the strings read by the host functions.
+const LIMIT: u32 = 64;
+
+/// A host type counting and logging the calls of the Wasm guest.
+#[derive(Debug, Default)]
+struct Host {
+    counter: i64,
+    lines: Vec<String>,
+}
+
+#[wasmi::host_module("env")]
+impl Host {
+    fn add(&mut self, delta: i64) -> i64 {
+        self.counter += delta;
+        self.counter
+    }
+
+    fn get(&self) -> i64 {
+        self.counter
+    }
+
+    fn log(&mut self, memory: GuestMemory, ptr: WasmPtr<u8>, len: u32) -> Result<(), Trap> {
+        let line = memory.read_str(ptr.slice(len), LIMIT)?;
+        self.lines.push(line.into());
+        Ok(())
+    }
+
+    #[host_func(name = "fill.bytes")]
+    fn fill(
+        &mut self,
+        mut memory: GuestMemory,
+        ptr: u32,
+        len: u32,
+        value: i32,
+    ) -> Result<(), Trap> {
+        memory.slice_mut(ptr, len)?.fill(value as u8);
+        Ok(())
+    }
+
+    fn divmod(&self, lhs: u32, rhs: u32) -> Result<(u32, u32), Trap> {
+        if rhs == 0 {
+            return Err(Trap::new("division by zero"));
+        }
+        Ok((lhs / rhs, lhs % rhs))
+    }
+
+    #[host_func(skip)]
+    fn reset(&mut self) {
+        self.counter = 0;
+    }
+
+    fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// A host type whose host functions use a differently named linear memory.
+#[derive(Debug, Default)]
+struct Shouter {
+    lines: Vec<String>,
+}
+
+#[wasmi::host_module("shout", memory = "mem")]
+impl Shouter {
+    fn shout(&mut self, memory: GuestMemory, ptr: WasmPtr<u8>, len: u32) -> Result<(), Trap> {
+        self.lines
+            .push(memory.read_str(ptr.slice(len), LIMIT)?.to_uppercase());
+        Ok(())
+    }
+}
+
+/// A host type whose host functions use the linear memory at index 0.
+#[derive(Debug, Default)]
+struct Whisperer {
+    lines: Vec<String>,
+}
+
+#[wasmi::host_module("whisper", memory_index = 0)]
+impl Whisperer {
+    fn whisper(&mut self, memory: GuestMemory, ptr: WasmPtr<u8>, len: u32) -> Result<(), Trap> {
+        self.lines
+            .push(memory.read_str(ptr.slice(len), LIMIT)?.to_lowercase());
+        Ok(())
+    }
+}
+
+/// The data of the `Store` embedding all host types.
+#[derive(Debug, Default)]
+struct Data {
+    host: Host,
+    shouter: Shouter,
+    whisperer: Whisperer,
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "add" (func $add (param i64) (result i64)))
+        (import "env" "get" (func $get (result i64)))
+        (import "env" "log" (func $log (param i32 i32)))
+        (import "env" "fill.bytes" (func $fill (param i32 i32 i32)))
+        (import "env" "divmod" (func $divmod (param i32 i32) (result i32 i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "hello")
+        (func (export "add_twice") (param i64) (result i64)
+            (drop (call $add (local.get 0)))
+            (drop (call $add (local.get 0)))
+            (call $get)
+        )
+        (func (export "log") (param i32 i32)
+            (call $log (local.get 0) (local.get 1))
+        )
+        (func (export "fill") (param i32 i32 i32)
+            (call $fill (local.get 0) (local.get 1) (local.get 2))
+        )
+        (func (export "divmod") (param i32 i32) (result i32 i32)
+            (call $divmod (local.get 0) (local.get 1))
+        )
+    )
+"#;
+
+/// Instantiates the `wat` module with the host functions of [`Data`].
+fn setup(wat: &str) -> (Store<Data>, Instance) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, Data::default());
+    let mut linker = <Linker<Data>>::new(&engine);
+    Host::add_to_linker(&mut linker, |data| &mut data.host).unwrap();
+    Shouter::add_to_linker(&mut linker, |data| &mut data.shouter).unwrap();
+    Whisperer::add_to_linker(&mut linker, |data| &mut data.whisperer).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+#[test]
+fn host_methods() {
+    let (mut store, instance) = setup(WAT);
+    let add_twice = instance
+        .get_typed_func::<i64, i64>(&store, "add_twice")
+        .unwrap();
+    assert_eq!(add_twice.call(&mut store, 5).unwrap(), 10);
+    assert_eq!(store.data().host.counter, 10);
+    store.data_mut().host.reset();
+    assert_eq!(add_twice.call(&mut store, 1).unwrap(), 2);
+    assert_eq!(Host::new().counter, 0);
+}
+
+#[test]
+fn host_methods_with_multiple_results() {
+    let (mut store, instance) = setup(WAT);
+    let divmod = instance
+        .get_typed_func::<(u32, u32), (u32, u32)>(&store, "divmod")
+        .unwrap();
+    assert_eq!(divmod.call(&mut store, (17, 5)).unwrap(), (3, 2));
+    let error = divmod.call(&mut store, (17, 0)).unwrap_err();
+    assert_eq!(error.to_string(), "division by zero");
+}
+
+#[test]
+fn host_methods_with_guest_memory() {
+    let (mut store, instance) = setup(WAT);
+    let log = instance
+        .get_typed_func::<(u32, u32), ()>(&store, "log")
+        .unwrap();
+    let fill = instance
+        .get_typed_func::<(u32, u32, i32), ()>(&store, "fill")
+        .unwrap();
+    log.call(&mut store, (0, 5)).unwrap();
+    fill.call(&mut store, (1, 3, i32::from(b'-'))).unwrap();
+    log.call(&mut store, (0, 5)).unwrap();
+    assert_eq!(store.data().host.lines, ["hello", "h---o"]);
+    let error = log.call(&mut store, (65_534, 4)).unwrap_err();
+    assert_eq!(error.to_string(), "out of bounds memory access");
+    fill.call(&mut store, (0, 1, 0xFF)).unwrap();
+    let error = log.call(&mut store, (0, 1)).unwrap_err();
+    assert_eq!(error.to_string(), "linear memory contains invalid UTF-8");
+}
+
+#[test]
+fn host_methods_with_named_memory() {
+    let (mut store, instance) = setup(
+        r#"
+        (module
+            (import "shout" "shout" (func $shout (param i32 i32)))
+            (memory (export "mem") 1)
+            (data (i32.const 0) "hello")
+            (func (export "shout") (param i32 i32)
+                (call $shout (local.get 0) (local.get 1))
+            )
+        )
+    "#,
+    );
+    let shout = instance
+        .get_typed_func::<(u32, u32), ()>(&store, "shout")
+        .unwrap();
+    shout.call(&mut store, (0, 5)).unwrap();
+    assert_eq!(store.data().shouter.lines, ["HELLO"]);
+}
+
+#[test]
+fn host_methods_with_indexed_memory() {
+    // The linear memory is not exported but found via its index.
+    let (mut store, instance) = setup(
+        r#"
+        (module
+            (import "whisper" "whisper" (func $whisper (param i32 i32)))
+            (memory 1)
+            (data (i32.const 0) "HELLO")
+            (func (export "whisper") (param i32 i32)
+                (call $whisper (local.get 0) (local.get 1))
+            )
+        )
+    "#,
+    );
+    let whisper = instance
+        .get_typed_func::<(u32, u32), ()>(&store, "whisper")
+        .unwrap();
+    whisper.call(&mut store, (0, 5)).unwrap();
+    assert_eq!(store.data().whisperer.lines, ["hello"]);
+}
+
+#[test]
+fn host_methods_with_missing_memory() {
+    let (mut store, instance) = setup(
+        r#"
+        (module
+            (import "env" "log" (func $log (param i32 i32)))
+            (func (export "log") (param i32 i32)
+                (call $log (local.get 0) (local.get 1))
+            )
+        )
+    "#,
+    );
+    let log = instance
+        .get_typed_func::<(u32, u32), ()>(&store, "log")
+        .unwrap();
+    let error = log.call(&mut store, (0, 0)).unwrap_err();
+    assert_eq!(error.to_string(), "missing required memory export `memory`");
+    let (mut store, instance) = setup(
+        r#"
+        (module
+            (import "whisper" "whisper" (func $whisper (param i32 i32)))
+            (func (export "whisper") (param i32 i32)
+                (call $whisper (local.get 0) (local.get 1))
+            )
+        )
+    "#,
+    );
+    let whisper = instance
+        .get_typed_func::<(u32, u32), ()>(&store, "whisper")
+        .unwrap();
+    let error = whisper.call(&mut store, (0, 0)).unwrap_err();
+    assert_eq!(error.to_string(), "missing required memory at index 0");
+}
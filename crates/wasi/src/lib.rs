@@ -1,14 +1,39 @@
 //! This crate provides support for WASI `preview1` for the `wasmi` interpreter.
 //!
-//! Use [`add_to_linker`] to add all supported WASI definitions to the `wasmi` linker.
+//! Use `add_to_linker` to add all supported WASI definitions to the `wasmi` linker.
+//! The WASI definitions operate on the linear memory exported as `"memory"` by default.
+//! Use `add_to_linker_with_memory` with a [`WasiMemory`] for other linear memories.
+//!
+//! # Crate Features
+//!
+//! - `sync` (default): The WASI implementation based on `wasi-common` and `cap-std`
+//!   including the in-memory virtual filesystem of the [`memfs`] module.
+//! - The [`native`] WASI implementation driven by a [`native::WasiHost`] is always
+//!   available and does not depend on `wasi-common` or `cap-std`.
 
+#[cfg(feature = "sync")]
 mod guest_memory;
+#[cfg(feature = "sync")]
+pub mod memfs;
+mod memory;
+pub mod native;
 
 #[cfg(feature = "sync")]
 pub mod sync;
 
+#[cfg(feature = "sync")]
 pub use self::guest_memory::WasmiGuestMemory;
-pub use wasi_common::{Error, WasiCtx, WasiDir, WasiFile};
+pub use self::memory::WasiMemory;
+#[cfg(feature = "sync")]
+pub use wasi_common::{
+    dir::DirCaps,
+    file::FileCaps,
+    pipe::{ReadPipe, WritePipe},
+    Error,
+    WasiCtx,
+    WasiDir,
+    WasiFile,
+};
 
 /// Sync mode is the "default" of this crate, so we also export it at the top level.
 #[cfg(feature = "sync")]
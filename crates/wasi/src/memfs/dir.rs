@@ -0,0 +1,304 @@
+use super::{is_dir, lock, not_empty, FsState, MemFile, NodeKind};
+use std::{
+    any::Any,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use wasi_common::{
+    dir::{ReaddirCursor, ReaddirEntity},
+    file::{FdFlags, FileType, Filestat, OFlags},
+    Error,
+    ErrorExt,
+    SystemTimeSpec,
+    WasiDir,
+    WasiFile,
+};
+
+/// A handle to a directory of a [`MemFs`](super::MemFs).
+///
+/// Paths are resolved relative to the directory and must not escape it via `..`.
+#[derive(Debug)]
+pub struct MemDir {
+    state: Arc<Mutex<FsState>>,
+    inode: u64,
+}
+
+impl MemDir {
+    /// Creates a new handle to the directory at `inode`.
+    pub(super) fn new(state: Arc<Mutex<FsState>>, inode: u64) -> Self {
+        if let Some(node) = lock(&state).nodes.get_mut(&inode) {
+            node.handles += 1;
+        }
+        Self { state, inode }
+    }
+
+    /// Returns exclusive access to the state of the [`MemFs`](super::MemFs).
+    fn state(&self) -> MutexGuard<'_, FsState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for MemDir {
+    fn drop(&mut self) {
+        let mut state = self.state();
+        if let Some(node) = state.nodes.get_mut(&self.inode) {
+            node.handles -= 1;
+        }
+        state.collect(self.inode);
+    }
+}
+
+/// Returns the [`Filestat`] of the node at `inode`.
+pub(super) fn filestat(state: &FsState, inode: u64) -> Result<Filestat, Error> {
+    let node = state.node(inode)?;
+    let size = match &node.kind {
+        NodeKind::File(contents) => contents.len() as u64,
+        NodeKind::Dir { entries, .. } => entries.len() as u64,
+    };
+    Ok(Filestat {
+        device_id: 0,
+        inode,
+        filetype: node.kind.filetype(),
+        nlink: node.links,
+        size,
+        atim: None,
+        mtim: None,
+        ctim: None,
+    })
+}
+
+#[wiggle::async_trait]
+impl WasiDir for MemDir {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn open_file(
+        &self,
+        _symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<Box<dyn WasiFile>, Error> {
+        if fdflags.intersects(FdFlags::DSYNC | FdFlags::SYNC | FdFlags::RSYNC) {
+            return Err(Error::not_supported().context("SYNC family of FdFlags"));
+        }
+        let mut state = self.state();
+        let (parent, name) = state.walk(self.inode, path)?;
+        let existing = match name {
+            "." => Some(parent),
+            name => state.lookup(parent, name),
+        };
+        let inode = match existing {
+            Some(_) if oflags.contains(OFlags::CREATE | OFlags::EXCLUSIVE) => {
+                return Err(Error::exist())
+            }
+            Some(inode) => {
+                state.contents(inode)?;
+                if oflags.contains(OFlags::TRUNCATE) {
+                    state.resize(inode, 0)?;
+                }
+                inode
+            }
+            None if oflags.contains(OFlags::CREATE) => {
+                state.create(parent, name, NodeKind::File(Vec::new()))?
+            }
+            None => return Err(Error::not_found()),
+        };
+        drop(state);
+        let append = fdflags.contains(FdFlags::APPEND);
+        Ok(Box::new(MemFile::new(
+            self.state.clone(),
+            inode,
+            read || !write,
+            write,
+            append,
+        )))
+    }
+
+    async fn open_dir(&self, _symlink_follow: bool, path: &str) -> Result<Box<dyn WasiDir>, Error> {
+        let inode = {
+            let state = self.state();
+            let inode = state.resolve(self.inode, path)?;
+            state.dir_entries(inode)?;
+            inode
+        };
+        Ok(Box::new(MemDir::new(self.state.clone(), inode)))
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Error> {
+        let mut state = self.state();
+        let (parent, name) = state.resolve_parent(self.inode, path)?;
+        state.create(parent, name, NodeKind::dir(parent))?;
+        Ok(())
+    }
+
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, Error>> + Send>, Error> {
+        let state = self.state();
+        let NodeKind::Dir { parent, entries } = &state.node(self.inode)?.kind else {
+            return Err(Error::not_dir());
+        };
+        let mut dirents = vec![
+            (String::from("."), self.inode, FileType::Directory),
+            (String::from(".."), *parent, FileType::Directory),
+        ];
+        for (name, inode) in entries {
+            let filetype = state.node(*inode)?.kind.filetype();
+            dirents.push((name.clone(), *inode, filetype));
+        }
+        let skip = usize::try_from(u64::from(cursor))?;
+        let entities = dirents
+            .into_iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(index, (name, inode, filetype))| {
+                Ok(ReaddirEntity {
+                    next: ReaddirCursor::from(index as u64 + 1),
+                    inode,
+                    name,
+                    filetype,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(Box::new(entities.into_iter()))
+    }
+
+    async fn symlink(&self, _old_path: &str, _new_path: &str) -> Result<(), Error> {
+        Err(Error::not_supported().context("symbolic links in the in-memory filesystem"))
+    }
+
+    async fn remove_dir(&self, path: &str) -> Result<(), Error> {
+        let mut state = self.state();
+        let (parent, name) = state.resolve_parent(self.inode, path)?;
+        let inode = state.lookup(parent, name).ok_or_else(Error::not_found)?;
+        if !state.dir_entries(inode)?.is_empty() {
+            return Err(not_empty());
+        }
+        state.unlink(parent, name)
+    }
+
+    async fn unlink_file(&self, path: &str) -> Result<(), Error> {
+        let mut state = self.state();
+        let (parent, name) = state.resolve_parent(self.inode, path)?;
+        let inode = state.lookup(parent, name).ok_or_else(Error::not_found)?;
+        state.contents(inode)?;
+        state.unlink(parent, name)
+    }
+
+    async fn read_link(&self, path: &str) -> Result<PathBuf, Error> {
+        self.state().resolve(self.inode, path)?;
+        Err(Error::invalid_argument().context("not a symbolic link"))
+    }
+
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        filestat(&self.state(), self.inode)
+    }
+
+    async fn get_path_filestat(
+        &self,
+        path: &str,
+        _follow_symlinks: bool,
+    ) -> Result<Filestat, Error> {
+        let state = self.state();
+        let inode = state.resolve(self.inode, path)?;
+        filestat(&state, inode)
+    }
+
+    async fn rename(
+        &self,
+        path: &str,
+        dest_dir: &dyn WasiDir,
+        dest_path: &str,
+    ) -> Result<(), Error> {
+        let dest_dir = same_fs(self, dest_dir)?;
+        let mut state = self.state();
+        let (src_parent, src_name) = state.resolve_parent(self.inode, path)?;
+        let (dest_parent, dest_name) = state.resolve_parent(dest_dir.inode, dest_path)?;
+        let inode = state
+            .lookup(src_parent, src_name)
+            .ok_or_else(Error::not_found)?;
+        if (src_parent, src_name) == (dest_parent, dest_name) {
+            return Ok(());
+        }
+        let is_src_dir = state.dir_entries(inode).is_ok();
+        if is_src_dir {
+            // Directories must not be moved into their own subtree.
+            let mut ancestor = dest_parent;
+            loop {
+                if ancestor == inode {
+                    return Err(Error::invalid_argument().context("cannot move into itself"));
+                }
+                match state.node(ancestor)?.kind {
+                    NodeKind::Dir { parent, .. } if parent != ancestor => ancestor = parent,
+                    _ => break,
+                }
+            }
+        }
+        if let Some(existing) = state.lookup(dest_parent, dest_name) {
+            match (is_src_dir, state.dir_entries(existing)) {
+                (true, Ok(entries)) if !entries.is_empty() => return Err(not_empty()),
+                (true, Err(_)) => return Err(Error::not_dir()),
+                (false, Ok(_)) => return Err(is_dir()),
+                _ => {}
+            }
+            state.unlink(dest_parent, dest_name)?;
+        }
+        if let NodeKind::Dir { entries, .. } = &mut state.node_mut(src_parent)?.kind {
+            entries.remove(src_name);
+        }
+        state.link(dest_parent, dest_name, inode)?;
+        if let NodeKind::Dir { parent, .. } = &mut state.node_mut(inode)?.kind {
+            *parent = dest_parent;
+        }
+        Ok(())
+    }
+
+    async fn hard_link(
+        &self,
+        path: &str,
+        target_dir: &dyn WasiDir,
+        target_path: &str,
+    ) -> Result<(), Error> {
+        let target_dir = same_fs(self, target_dir)?;
+        let mut state = self.state();
+        let inode = state.resolve(self.inode, path)?;
+        if state.dir_entries(inode).is_ok() {
+            return Err(Error::perm().context("cannot hard link directories"));
+        }
+        let (parent, name) = state.resolve_parent(target_dir.inode, target_path)?;
+        if state.lookup(parent, name).is_some() {
+            return Err(Error::exist());
+        }
+        state.link(parent, name, inode)?;
+        state.node_mut(inode)?.links += 1;
+        Ok(())
+    }
+
+    async fn set_times(
+        &self,
+        _path: &str,
+        _atime: Option<SystemTimeSpec>,
+        _mtime: Option<SystemTimeSpec>,
+        _follow_symlinks: bool,
+    ) -> Result<(), Error> {
+        Err(Error::not_supported().context("timestamps in the in-memory filesystem"))
+    }
+}
+
+/// Returns `other` as [`MemDir`] if it belongs to the same [`MemFs`](super::MemFs) as `dir`.
+///
+/// # Errors
+///
+/// If `other` is not a [`MemDir`] of the same [`MemFs`](super::MemFs).
+fn same_fs<'a>(dir: &MemDir, other: &'a dyn WasiDir) -> Result<&'a MemDir, Error> {
+    other
+        .as_any()
+        .downcast_ref::<MemDir>()
+        .filter(|other| Arc::ptr_eq(&dir.state, &other.state))
+        .ok_or_else(|| Error::not_supported().context("cross filesystem operation"))
+}
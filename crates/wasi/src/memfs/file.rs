@@ -0,0 +1,226 @@
+use super::{dir::filestat, lock, FsState};
+use std::{
+    any::Any,
+    io::{IoSlice, IoSliceMut, SeekFrom},
+    sync::{Arc, Mutex, MutexGuard},
+};
+use wasi_common::{
+    file::{Advice, FdFlags, FileType, Filestat},
+    Error,
+    ErrorExt,
+    SystemTimeSpec,
+    WasiFile,
+};
+
+/// A handle to an open file of a [`MemFs`](super::MemFs).
+///
+/// The contents of the file stay accessible until the handle is closed even if
+/// the file is removed from its directory in the meantime.
+#[derive(Debug)]
+pub struct MemFile {
+    state: Arc<Mutex<FsState>>,
+    inode: u64,
+    /// The current file position.
+    position: u64,
+    /// Whether the file has been opened for reading.
+    read: bool,
+    /// Whether the file has been opened for writing.
+    write: bool,
+    /// Whether writes always append to the end of the file.
+    append: bool,
+}
+
+impl MemFile {
+    /// Creates a new handle to the file at `inode`.
+    pub(super) fn new(
+        state: Arc<Mutex<FsState>>,
+        inode: u64,
+        read: bool,
+        write: bool,
+        append: bool,
+    ) -> Self {
+        if let Some(node) = lock(&state).nodes.get_mut(&inode) {
+            node.handles += 1;
+        }
+        Self {
+            state,
+            inode,
+            position: 0,
+            read,
+            write,
+            append,
+        }
+    }
+
+    /// Returns exclusive access to the state of the [`MemFs`](super::MemFs).
+    fn state(&self) -> MutexGuard<'_, FsState> {
+        lock(&self.state)
+    }
+
+    /// Reads into `bufs` starting at `offset` and returns the number of bytes read.
+    fn read_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> Result<u64, Error> {
+        if !self.read {
+            return Err(Error::badf());
+        }
+        let state = self.state();
+        let contents = state.contents(self.inode)?;
+        let begin = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(contents.len());
+        let mut end = begin;
+        for buf in bufs {
+            let len = buf.len().min(contents.len() - end);
+            buf[..len].copy_from_slice(&contents[end..end + len]);
+            end += len;
+        }
+        Ok((end - begin) as u64)
+    }
+
+    /// Writes `bufs` starting at `offset` and returns the number of bytes written.
+    fn write_at(&self, bufs: &[IoSlice], offset: u64) -> Result<u64, Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        let mut state = self.state();
+        let mut offset = offset;
+        let mut written = 0;
+        for buf in bufs {
+            state.write(self.inode, offset, buf)?;
+            offset += buf.len() as u64;
+            written += buf.len() as u64;
+        }
+        Ok(written)
+    }
+
+    /// Returns the current size of the file.
+    fn size(&self) -> Result<u64, Error> {
+        Ok(self.state().contents(self.inode)?.len() as u64)
+    }
+}
+
+impl Drop for MemFile {
+    fn drop(&mut self) {
+        let mut state = self.state();
+        if let Some(node) = state.nodes.get_mut(&self.inode) {
+            node.handles -= 1;
+        }
+        state.collect(self.inode);
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiFile for MemFile {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn get_filetype(&mut self) -> Result<FileType, Error> {
+        Ok(FileType::RegularFile)
+    }
+
+    async fn get_fdflags(&mut self) -> Result<FdFlags, Error> {
+        match self.append {
+            true => Ok(FdFlags::APPEND),
+            false => Ok(FdFlags::empty()),
+        }
+    }
+
+    async fn set_fdflags(&mut self, flags: FdFlags) -> Result<(), Error> {
+        if flags.intersects(FdFlags::DSYNC | FdFlags::SYNC | FdFlags::RSYNC) {
+            return Err(Error::not_supported().context("SYNC family of FdFlags"));
+        }
+        self.append = flags.contains(FdFlags::APPEND);
+        Ok(())
+    }
+
+    async fn get_filestat(&mut self) -> Result<Filestat, Error> {
+        filestat(&self.state(), self.inode)
+    }
+
+    async fn set_filestat_size(&mut self, size: u64) -> Result<(), Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        self.state().resize(self.inode, size)
+    }
+
+    async fn advise(&mut self, _offset: u64, _len: u64, _advice: Advice) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn allocate(&mut self, offset: u64, len: u64) -> Result<(), Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        let end = offset.checked_add(len).ok_or_else(Error::overflow)?;
+        if end > self.size()? {
+            self.state().resize(self.inode, end)?;
+        }
+        Ok(())
+    }
+
+    async fn set_times(
+        &mut self,
+        _atime: Option<SystemTimeSpec>,
+        _mtime: Option<SystemTimeSpec>,
+    ) -> Result<(), Error> {
+        Err(Error::not_supported().context("timestamps in the in-memory filesystem"))
+    }
+
+    async fn read_vectored<'a>(&mut self, bufs: &mut [IoSliceMut<'a>]) -> Result<u64, Error> {
+        let read = self.read_at(bufs, self.position)?;
+        self.position += read;
+        Ok(read)
+    }
+
+    async fn read_vectored_at<'a>(
+        &mut self,
+        bufs: &mut [IoSliceMut<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        self.read_at(bufs, offset)
+    }
+
+    async fn write_vectored<'a>(&mut self, bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+        if self.append {
+            self.position = self.size()?;
+        }
+        let written = self.write_at(bufs, self.position)?;
+        self.position += written;
+        Ok(written)
+    }
+
+    async fn write_vectored_at<'a>(
+        &mut self,
+        bufs: &[IoSlice<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        self.write_at(bufs, offset)
+    }
+
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size()?.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(Error::invalid_argument)?;
+        Ok(self.position)
+    }
+
+    async fn peek(&mut self, buf: &mut [u8]) -> Result<u64, Error> {
+        self.read_at(&mut [IoSliceMut::new(buf)], self.position)
+    }
+
+    async fn num_ready_bytes(&self) -> Result<u64, Error> {
+        Ok(self.size()?.saturating_sub(self.position))
+    }
+
+    async fn readable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn writable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,664 @@
+//! An in-memory virtual filesystem that can be pre-opened in place of host directories.
+//!
+//! A [`MemFs`] is populated by the embedder, for example from a map of paths to file
+//! contents via [`MemFs::from_map`] or from a tar archive via [`MemFs::from_tar`],
+//! and then pre-opened for the Wasm guest using [`MemFs::root_dir`]:
+//!
+//! ```
+//! use wasmi_wasi::{memfs::MemFs, WasiCtxBuilder};
+//!
+//! let fs = MemFs::from_map([("config.toml", "answer = 42"), ("data/input.txt", "hello")])?;
+//! let mut wasi_ctx = WasiCtxBuilder::new().build();
+//! wasi_ctx.push_preopened_dir(Box::new(fs.root_dir()), "/")?;
+//! # Ok::<(), wasmi_wasi::Error>(())
+//! ```
+//!
+//! A [`MemFs`] is a cheap handle to shared state so that the embedder can keep a clone
+//! around and inspect the files written by the Wasm guest via [`MemFs::read_file`].
+//!
+//! Symbolic links are not supported.
+
+mod dir;
+mod file;
+mod tar;
+
+pub use self::{dir::MemDir, file::MemFile};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+use wasi_common::{file::FileType, Error, ErrorExt};
+
+/// The default maximum size of a single file in a [`MemFs`] in bytes.
+///
+/// This is 256 MiB.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The default maximum total size of all file contents in a [`MemFs`] in bytes.
+///
+/// This is 512 MiB.
+pub const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+/// An in-memory virtual filesystem.
+///
+/// Cloning a [`MemFs`] yields another handle to the same filesystem.
+#[derive(Debug, Clone, Default)]
+pub struct MemFs {
+    state: Arc<Mutex<FsState>>,
+}
+
+impl MemFs {
+    /// Creates a new empty [`MemFs`] with the default quotas.
+    ///
+    /// # Note
+    ///
+    /// File contents are limited to [`DEFAULT_MAX_FILE_SIZE`] bytes per file
+    /// and [`DEFAULT_MAX_BYTES`] bytes in total since the Wasm guest could
+    /// otherwise make the host allocate arbitrary amounts of memory, for
+    /// example by writing at a huge file offset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`MemFs`] with the `files` given as pairs of path and contents.
+    ///
+    /// Parent directories of the files are created as needed.
+    ///
+    /// # Errors
+    ///
+    /// If any of the paths is invalid or refers to a directory.
+    pub fn from_map<I, P, D>(files: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (P, D)>,
+        P: AsRef<str>,
+        D: Into<Vec<u8>>,
+    {
+        let mut fs = Self::new();
+        for (path, contents) in files {
+            fs.insert_file(path.as_ref(), contents)?;
+        }
+        Ok(fs)
+    }
+
+    /// Creates a new [`MemFs`] with the directories and regular files of the tar `archive`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `archive` is malformed.
+    /// - If the `archive` contains links or other unsupported entries.
+    pub fn from_tar(archive: &[u8]) -> Result<Self, Error> {
+        let mut fs = Self::new();
+        fs.insert_tar(archive)?;
+        Ok(fs)
+    }
+
+    /// Limits the total size of all file contents in the [`MemFs`] to `max_bytes`.
+    ///
+    /// Operations that would exceed the quota fail with `ENOSPC`.
+    /// By default the contents are limited to [`DEFAULT_MAX_BYTES`] bytes.
+    /// Use `u64::MAX` to lift the limit.
+    pub fn max_bytes(&mut self, max_bytes: u64) -> &mut Self {
+        self.state().max_bytes = max_bytes;
+        self
+    }
+
+    /// Limits the size of every single file in the [`MemFs`] to `max_file_size`.
+    ///
+    /// Operations that would exceed the limit fail with `EFBIG`.
+    /// By default files are limited to [`DEFAULT_MAX_FILE_SIZE`] bytes.
+    /// Use `u64::MAX` to lift the limit.
+    pub fn max_file_size(&mut self, max_file_size: u64) -> &mut Self {
+        self.state().max_file_size = max_file_size;
+        self
+    }
+
+    /// Limits the number of files and directories in the [`MemFs`] to `max_entries`.
+    ///
+    /// The root directory does not count towards the quota.
+    /// Operations that would exceed the quota fail with `ENOSPC`.
+    /// By default there is no limit.
+    pub fn max_entries(&mut self, max_entries: u64) -> &mut Self {
+        self.state().max_entries = Some(max_entries);
+        self
+    }
+
+    /// Returns the total size of all file contents in the [`MemFs`].
+    pub fn used_bytes(&self) -> u64 {
+        self.state().used_bytes
+    }
+
+    /// Returns the number of files and directories in the [`MemFs`] excluding the root directory.
+    pub fn used_entries(&self) -> u64 {
+        self.state().used_entries
+    }
+
+    /// Inserts a file with `contents` at `path` replacing the contents of existing files.
+    ///
+    /// Parent directories are created as needed.
+    ///
+    /// # Errors
+    ///
+    /// - If the `path` is invalid or refers to a directory.
+    /// - If a quota is exceeded.
+    pub fn insert_file(&mut self, path: &str, contents: impl Into<Vec<u8>>) -> Result<(), Error> {
+        let mut state = self.state();
+        let (parent, name) = state.create_parents(path)?;
+        let inode = match state.lookup(parent, name) {
+            Some(inode) => inode,
+            None => state.create(parent, name, NodeKind::File(Vec::new()))?,
+        };
+        state.set_contents(inode, contents.into())
+    }
+
+    /// Inserts a directory at `path` including all of its missing parent directories.
+    ///
+    /// # Errors
+    ///
+    /// - If the `path` is invalid or any of its components refers to a file.
+    /// - If a quota is exceeded.
+    pub fn insert_dir(&mut self, path: &str) -> Result<(), Error> {
+        let mut state = self.state();
+        let (parent, name) = state.create_parents(path)?;
+        match state.lookup(parent, name) {
+            Some(inode) => state.dir_entries(inode).map(|_| ()),
+            None => state
+                .create(parent, name, NodeKind::dir(parent))
+                .map(|_| ()),
+        }
+    }
+
+    /// Inserts the directories and regular files of the tar `archive`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `archive` is malformed.
+    /// - If the `archive` contains links or other unsupported entries.
+    /// - If a quota is exceeded.
+    pub fn insert_tar(&mut self, archive: &[u8]) -> Result<(), Error> {
+        for entry in tar::entries(archive)? {
+            match entry.contents {
+                Some(contents) => self.insert_file(&entry.path, contents)?,
+                None => self.insert_dir(&entry.path)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the contents of the file at `path` if any.
+    pub fn read_file(&self, path: &str) -> Option<Vec<u8>> {
+        let state = self.state();
+        let inode = state.resolve(ROOT, path.trim_start_matches('/')).ok()?;
+        state.contents(inode).ok().map(<[u8]>::to_vec)
+    }
+
+    /// Returns a handle to the root directory of the [`MemFs`].
+    ///
+    /// The returned [`MemDir`] can be pre-opened for the Wasm guest.
+    pub fn root_dir(&self) -> MemDir {
+        MemDir::new(self.state.clone(), ROOT)
+    }
+
+    /// Returns exclusive access to the state of the [`MemFs`].
+    fn state(&self) -> MutexGuard<'_, FsState> {
+        lock(&self.state)
+    }
+}
+
+/// Returns exclusive access to the shared `state` of a [`MemFs`].
+///
+/// Since all operations keep the [`FsState`] consistent a poisoned lock is not an error.
+fn lock(state: &Mutex<FsState>) -> MutexGuard<'_, FsState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The inode of the root directory of a [`MemFs`].
+const ROOT: u64 = 1;
+
+/// The state of a [`MemFs`] shared by all of its handles.
+#[derive(Debug)]
+struct FsState {
+    /// The files and directories by their inode.
+    nodes: BTreeMap<u64, Node>,
+    /// The inode of the next created file or directory.
+    next_inode: u64,
+    /// The total size of all file contents.
+    used_bytes: u64,
+    /// The number of files and directories excluding the root directory.
+    used_entries: u64,
+    /// The quota for `used_bytes`.
+    max_bytes: u64,
+    /// The optional quota for `used_entries`.
+    max_entries: Option<u64>,
+    /// The limit for the size of every single file.
+    max_file_size: u64,
+}
+
+impl Default for FsState {
+    fn default() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(
+            ROOT,
+            Node {
+                kind: NodeKind::dir(ROOT),
+                links: 1,
+                handles: 0,
+            },
+        );
+        Self {
+            nodes,
+            next_inode: ROOT + 1,
+            used_bytes: 0,
+            used_entries: 0,
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_entries: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// A file or directory of a [`MemFs`].
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    /// The number of directory entries referring to the [`Node`].
+    links: u64,
+    /// The number of open [`MemDir`] and [`MemFile`] handles referring to the [`Node`].
+    ///
+    /// A [`Node`] without links is kept alive until its last handle is closed.
+    handles: u64,
+}
+
+/// The kind of a [`Node`] and its contents.
+#[derive(Debug)]
+enum NodeKind {
+    /// A regular file with its contents.
+    File(Vec<u8>),
+    /// A directory with the inode of its parent and its entries.
+    Dir {
+        parent: u64,
+        entries: BTreeMap<String, u64>,
+    },
+}
+
+impl NodeKind {
+    /// Creates a new empty directory within the `parent` directory.
+    fn dir(parent: u64) -> Self {
+        Self::Dir {
+            parent,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the [`FileType`] of the [`NodeKind`].
+    fn filetype(&self) -> FileType {
+        match self {
+            Self::File(_) => FileType::RegularFile,
+            Self::Dir { .. } => FileType::Directory,
+        }
+    }
+}
+
+impl FsState {
+    /// Returns the [`Node`] at `inode`.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Node`] no longer exists.
+    fn node(&self, inode: u64) -> Result<&Node, Error> {
+        self.nodes.get(&inode).ok_or_else(Error::not_found)
+    }
+
+    /// Returns the [`Node`] at `inode` mutably.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Node`] no longer exists.
+    fn node_mut(&mut self, inode: u64) -> Result<&mut Node, Error> {
+        self.nodes.get_mut(&inode).ok_or_else(Error::not_found)
+    }
+
+    /// Returns the entries of the directory at `inode`.
+    ///
+    /// # Errors
+    ///
+    /// If `inode` does not refer to a directory.
+    fn dir_entries(&self, inode: u64) -> Result<&BTreeMap<String, u64>, Error> {
+        match &self.node(inode)?.kind {
+            NodeKind::Dir { entries, .. } => Ok(entries),
+            NodeKind::File(_) => Err(Error::not_dir()),
+        }
+    }
+
+    /// Returns the contents of the file at `inode`.
+    ///
+    /// # Errors
+    ///
+    /// If `inode` does not refer to a file.
+    fn contents(&self, inode: u64) -> Result<&[u8], Error> {
+        match &self.node(inode)?.kind {
+            NodeKind::File(contents) => Ok(contents),
+            NodeKind::Dir { .. } => Err(is_dir()),
+        }
+    }
+
+    /// Returns the inode of the entry `name` of the directory `dir` if any.
+    fn lookup(&self, dir: u64, name: &str) -> Option<u64> {
+        self.dir_entries(dir).ok()?.get(name).copied()
+    }
+
+    /// Walks the `components` of a path starting at the directory `dir`.
+    ///
+    /// Returns the inode of the directory containing the last component as well as the
+    /// last component itself which is `"."` for paths that refer to `dir`.
+    ///
+    /// # Errors
+    ///
+    /// - If the path is absolute or escapes `dir` via `..`.
+    /// - If any of the intermediate components does not exist or is not a directory.
+    fn walk<'a>(&self, dir: u64, path: &'a str) -> Result<(u64, &'a str), Error> {
+        if path.starts_with('/') {
+            return Err(Error::perm().context("absolute paths are not allowed"));
+        }
+        let mut components = path.split('/').filter(|c| !c.is_empty() && *c != ".");
+        let Some(mut last) = components.next_back() else {
+            return Ok((dir, "."));
+        };
+        let mut ancestors = vec![dir];
+        for component in components {
+            let current = *ancestors.last().unwrap_or(&dir);
+            if component == ".." {
+                if ancestors.len() == 1 {
+                    return Err(Error::perm().context("path escapes the directory"));
+                }
+                ancestors.pop();
+                continue;
+            }
+            let child = self
+                .lookup(current, component)
+                .ok_or_else(Error::not_found)?;
+            self.dir_entries(child)?;
+            ancestors.push(child);
+        }
+        let mut parent = *ancestors.last().unwrap_or(&dir);
+        if last == ".." {
+            if ancestors.len() == 1 {
+                return Err(Error::perm().context("path escapes the directory"));
+            }
+            ancestors.pop();
+            parent = *ancestors.last().unwrap_or(&dir);
+            last = ".";
+        }
+        Ok((parent, last))
+    }
+
+    /// Resolves `path` relative to the directory `dir` to an inode.
+    ///
+    /// # Errors
+    ///
+    /// If the `path` is invalid or does not exist.
+    fn resolve(&self, dir: u64, path: &str) -> Result<u64, Error> {
+        match self.walk(dir, path)? {
+            (parent, ".") => Ok(parent),
+            (parent, name) => self.lookup(parent, name).ok_or_else(Error::not_found),
+        }
+    }
+
+    /// Resolves the parent directory of `path` relative to the directory `dir`.
+    ///
+    /// Returns the inode of the parent directory and the name of the entry within it.
+    ///
+    /// # Errors
+    ///
+    /// If the `path` is invalid or refers to `dir` itself or one of its ancestors.
+    fn resolve_parent<'a>(&self, dir: u64, path: &'a str) -> Result<(u64, &'a str), Error> {
+        match self.walk(dir, path)? {
+            (_, ".") => Err(Error::invalid_argument().context("path has no file name")),
+            (parent, name) => Ok((parent, name)),
+        }
+    }
+
+    /// Resolves the parent directory of `path` relative to the root directory.
+    ///
+    /// Unlike [`FsState::resolve_parent`] this creates missing parent directories.
+    ///
+    /// # Errors
+    ///
+    /// - If the `path` is invalid.
+    /// - If a quota is exceeded.
+    fn create_parents<'a>(&mut self, path: &'a str) -> Result<(u64, &'a str), Error> {
+        let path = path.trim_start_matches('/');
+        let (parents, name) = path
+            .trim_end_matches('/')
+            .rsplit_once('/')
+            .unwrap_or(("", path.trim_end_matches('/')));
+        let mut parent = ROOT;
+        for component in parents.split('/').filter(|c| !c.is_empty() && *c != ".") {
+            if component == ".." {
+                return Err(Error::invalid_argument().context("path must not contain `..`"));
+            }
+            parent = match self.lookup(parent, component) {
+                Some(inode) => {
+                    self.dir_entries(inode)?;
+                    inode
+                }
+                None => self.create(parent, component, NodeKind::dir(parent))?,
+            };
+        }
+        if matches!(name, "" | "." | "..") {
+            return Err(Error::invalid_argument().context("path has no file name"));
+        }
+        Ok((parent, name))
+    }
+
+    /// Creates the entry `name` of `kind` in the directory `parent` and returns its inode.
+    ///
+    /// # Errors
+    ///
+    /// - If `parent` is not a directory or already contains `name`.
+    /// - If the `max_entries` quota is exceeded.
+    fn create(&mut self, parent: u64, name: &str, kind: NodeKind) -> Result<u64, Error> {
+        if self.dir_entries(parent)?.contains_key(name) {
+            return Err(Error::exist());
+        }
+        if self.max_entries.is_some_and(|max| self.used_entries >= max) {
+            return Err(no_space().context("entry quota of the in-memory filesystem exceeded"));
+        }
+        let inode = self.next_inode;
+        self.next_inode += 1;
+        self.used_entries += 1;
+        self.nodes.insert(
+            inode,
+            Node {
+                kind,
+                links: 1,
+                handles: 0,
+            },
+        );
+        self.link(parent, name, inode)?;
+        Ok(inode)
+    }
+
+    /// Adds the entry `name` referring to `inode` to the directory `parent`.
+    fn link(&mut self, parent: u64, name: &str, inode: u64) -> Result<(), Error> {
+        match &mut self.node_mut(parent)?.kind {
+            NodeKind::Dir { entries, .. } => {
+                entries.insert(name.into(), inode);
+                Ok(())
+            }
+            NodeKind::File(_) => Err(Error::not_dir()),
+        }
+    }
+
+    /// Removes the entry `name` from the directory `parent`.
+    ///
+    /// The [`Node`] of the entry is dropped once it has no links and no open handles.
+    fn unlink(&mut self, parent: u64, name: &str) -> Result<(), Error> {
+        let inode = match &mut self.node_mut(parent)?.kind {
+            NodeKind::Dir { entries, .. } => entries.remove(name).ok_or_else(Error::not_found)?,
+            NodeKind::File(_) => return Err(Error::not_dir()),
+        };
+        self.node_mut(inode)?.links -= 1;
+        self.collect(inode);
+        Ok(())
+    }
+
+    /// Drops the [`Node`] at `inode` if it has neither links nor open handles.
+    fn collect(&mut self, inode: u64) {
+        let Some(node) = self.nodes.get(&inode) else {
+            return;
+        };
+        if node.links > 0 || node.handles > 0 {
+            return;
+        }
+        if let Some(Node {
+            kind: NodeKind::File(contents),
+            ..
+        }) = self.nodes.remove(&inode)
+        {
+            self.used_bytes -= contents.len() as u64;
+        }
+        self.used_entries -= 1;
+    }
+
+    /// Resizes the file at `inode` to `len` bytes filling new bytes with zeros.
+    ///
+    /// # Errors
+    ///
+    /// - If `inode` does not refer to a file.
+    /// - If `len` exceeds the `max_file_size` limit.
+    /// - If the `max_bytes` quota is exceeded or the contents cannot be allocated.
+    fn resize(&mut self, inode: u64, len: u64) -> Result<(), Error> {
+        let old_len = self.contents(inode)?.len() as u64;
+        if len <= old_len {
+            self.used_bytes -= old_len - len;
+            if let NodeKind::File(contents) = &mut self.node_mut(inode)?.kind {
+                contents.truncate(len as usize);
+            }
+            return Ok(());
+        }
+        let len = self.check_file_size(len)?;
+        let delta = len as u64 - old_len;
+        self.reserve_bytes(delta)?;
+        if let NodeKind::File(contents) = &mut self.node_mut(inode)?.kind {
+            if contents.try_reserve_exact(len - contents.len()).is_err() {
+                self.used_bytes -= delta;
+                return Err(no_space().context("failed to allocate file contents"));
+            }
+            contents.resize(len, 0x00);
+        }
+        Ok(())
+    }
+
+    /// Replaces the contents of the file at `inode` with `contents`.
+    ///
+    /// # Errors
+    ///
+    /// - If `inode` does not refer to a file.
+    /// - If `contents` exceed the `max_file_size` limit.
+    /// - If the `max_bytes` quota is exceeded.
+    fn set_contents(&mut self, inode: u64, contents: Vec<u8>) -> Result<(), Error> {
+        self.check_file_size(contents.len() as u64)?;
+        let old_len = self.contents(inode)?.len() as u64;
+        self.used_bytes -= old_len;
+        if let Err(error) = self.reserve_bytes(contents.len() as u64) {
+            self.used_bytes += old_len;
+            return Err(error);
+        }
+        if let NodeKind::File(old_contents) = &mut self.node_mut(inode)?.kind {
+            *old_contents = contents;
+        }
+        Ok(())
+    }
+
+    /// Writes `bytes` to the file at `inode` starting at `offset`.
+    ///
+    /// The file is extended with zeros if `offset` is beyond its end.
+    ///
+    /// # Errors
+    ///
+    /// - If `inode` does not refer to a file.
+    /// - If the file would exceed the `max_file_size` limit.
+    /// - If the `max_bytes` quota is exceeded or the contents cannot be allocated.
+    fn write(&mut self, inode: u64, offset: u64, bytes: &[u8]) -> Result<(), Error> {
+        let end = offset
+            .checked_add(bytes.len() as u64)
+            .ok_or_else(Error::overflow)?;
+        if end > self.contents(inode)?.len() as u64 {
+            self.resize(inode, end)?;
+        }
+        let offset = usize::try_from(offset)?;
+        if let NodeKind::File(contents) = &mut self.node_mut(inode)?.kind {
+            contents[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        Ok(())
+    }
+
+    /// Returns `len` as `usize` if it does not exceed the `max_file_size` limit.
+    ///
+    /// # Errors
+    ///
+    /// If `len` exceeds the `max_file_size` limit or the address space.
+    fn check_file_size(&self, len: u64) -> Result<usize, Error> {
+        if len > self.max_file_size {
+            return Err(file_too_big());
+        }
+        usize::try_from(len).map_err(|_| file_too_big())
+    }
+
+    /// Accounts for `delta` additional bytes of file contents.
+    ///
+    /// # Errors
+    ///
+    /// If the `max_bytes` quota is exceeded.
+    fn reserve_bytes(&mut self, delta: u64) -> Result<(), Error> {
+        let used_bytes = self
+            .used_bytes
+            .checked_add(delta)
+            .ok_or_else(Error::overflow)?;
+        if used_bytes > self.max_bytes {
+            return Err(no_space().context("byte quota of the in-memory filesystem exceeded"));
+        }
+        self.used_bytes = used_bytes;
+        Ok(())
+    }
+}
+
+/// Returns an [`Error`] that maps to the WASI `errno` `code`.
+///
+/// This is used for the `errno` values for which `wasi-common` has no `ErrorKind`.
+#[cfg(unix)]
+fn os_error(code: i32) -> Error {
+    std::io::Error::from_raw_os_error(code).into()
+}
+
+/// Returns the [`Error`] for operations that expect a file but found a directory.
+fn is_dir() -> Error {
+    #[cfg(unix)]
+    return os_error(libc::EISDIR);
+    #[cfg(not(unix))]
+    return Error::perm().context("is a directory");
+}
+
+/// Returns the [`Error`] for removing directories that are not empty.
+fn not_empty() -> Error {
+    #[cfg(unix)]
+    return os_error(libc::ENOTEMPTY);
+    #[cfg(not(unix))]
+    return Error::perm().context("directory not empty");
+}
+
+/// Returns the [`Error`] for files exceeding the `max_file_size` limit.
+fn file_too_big() -> Error {
+    #[cfg(unix)]
+    return os_error(libc::EFBIG);
+    #[cfg(not(unix))]
+    return Error::overflow().context("file too large");
+}
+
+/// Returns the [`Error`] for exceeded quotas.
+fn no_space() -> Error {
+    #[cfg(unix)]
+    return os_error(libc::ENOSPC);
+    #[cfg(not(unix))]
+    return Error::io().context("no space left");
+}
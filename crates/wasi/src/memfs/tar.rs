@@ -0,0 +1,166 @@
+//! A minimal reader for `ustar` tar archives including GNU and PAX long path names.
+
+use wasi_common::{Error, ErrorExt};
+
+/// The size of tar headers and the alignment of entry contents.
+const BLOCK_SIZE: usize = 512;
+
+/// A directory or regular file of a tar archive.
+#[derive(Debug)]
+pub struct Entry<'a> {
+    /// The path of the entry relative to the archive root.
+    pub path: String,
+    /// The contents of a regular file or `None` for a directory.
+    pub contents: Option<&'a [u8]>,
+}
+
+/// Returns the directories and regular files of the tar `archive` in order.
+///
+/// # Errors
+///
+/// - If the `archive` is malformed.
+/// - If the `archive` contains links, devices or other unsupported entries.
+pub fn entries(archive: &[u8]) -> Result<Vec<Entry<'_>>, Error> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    // The path of the next entry as given by a preceding GNU or PAX extension entry.
+    let mut long_path = None;
+    while let Some(header) = archive.get(offset..offset + BLOCK_SIZE) {
+        if header.iter().all(|byte| *byte == 0x00) {
+            break;
+        }
+        verify_checksum(header)?;
+        let size = usize::try_from(parse_octal(&header[124..136])?)?;
+        let start = offset + BLOCK_SIZE;
+        let contents = start
+            .checked_add(size)
+            .and_then(|end| archive.get(start..end))
+            .ok_or_else(|| malformed("entry contents out of bounds"))?;
+        offset = start + size.next_multiple_of(BLOCK_SIZE);
+        let path = match long_path.take() {
+            Some(path) => path,
+            None => header_path(header)?,
+        };
+        match header[156] {
+            b'0' | b'\0' | b'7' => match sanitize(&path)? {
+                path if path.is_empty() => return Err(malformed("file without a name")),
+                path => entries.push(Entry {
+                    path,
+                    contents: Some(contents),
+                }),
+            },
+            // Archives usually contain the archive root itself as `./`.
+            b'5' => match sanitize(&path)? {
+                path if path.is_empty() => {}
+                path => entries.push(Entry {
+                    path,
+                    contents: None,
+                }),
+            },
+            b'L' => long_path = Some(parse_str(contents)?.to_string()),
+            b'x' => long_path = pax_path(contents)?,
+            b'g' => {}
+            kind => {
+                return Err(Error::not_supported().context(format!(
+                    "unsupported tar entry type {:?} for {path:?}",
+                    char::from(kind)
+                )))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns an [`Error`] for a malformed archive with the given `reason`.
+fn malformed(reason: &str) -> Error {
+    Error::invalid_argument().context(format!("malformed tar archive: {reason}"))
+}
+
+/// Verifies the checksum of the tar `header`.
+fn verify_checksum(header: &[u8]) -> Result<(), Error> {
+    let expected = parse_octal(&header[148..156])?;
+    let actual = header
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| match index {
+            148..=155 => u64::from(b' '),
+            _ => u64::from(*byte),
+        })
+        .sum::<u64>();
+    if actual != expected {
+        return Err(malformed("header checksum mismatch"));
+    }
+    Ok(())
+}
+
+/// Parses the NUL or space terminated octal number in `field`.
+fn parse_octal(field: &[u8]) -> Result<u64, Error> {
+    let digits = parse_str(field)?.trim_matches(' ');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(digits, 8).map_err(|_| malformed("invalid octal number"))
+}
+
+/// Parses the NUL terminated string in `field`.
+fn parse_str(field: &[u8]) -> Result<&str, Error> {
+    let len = field
+        .iter()
+        .position(|byte| *byte == 0x00)
+        .unwrap_or(field.len());
+    core::str::from_utf8(&field[..len]).map_err(|_| malformed("path is not valid UTF-8"))
+}
+
+/// Returns the path of the `header` including the `ustar` prefix if any.
+fn header_path(header: &[u8]) -> Result<String, Error> {
+    let name = parse_str(&header[0..100])?;
+    if &header[257..263] != b"ustar\0" {
+        return Ok(name.to_string());
+    }
+    match parse_str(&header[345..500])? {
+        "" => Ok(name.to_string()),
+        prefix => Ok(format!("{prefix}/{name}")),
+    }
+}
+
+/// Returns the `path` record of the PAX extended header `records` if any.
+///
+/// Each record has the form `"<len> <key>=<value>\n"` where `<len>` is the length of
+/// the whole record in bytes.
+fn pax_path(records: &[u8]) -> Result<Option<String>, Error> {
+    let mut records = records;
+    while !records.is_empty() {
+        let space = records
+            .iter()
+            .position(|byte| *byte == b' ')
+            .ok_or_else(|| malformed("invalid PAX record"))?;
+        let len = core::str::from_utf8(&records[..space])
+            .ok()
+            .and_then(|len| len.parse::<usize>().ok())
+            .filter(|len| space < *len && *len <= records.len())
+            .ok_or_else(|| malformed("invalid PAX record length"))?;
+        let record = core::str::from_utf8(&records[space + 1..len])
+            .map_err(|_| malformed("PAX record is not valid UTF-8"))?;
+        if let Some(path) = record.strip_prefix("path=") {
+            return Ok(Some(path.trim_end_matches('\n').to_string()));
+        }
+        records = &records[len..];
+    }
+    Ok(None)
+}
+
+/// Returns `path` relative to the archive root which is empty for the archive root itself.
+///
+/// # Errors
+///
+/// If the `path` escapes the archive root via `..`.
+fn sanitize(path: &str) -> Result<String, Error> {
+    let components = path
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+    if components.contains(&"..") {
+        return Err(malformed("path escapes the archive root").context(path.to_string()));
+    }
+    Ok(components.join("/"))
+}
@@ -0,0 +1,100 @@
+use std::sync::{PoisonError, RwLock};
+use wasmi::{core::Trap, Caller, Extern, Instance, Memory};
+
+/// The maximum number of instances for which the WASI memory is cached.
+const CACHE_CAPACITY: usize = 16;
+
+/// The linear memory of the Wasm guest that the WASI functions operate on.
+///
+/// Defaults to the linear memory exported as `"memory"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasiMemory {
+    /// The linear memory exported under the given name.
+    Export(String),
+    /// The linear memory at the given index of the memory index space of the Wasm guest.
+    ///
+    /// The memory index space starts with the imported linear memories so that
+    /// this also allows to use WASI with imported linear memories that are not re-exported.
+    Index(u32),
+}
+
+impl Default for WasiMemory {
+    fn default() -> Self {
+        Self::Export(String::from("memory"))
+    }
+}
+
+/// Resolves the [`WasiMemory`] of the calling instances of WASI functions.
+///
+/// The resolved [`Memory`] is cached per instance so that WASI calls
+/// do not look up the linear memory export of the Wasm guest every time.
+/// The cache is guarded by a [`RwLock`] so that concurrent WASI calls
+/// of cached instances do not block each other.
+#[derive(Debug)]
+pub(crate) struct MemoryResolver {
+    /// The linear memory that the WASI functions operate on.
+    memory: WasiMemory,
+    /// The resolved linear memories of the most recently resolving instances.
+    ///
+    /// Instances of different stores never compare equal so that the
+    /// cache can be shared by all stores using the same linker.
+    cache: RwLock<Vec<(Instance, Memory)>>,
+}
+
+impl MemoryResolver {
+    /// Creates a new [`MemoryResolver`] for the `memory`.
+    pub fn new(memory: WasiMemory) -> Self {
+        Self {
+            memory,
+            cache: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Returns the [`WasiMemory`] of the instance calling a WASI function.
+    ///
+    /// # Errors
+    ///
+    /// If the WASI function was not called by a Wasm guest or the
+    /// Wasm guest does not provide the [`WasiMemory`].
+    pub fn resolve<T>(&self, caller: &Caller<'_, T>) -> Result<Memory, Trap> {
+        let Some(instance) = caller.instance() else {
+            return Err(self.missing());
+        };
+        let cached = self
+            .cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find_map(|(cached, memory)| (*cached == instance).then_some(*memory));
+        if let Some(memory) = cached {
+            return Ok(memory);
+        }
+        let memory = match &self.memory {
+            WasiMemory::Export(name) => match caller.get_export(name) {
+                Some(Extern::Memory(memory)) => Some(memory),
+                _ => None,
+            },
+            WasiMemory::Index(index) => caller.get_memory(*index),
+        }
+        .ok_or_else(|| self.missing())?;
+        let mut cache = self.cache.write().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() == CACHE_CAPACITY {
+            cache.remove(0);
+        }
+        cache.push((instance, memory));
+        Ok(memory)
+    }
+
+    /// Returns a [`Trap`] signalling that the Wasm guest does not provide the [`WasiMemory`].
+    #[cold]
+    fn missing(&self) -> Trap {
+        match &self.memory {
+            WasiMemory::Export(name) => {
+                Trap::new(format!("missing required WASI memory export `{name}`"))
+            }
+            WasiMemory::Index(index) => {
+                Trap::new(format!("missing required WASI memory at index {index}"))
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use super::types::{
+    ClockId,
+    Dirent,
+    Errno,
+    Fd,
+    FdFlags,
+    Fdstat,
+    Filestat,
+    LookupFlags,
+    OFlags,
+    Rights,
+    Timestamp,
+    Whence,
+};
+
+/// The host environment of the native WASI `preview1` implementation.
+///
+/// The native WASI functions decode their arguments from the memory of the Wasm guest,
+/// forward the request to the [`WasiHost`] and encode its answer back into the memory
+/// of the Wasm guest. The [`WasiHost`] therefore decides what the Wasm guest can observe
+/// of its filesystem, clocks, randomness and standard I/O.
+///
+/// All methods have default implementations so that hosts only need to implement
+/// what they want to support:
+///
+/// - Operations on file descriptors and paths fail with [`Errno::Badf`] by default
+///   since there are no open file descriptors.
+/// - Clocks and randomness fail with [`Errno::Nosys`] by default.
+///
+/// # Note
+///
+/// By convention the file descriptors 0, 1 and 2 are standard input, output and error
+/// while the following file descriptors are the pre-opened directories for which
+/// [`WasiHost::fd_prestat_dir_name`] succeeds.
+pub trait WasiHost {
+    /// Returns the command line arguments of the Wasm guest.
+    fn args(&self) -> &[String] {
+        &[]
+    }
+
+    /// Returns the environment variables of the Wasm guest as key-value pairs.
+    fn env(&self) -> &[(String, String)] {
+        &[]
+    }
+
+    /// Returns the resolution of the clock `id`.
+    fn clock_res_get(&mut self, id: ClockId) -> Result<Timestamp, Errno> {
+        let _ = id;
+        Err(Errno::Nosys)
+    }
+
+    /// Returns the time of the clock `id`.
+    ///
+    /// The `precision` is the maximum lag that the Wasm guest accepts.
+    fn clock_time_get(&mut self, id: ClockId, precision: Timestamp) -> Result<Timestamp, Errno> {
+        let _ = (id, precision);
+        Err(Errno::Nosys)
+    }
+
+    /// Fills the `buffer` with random bytes.
+    fn random_get(&mut self, buffer: &mut [u8]) -> Result<(), Errno> {
+        let _ = buffer;
+        Err(Errno::Nosys)
+    }
+
+    /// Yields execution of the calling thread.
+    fn sched_yield(&mut self) -> Result<(), Errno> {
+        Ok(())
+    }
+
+    /// Reads from `fd` into `buffer` and returns the number of bytes read.
+    ///
+    /// Returning fewer bytes than the `buffer` can hold stops reading further buffers.
+    fn fd_read(&mut self, fd: Fd, buffer: &mut [u8]) -> Result<usize, Errno> {
+        let _ = (fd, buffer);
+        Err(Errno::Badf)
+    }
+
+    /// Reads from `fd` at `offset` into `buffer` without changing the file position.
+    fn fd_pread(&mut self, fd: Fd, buffer: &mut [u8], offset: u64) -> Result<usize, Errno> {
+        let _ = (fd, buffer, offset);
+        Err(Errno::Badf)
+    }
+
+    /// Writes `buffer` to `fd` and returns the number of bytes written.
+    ///
+    /// Writing fewer bytes than the `buffer` holds stops writing further buffers.
+    fn fd_write(&mut self, fd: Fd, buffer: &[u8]) -> Result<usize, Errno> {
+        let _ = (fd, buffer);
+        Err(Errno::Badf)
+    }
+
+    /// Writes `buffer` to `fd` at `offset` without changing the file position.
+    fn fd_pwrite(&mut self, fd: Fd, buffer: &[u8], offset: u64) -> Result<usize, Errno> {
+        let _ = (fd, buffer, offset);
+        Err(Errno::Badf)
+    }
+
+    /// Moves the file position of `fd` by `offset` relative to `whence`.
+    ///
+    /// Returns the new file position.
+    fn fd_seek(&mut self, fd: Fd, offset: i64, whence: Whence) -> Result<u64, Errno> {
+        let _ = (fd, offset, whence);
+        Err(Errno::Badf)
+    }
+
+    /// Closes `fd`.
+    fn fd_close(&mut self, fd: Fd) -> Result<(), Errno> {
+        let _ = fd;
+        Err(Errno::Badf)
+    }
+
+    /// Synchronizes the data and metadata of `fd` with the storage device.
+    fn fd_sync(&mut self, fd: Fd) -> Result<(), Errno> {
+        let _ = fd;
+        Err(Errno::Badf)
+    }
+
+    /// Returns the attributes of `fd`.
+    fn fd_fdstat_get(&mut self, fd: Fd) -> Result<Fdstat, Errno> {
+        let _ = fd;
+        Err(Errno::Badf)
+    }
+
+    /// Returns the attributes of the file opened as `fd`.
+    fn fd_filestat_get(&mut self, fd: Fd) -> Result<Filestat, Errno> {
+        let _ = fd;
+        Err(Errno::Badf)
+    }
+
+    /// Returns the guest path of the pre-opened directory `fd`.
+    ///
+    /// Fails with [`Errno::Badf`] if `fd` is not a pre-opened directory.
+    fn fd_prestat_dir_name(&mut self, fd: Fd) -> Result<String, Errno> {
+        let _ = fd;
+        Err(Errno::Badf)
+    }
+
+    /// Returns the entries of the directory `fd` starting at the entry identified by `cookie`.
+    ///
+    /// The first entry has the `cookie` 0.
+    fn fd_readdir(&mut self, fd: Fd, cookie: u64) -> Result<Vec<Dirent>, Errno> {
+        let _ = (fd, cookie);
+        Err(Errno::Badf)
+    }
+
+    /// Opens the file at `path` relative to the directory `fd` and returns its file descriptor.
+    #[allow(clippy::too_many_arguments)]
+    fn path_open(
+        &mut self,
+        fd: Fd,
+        lookup_flags: LookupFlags,
+        path: &str,
+        oflags: OFlags,
+        rights_base: Rights,
+        rights_inheriting: Rights,
+        fdflags: FdFlags,
+    ) -> Result<Fd, Errno> {
+        let _ = (
+            fd,
+            lookup_flags,
+            path,
+            oflags,
+            rights_base,
+            rights_inheriting,
+            fdflags,
+        );
+        Err(Errno::Badf)
+    }
+
+    /// Returns the attributes of the file at `path` relative to the directory `fd`.
+    fn path_filestat_get(
+        &mut self,
+        fd: Fd,
+        lookup_flags: LookupFlags,
+        path: &str,
+    ) -> Result<Filestat, Errno> {
+        let _ = (fd, lookup_flags, path);
+        Err(Errno::Badf)
+    }
+
+    /// Creates a directory at `path` relative to the directory `fd`.
+    fn path_create_directory(&mut self, fd: Fd, path: &str) -> Result<(), Errno> {
+        let _ = (fd, path);
+        Err(Errno::Badf)
+    }
+
+    /// Removes the empty directory at `path` relative to the directory `fd`.
+    fn path_remove_directory(&mut self, fd: Fd, path: &str) -> Result<(), Errno> {
+        let _ = (fd, path);
+        Err(Errno::Badf)
+    }
+
+    /// Removes the file at `path` relative to the directory `fd`.
+    fn path_unlink_file(&mut self, fd: Fd, path: &str) -> Result<(), Errno> {
+        let _ = (fd, path);
+        Err(Errno::Badf)
+    }
+
+    /// Renames the file at `old_path` relative to `old_fd` to `new_path` relative to `new_fd`.
+    fn path_rename(
+        &mut self,
+        old_fd: Fd,
+        old_path: &str,
+        new_fd: Fd,
+        new_path: &str,
+    ) -> Result<(), Errno> {
+        let _ = (old_fd, old_path, new_fd, new_path);
+        Err(Errno::Badf)
+    }
+}
@@ -0,0 +1,35 @@
+//! A native WASI `preview1` implementation driven by a [`WasiHost`].
+//!
+//! Unlike the `sync` implementation this does not depend on `wasi-common`,
+//! `cap-std` or `wiggle`. Instead all WASI functions are implemented on top of the
+//! [`WasiHost`] trait which is provided by the embedder and decides which filesystem,
+//! clocks, randomness and standard I/O the Wasm guest can observe.
+//!
+//! Use [`add_to_linker`] to add all WASI definitions to the `wasmi` linker.
+
+mod host;
+mod preview_1;
+mod types;
+
+pub use self::{
+    host::WasiHost,
+    preview_1::{add_to_linker, add_to_linker_with_memory},
+    types::{
+        fdflags,
+        lookupflags,
+        oflags,
+        ClockId,
+        Dirent,
+        Errno,
+        Fd,
+        FdFlags,
+        Fdstat,
+        Filestat,
+        Filetype,
+        LookupFlags,
+        OFlags,
+        Rights,
+        Timestamp,
+        Whence,
+    },
+};
@@ -0,0 +1,669 @@
+use super::{
+    types::{ClockId, Errno, Whence},
+    WasiHost,
+};
+use crate::{memory::MemoryResolver, WasiMemory};
+use std::sync::Arc;
+use wasmi::{core::Trap, errors::LinkerError, Caller, GuestMemory, Linker, WasmPtr};
+
+/// The Wasm module name under which the WASI `preview1` functions are imported.
+const MODULE: &str = "wasi_snapshot_preview1";
+
+/// The maximum length in bytes of the paths passed to the native WASI functions.
+const MAX_PATH_LEN: u32 = 4096;
+
+/// The result of a native WASI function.
+type WasiResult = Result<(), Errno>;
+
+// Creates the function item `add_to_linker` which when called adds all
+// natively implemented and all unsupported `wasi preview_1` functions to the linker.
+macro_rules! impl_add_to_linker_for_funcs {
+    (
+        supported {
+            $( fn $fname:ident ($( $arg:ident : $typ:ty ),* $(,)? ); )*
+        }
+        unsupported {
+            $( fn $uname:ident ($( $uarg:ident : $utyp:ty ),* $(,)? ); )*
+        }
+    ) => {
+        /// Adds the native `WASI preview_1` implementation to the [`Linker`].
+        ///
+        /// All WASI functions forward their requests to the [`WasiHost`] returned by `wasi_ctx`.
+        ///
+        /// # Note
+        ///
+        /// - WASI functions that are not supported by the native implementation,
+        ///   such as `poll_oneoff` and all socket functions, are defined but
+        ///   always return [`Errno::Nosys`] to the Wasm guest.
+        /// - `proc_exit` traps with the given exit status.
+        ///
+        /// - The WASI functions operate on the linear memory exported as `"memory"`.
+        ///   Use [`add_to_linker_with_memory`] for other linear memories.
+        ///
+        /// # Errors
+        ///
+        /// If any of the WASI functions is already defined in the [`Linker`].
+        pub fn add_to_linker<T, H>(
+            linker: &mut Linker<T>,
+            wasi_ctx: impl Fn(&mut T) -> &mut H + Send + Sync + Copy + 'static,
+        ) -> Result<(), LinkerError>
+        where
+            H: WasiHost,
+        {
+            add_to_linker_with_memory(linker, WasiMemory::default(), wasi_ctx)
+        }
+
+        /// Adds the native `WASI preview_1` implementation operating on the given [`WasiMemory`] to the [`Linker`].
+        ///
+        /// See [`add_to_linker`] for more information.
+        ///
+        /// # Errors
+        ///
+        /// If any of the WASI functions is already defined in the [`Linker`].
+        pub fn add_to_linker_with_memory<T, H>(
+            linker: &mut Linker<T>,
+            memory: WasiMemory,
+            wasi_ctx: impl Fn(&mut T) -> &mut H + Send + Sync + Copy + 'static,
+        ) -> Result<(), LinkerError>
+        where
+            H: WasiHost,
+        {
+            let resolver = Arc::new(MemoryResolver::new(memory));
+            $(
+                let memory_resolver = resolver.clone();
+                linker.func_wrap(
+                    MODULE,
+                    stringify!($fname),
+                    move |mut caller: Caller<'_, T>, $($arg : $typ,)*| -> Result<i32, Trap> {
+                        call_wasi(&mut caller, &memory_resolver, wasi_ctx, |memory, host| {
+                            $fname(memory, host, $($arg,)*)
+                        })
+                    },
+                )?;
+            )*
+            $(
+                linker.func_wrap(
+                    MODULE,
+                    stringify!($uname),
+                    |$(_: $utyp,)*| -> i32 { i32::from(Errno::Nosys.code()) },
+                )?;
+            )*
+            linker.func_wrap(MODULE, "proc_exit", |status: i32| -> Result<(), Trap> {
+                Err(Trap::i32_exit(status))
+            })?;
+            Ok(())
+        }
+    };
+}
+
+impl_add_to_linker_for_funcs! {
+    supported {
+        fn args_get(argv: i32, argv_buf: i32);
+        fn args_sizes_get(argc: i32, argv_buf_size: i32);
+        fn environ_get(environ: i32, environ_buf: i32);
+        fn environ_sizes_get(environc: i32, environ_buf_size: i32);
+        fn clock_res_get(id: i32, resolution: i32);
+        fn clock_time_get(id: i32, precision: i64, time: i32);
+        fn fd_close(fd: i32);
+        fn fd_datasync(fd: i32);
+        fn fd_fdstat_get(fd: i32, stat: i32);
+        fn fd_filestat_get(fd: i32, stat: i32);
+        fn fd_pread(fd: i32, iovs: i32, iovs_len: i32, offset: i64, nread: i32);
+        fn fd_prestat_get(fd: i32, prestat: i32);
+        fn fd_prestat_dir_name(fd: i32, path: i32, path_len: i32);
+        fn fd_pwrite(fd: i32, iovs: i32, iovs_len: i32, offset: i64, nwritten: i32);
+        fn fd_read(fd: i32, iovs: i32, iovs_len: i32, nread: i32);
+        fn fd_readdir(fd: i32, buf: i32, buf_len: i32, cookie: i64, bufused: i32);
+        fn fd_seek(fd: i32, offset: i64, whence: i32, new_offset: i32);
+        fn fd_sync(fd: i32);
+        fn fd_tell(fd: i32, offset: i32);
+        fn fd_write(fd: i32, iovs: i32, iovs_len: i32, nwritten: i32);
+        fn path_create_directory(fd: i32, path: i32, path_len: i32);
+        fn path_filestat_get(fd: i32, flags: i32, path: i32, path_len: i32, stat: i32);
+        fn path_open(
+            fd: i32,
+            dirflags: i32,
+            path: i32,
+            path_len: i32,
+            oflags: i32,
+            rights_base: i64,
+            rights_inheriting: i64,
+            fdflags: i32,
+            opened_fd: i32,
+        );
+        fn path_remove_directory(fd: i32, path: i32, path_len: i32);
+        fn path_rename(
+            fd: i32,
+            old_path: i32,
+            old_path_len: i32,
+            new_fd: i32,
+            new_path: i32,
+            new_path_len: i32,
+        );
+        fn path_unlink_file(fd: i32, path: i32, path_len: i32);
+        fn random_get(buf: i32, buf_len: i32);
+        fn sched_yield();
+    }
+    unsupported {
+        fn fd_advise(fd: i32, offset: i64, len: i64, advice: i32);
+        fn fd_allocate(fd: i32, offset: i64, len: i64);
+        fn fd_fdstat_set_flags(fd: i32, flags: i32);
+        fn fd_fdstat_set_rights(fd: i32, rights_base: i64, rights_inheriting: i64);
+        fn fd_filestat_set_size(fd: i32, size: i64);
+        fn fd_filestat_set_times(fd: i32, atim: i64, mtim: i64, fst_flags: i32);
+        fn fd_renumber(fd: i32, to: i32);
+        fn path_filestat_set_times(
+            fd: i32,
+            flags: i32,
+            path: i32,
+            path_len: i32,
+            atim: i64,
+            mtim: i64,
+            fst_flags: i32,
+        );
+        fn path_link(
+            old_fd: i32,
+            old_flags: i32,
+            old_path: i32,
+            old_path_len: i32,
+            new_fd: i32,
+            new_path: i32,
+            new_path_len: i32,
+        );
+        fn path_readlink(fd: i32, path: i32, path_len: i32, buf: i32, buf_len: i32, bufused: i32);
+        fn path_symlink(old_path: i32, old_path_len: i32, fd: i32, new_path: i32, new_path_len: i32);
+        fn poll_oneoff(subscriptions: i32, events: i32, nsubscriptions: i32, nevents: i32);
+        fn proc_raise(sig: i32);
+        fn sock_accept(fd: i32, flags: i32, new_fd: i32);
+        fn sock_recv(fd: i32, iovs: i32, iovs_len: i32, ri_flags: i32, nread: i32, ro_flags: i32);
+        fn sock_send(fd: i32, iovs: i32, iovs_len: i32, si_flags: i32, nwritten: i32);
+        fn sock_shutdown(fd: i32, how: i32);
+    }
+}
+
+/// Calls the native WASI function `f` with the memory of the Wasm guest and the [`WasiHost`].
+///
+/// Returns the WASI error code of `f` to the Wasm guest.
+///
+/// # Errors
+///
+/// If the Wasm guest does not provide the linear memory resolved by the `resolver`.
+fn call_wasi<T, H>(
+    caller: &mut Caller<'_, T>,
+    resolver: &MemoryResolver,
+    wasi_ctx: impl Fn(&mut T) -> &mut H,
+    f: impl FnOnce(&mut GuestMemory, &mut H) -> WasiResult,
+) -> Result<i32, Trap>
+where
+    H: WasiHost,
+{
+    let memory = resolver.resolve(caller)?;
+    let (memory, ctx) = memory.data_and_store_mut(caller);
+    let mut memory = GuestMemory::new(memory);
+    match f(&mut memory, wasi_ctx(ctx)) {
+        Ok(()) => Ok(0),
+        Err(errno) => Ok(i32::from(errno.code())),
+    }
+}
+
+/// Returns the UTF-8 encoded path of `path_len` bytes at `path`.
+///
+/// Fails with [`Errno::Nametoolong`] if the path is longer than [`MAX_PATH_LEN`] bytes.
+fn read_path<'a>(memory: &'a GuestMemory, path: i32, path_len: i32) -> Result<&'a str, Errno> {
+    let path = WasmPtr::new(path as u32).slice(path_len as u32);
+    memory.read_str(path, MAX_PATH_LEN).map_err(Errno::from)
+}
+
+/// Converts the WASI function argument `value` into the integer type `T`.
+///
+/// Fails with [`Errno::Inval`] if `value` is out of bounds for `T`.
+fn convert<T, U>(value: U) -> Result<T, Errno>
+where
+    T: TryFrom<U>,
+{
+    T::try_from(value).map_err(|_| Errno::Inval)
+}
+
+/// Writes the NUL terminated `strings` to `buf` and pointers to them to `ptrs`.
+fn write_strings<'a>(
+    memory: &mut GuestMemory,
+    strings: impl IntoIterator<Item = &'a [u8]>,
+    mut ptrs: u32,
+    mut buf: u32,
+) -> WasiResult {
+    for string in strings {
+        memory.write_ptr(WasmPtr::new(ptrs), buf)?;
+        memory.write(buf, string)?;
+        let len = u32::try_from(string.len()).map_err(|_| Errno::Overflow)?;
+        let nul = buf.checked_add(len).ok_or(Errno::Fault)?;
+        memory.write(nul, &[0x00])?;
+        ptrs = ptrs.checked_add(4).ok_or(Errno::Fault)?;
+        buf = nul.checked_add(1).ok_or(Errno::Fault)?;
+    }
+    Ok(())
+}
+
+/// Writes the number of `strings` to `count` and their NUL terminated size to `buf_size`.
+fn write_strings_sizes<'a>(
+    memory: &mut GuestMemory,
+    strings: impl IntoIterator<Item = &'a [u8]>,
+    count: u32,
+    buf_size: u32,
+) -> WasiResult {
+    let (len, size) = strings
+        .into_iter()
+        .fold((0_usize, 0_usize), |(len, size), string| {
+            (len + 1, size + string.len() + 1)
+        });
+    let len = u32::try_from(len).map_err(|_| Errno::Overflow)?;
+    let size = u32::try_from(size).map_err(|_| Errno::Overflow)?;
+    memory.write_ptr(WasmPtr::new(count), len)?;
+    memory
+        .write_ptr(WasmPtr::new(buf_size), size)
+        .map_err(Errno::from)
+}
+
+/// Returns the environment variables of the [`WasiHost`] as `KEY=VALUE` strings.
+fn environ(host: &impl WasiHost) -> Vec<String> {
+    host.env()
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect()
+}
+
+fn args_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    argv: i32,
+    argv_buf: i32,
+) -> WasiResult {
+    let args = host.args().iter().map(String::as_bytes);
+    write_strings(memory, args, argv as u32, argv_buf as u32)
+}
+
+fn args_sizes_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    argc: i32,
+    argv_buf_size: i32,
+) -> WasiResult {
+    let args = host.args().iter().map(String::as_bytes);
+    write_strings_sizes(memory, args, argc as u32, argv_buf_size as u32)
+}
+
+fn environ_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    environ_ptrs: i32,
+    environ_buf: i32,
+) -> WasiResult {
+    let environ = environ(host);
+    let environ = environ.iter().map(String::as_bytes);
+    write_strings(memory, environ, environ_ptrs as u32, environ_buf as u32)
+}
+
+fn environ_sizes_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    environc: i32,
+    environ_buf_size: i32,
+) -> WasiResult {
+    let environ = environ(host);
+    let environ = environ.iter().map(String::as_bytes);
+    write_strings_sizes(memory, environ, environc as u32, environ_buf_size as u32)
+}
+
+fn clock_res_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    id: i32,
+    resolution: i32,
+) -> WasiResult {
+    let value = host.clock_res_get(ClockId::try_from(id)?)?;
+    memory
+        .write_ptr(WasmPtr::new(resolution as u32), value)
+        .map_err(Errno::from)
+}
+
+fn clock_time_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    id: i32,
+    precision: i64,
+    time: i32,
+) -> WasiResult {
+    let value = host.clock_time_get(ClockId::try_from(id)?, precision as u64)?;
+    memory
+        .write_ptr(WasmPtr::new(time as u32), value)
+        .map_err(Errno::from)
+}
+
+fn fd_close(_memory: &mut GuestMemory, host: &mut impl WasiHost, fd: i32) -> WasiResult {
+    host.fd_close(fd as u32)
+}
+
+fn fd_datasync(_memory: &mut GuestMemory, host: &mut impl WasiHost, fd: i32) -> WasiResult {
+    host.fd_sync(fd as u32)
+}
+
+fn fd_fdstat_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    stat: i32,
+) -> WasiResult {
+    let fdstat = host.fd_fdstat_get(fd as u32)?;
+    memory
+        .write(stat as u32, &fdstat.encode())
+        .map_err(Errno::from)
+}
+
+fn fd_filestat_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    stat: i32,
+) -> WasiResult {
+    let filestat = host.fd_filestat_get(fd as u32)?;
+    memory
+        .write(stat as u32, &filestat.encode())
+        .map_err(Errno::from)
+}
+
+/// Returns the `[buf, buf_len]` pairs of the `iovs_len` I/O vectors at `iovs`.
+fn iovecs(memory: &GuestMemory, iovs: i32, iovs_len: i32) -> Result<Vec<[u32; 2]>, Errno> {
+    let iovs = WasmPtr::<[u32; 2]>::new(iovs as u32).slice(iovs_len as u32);
+    memory.read_slice(iovs).map_err(Errno::from)
+}
+
+/// Reads from `fd` into the I/O vectors using `read` and writes the total bytes read to `nread`.
+///
+/// Reading stops at the first I/O vector that has not been filled entirely.
+fn read_iovecs(
+    memory: &mut GuestMemory,
+    iovs: i32,
+    iovs_len: i32,
+    nread: i32,
+    mut read: impl FnMut(&mut [u8], u64) -> Result<usize, Errno>,
+) -> WasiResult {
+    let mut total = 0_u32;
+    for [buf, buf_len] in iovecs(memory, iovs, iovs_len)? {
+        let len = read(memory.slice_mut(buf, buf_len)?, u64::from(total))?;
+        let len = u32::try_from(len).map_or(buf_len, |len| len.min(buf_len));
+        total = total.saturating_add(len);
+        if len < buf_len {
+            break;
+        }
+    }
+    memory
+        .write_ptr(WasmPtr::new(nread as u32), total)
+        .map_err(Errno::from)
+}
+
+/// Writes the I/O vectors to `fd` using `write` and writes the total bytes written to `nwritten`.
+///
+/// Writing stops at the first I/O vector that has not been written entirely.
+fn write_iovecs(
+    memory: &mut GuestMemory,
+    iovs: i32,
+    iovs_len: i32,
+    nwritten: i32,
+    mut write: impl FnMut(&[u8], u64) -> Result<usize, Errno>,
+) -> WasiResult {
+    let mut total = 0_u32;
+    for [buf, buf_len] in iovecs(memory, iovs, iovs_len)? {
+        let len = write(memory.slice(buf, buf_len)?, u64::from(total))?;
+        let len = u32::try_from(len).map_or(buf_len, |len| len.min(buf_len));
+        total = total.saturating_add(len);
+        if len < buf_len {
+            break;
+        }
+    }
+    memory
+        .write_ptr(WasmPtr::new(nwritten as u32), total)
+        .map_err(Errno::from)
+}
+
+fn fd_pread(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    offset: i64,
+    nread: i32,
+) -> WasiResult {
+    read_iovecs(memory, iovs, iovs_len, nread, |buffer, total| {
+        let offset = (offset as u64).checked_add(total).ok_or(Errno::Overflow)?;
+        host.fd_pread(fd as u32, buffer, offset)
+    })
+}
+
+fn fd_prestat_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    prestat: i32,
+) -> WasiResult {
+    let name = host.fd_prestat_dir_name(fd as u32)?;
+    let name_len = u32::try_from(name.len()).map_err(|_| Errno::Nametoolong)?;
+    // The tag 0 denotes a pre-opened directory followed by the length of its name.
+    let mut encoded = [0x00; 8];
+    encoded[4..8].copy_from_slice(&name_len.to_le_bytes());
+    memory.write(prestat as u32, &encoded).map_err(Errno::from)
+}
+
+fn fd_prestat_dir_name(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    path: i32,
+    path_len: i32,
+) -> WasiResult {
+    let name = host.fd_prestat_dir_name(fd as u32)?;
+    if name.len() > path_len as u32 as usize {
+        return Err(Errno::Nametoolong);
+    }
+    memory
+        .write(path as u32, name.as_bytes())
+        .map_err(Errno::from)
+}
+
+fn fd_pwrite(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    offset: i64,
+    nwritten: i32,
+) -> WasiResult {
+    write_iovecs(memory, iovs, iovs_len, nwritten, |buffer, total| {
+        let offset = (offset as u64).checked_add(total).ok_or(Errno::Overflow)?;
+        host.fd_pwrite(fd as u32, buffer, offset)
+    })
+}
+
+fn fd_read(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    nread: i32,
+) -> WasiResult {
+    read_iovecs(memory, iovs, iovs_len, nread, |buffer, _| {
+        host.fd_read(fd as u32, buffer)
+    })
+}
+
+fn fd_readdir(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    buf: i32,
+    buf_len: i32,
+    cookie: i64,
+    bufused: i32,
+) -> WasiResult {
+    let buf_len = buf_len as u32 as usize;
+    let mut encoded = Vec::new();
+    for dirent in host.fd_readdir(fd as u32, cookie as u64)? {
+        encoded.extend(dirent.encode());
+        if encoded.len() >= buf_len {
+            break;
+        }
+    }
+    // The Wasm guest detects the end of the directory by a partially filled buffer.
+    // Therefore the last directory entry is truncated if it does not fit.
+    encoded.truncate(buf_len);
+    memory.write(buf as u32, &encoded)?;
+    memory
+        .write_ptr(WasmPtr::new(bufused as u32), encoded.len() as u32)
+        .map_err(Errno::from)
+}
+
+fn fd_seek(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    offset: i64,
+    whence: i32,
+    new_offset: i32,
+) -> WasiResult {
+    let position = host.fd_seek(fd as u32, offset, Whence::try_from(whence)?)?;
+    memory
+        .write_ptr(WasmPtr::new(new_offset as u32), position)
+        .map_err(Errno::from)
+}
+
+fn fd_sync(_memory: &mut GuestMemory, host: &mut impl WasiHost, fd: i32) -> WasiResult {
+    host.fd_sync(fd as u32)
+}
+
+fn fd_tell(memory: &mut GuestMemory, host: &mut impl WasiHost, fd: i32, offset: i32) -> WasiResult {
+    let position = host.fd_seek(fd as u32, 0, Whence::Cur)?;
+    memory
+        .write_ptr(WasmPtr::new(offset as u32), position)
+        .map_err(Errno::from)
+}
+
+fn fd_write(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    nwritten: i32,
+) -> WasiResult {
+    write_iovecs(memory, iovs, iovs_len, nwritten, |buffer, _| {
+        host.fd_write(fd as u32, buffer)
+    })
+}
+
+fn path_create_directory(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    path: i32,
+    path_len: i32,
+) -> WasiResult {
+    let path = read_path(memory, path, path_len)?;
+    host.path_create_directory(fd as u32, path)
+}
+
+fn path_filestat_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    flags: i32,
+    path: i32,
+    path_len: i32,
+    stat: i32,
+) -> WasiResult {
+    let path = read_path(memory, path, path_len)?;
+    let filestat = host.path_filestat_get(fd as u32, flags as u32, path)?;
+    memory
+        .write(stat as u32, &filestat.encode())
+        .map_err(Errno::from)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn path_open(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    dirflags: i32,
+    path: i32,
+    path_len: i32,
+    oflags: i32,
+    rights_base: i64,
+    rights_inheriting: i64,
+    fdflags: i32,
+    opened_fd: i32,
+) -> WasiResult {
+    let path = read_path(memory, path, path_len)?;
+    let opened = host.path_open(
+        fd as u32,
+        dirflags as u32,
+        path,
+        convert(oflags)?,
+        rights_base as u64,
+        rights_inheriting as u64,
+        convert(fdflags)?,
+    )?;
+    memory
+        .write_ptr(WasmPtr::new(opened_fd as u32), opened)
+        .map_err(Errno::from)
+}
+
+fn path_remove_directory(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    path: i32,
+    path_len: i32,
+) -> WasiResult {
+    let path = read_path(memory, path, path_len)?;
+    host.path_remove_directory(fd as u32, path)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn path_rename(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    old_path: i32,
+    old_path_len: i32,
+    new_fd: i32,
+    new_path: i32,
+    new_path_len: i32,
+) -> WasiResult {
+    let old_path = read_path(memory, old_path, old_path_len)?;
+    let new_path = read_path(memory, new_path, new_path_len)?;
+    host.path_rename(fd as u32, old_path, new_fd as u32, new_path)
+}
+
+fn path_unlink_file(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    fd: i32,
+    path: i32,
+    path_len: i32,
+) -> WasiResult {
+    let path = read_path(memory, path, path_len)?;
+    host.path_unlink_file(fd as u32, path)
+}
+
+fn random_get(
+    memory: &mut GuestMemory,
+    host: &mut impl WasiHost,
+    buf: i32,
+    buf_len: i32,
+) -> WasiResult {
+    host.random_get(memory.slice_mut(buf as u32, buf_len as u32)?)
+}
+
+fn sched_yield(_memory: &mut GuestMemory, host: &mut impl WasiHost) -> WasiResult {
+    host.sched_yield()
+}
@@ -0,0 +1,416 @@
+//! Types of the WASI `preview1` API as seen by a [`WasiHost`].
+//!
+//! [`WasiHost`]: super::WasiHost
+
+use core::fmt;
+use wasmi::errors::MemoryError;
+
+/// A WASI file descriptor.
+pub type Fd = u32;
+
+/// A WASI timestamp in nanoseconds.
+pub type Timestamp = u64;
+
+/// The WASI file descriptor flags, see [`fdflags`].
+pub type FdFlags = u16;
+
+/// The WASI open flags of `path_open`, see [`oflags`].
+pub type OFlags = u16;
+
+/// The WASI flags determining how paths are resolved, see [`lookupflags`].
+pub type LookupFlags = u32;
+
+/// The WASI rights of a file descriptor.
+pub type Rights = u64;
+
+/// The WASI file descriptor flags.
+pub mod fdflags {
+    use super::FdFlags;
+
+    /// Append mode: Data written to the file is always appended to the file's end.
+    pub const APPEND: FdFlags = 1 << 0;
+    /// Write according to synchronized I/O data integrity completion.
+    pub const DSYNC: FdFlags = 1 << 1;
+    /// Non-blocking mode.
+    pub const NONBLOCK: FdFlags = 1 << 2;
+    /// Synchronized read I/O operations.
+    pub const RSYNC: FdFlags = 1 << 3;
+    /// Write according to synchronized I/O file integrity completion.
+    pub const SYNC: FdFlags = 1 << 4;
+}
+
+/// The WASI open flags of `path_open`.
+pub mod oflags {
+    use super::OFlags;
+
+    /// Create the file if it does not exist.
+    pub const CREAT: OFlags = 1 << 0;
+    /// Fail if the opened file is not a directory.
+    pub const DIRECTORY: OFlags = 1 << 1;
+    /// Fail if the file already exists.
+    pub const EXCL: OFlags = 1 << 2;
+    /// Truncate the file to size 0.
+    pub const TRUNC: OFlags = 1 << 3;
+}
+
+/// The WASI flags determining how paths are resolved.
+pub mod lookupflags {
+    use super::LookupFlags;
+
+    /// Expand the path if it resolves to a symbolic link.
+    pub const SYMLINK_FOLLOW: LookupFlags = 1 << 0;
+}
+
+/// A WASI error code.
+///
+/// The [`Errno`] is returned to the Wasm guest as the result of the WASI function.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u16)]
+pub enum Errno {
+    /// Argument list too long.
+    Toobig = 1,
+    /// Permission denied.
+    Acces = 2,
+    /// Address in use.
+    Addrinuse = 3,
+    /// Address not available.
+    Addrnotavail = 4,
+    /// Address family not supported.
+    Afnosupport = 5,
+    /// Resource unavailable, or operation would block.
+    Again = 6,
+    /// Connection already in progress.
+    Already = 7,
+    /// Bad file descriptor.
+    Badf = 8,
+    /// Bad message.
+    Badmsg = 9,
+    /// Device or resource busy.
+    Busy = 10,
+    /// Operation canceled.
+    Canceled = 11,
+    /// No child processes.
+    Child = 12,
+    /// Connection aborted.
+    Connaborted = 13,
+    /// Connection refused.
+    Connrefused = 14,
+    /// Connection reset.
+    Connreset = 15,
+    /// Resource deadlock would occur.
+    Deadlk = 16,
+    /// Destination address required.
+    Destaddrreq = 17,
+    /// Mathematics argument out of domain of function.
+    Dom = 18,
+    /// Reserved.
+    Dquot = 19,
+    /// File exists.
+    Exist = 20,
+    /// Bad address.
+    Fault = 21,
+    /// File too large.
+    Fbig = 22,
+    /// Host is unreachable.
+    Hostunreach = 23,
+    /// Identifier removed.
+    Idrm = 24,
+    /// Illegal byte sequence.
+    Ilseq = 25,
+    /// Operation in progress.
+    Inprogress = 26,
+    /// Interrupted function.
+    Intr = 27,
+    /// Invalid argument.
+    Inval = 28,
+    /// I/O error.
+    Io = 29,
+    /// Socket is connected.
+    Isconn = 30,
+    /// Is a directory.
+    Isdir = 31,
+    /// Too many levels of symbolic links.
+    Loop = 32,
+    /// File descriptor value too large.
+    Mfile = 33,
+    /// Too many links.
+    Mlink = 34,
+    /// Message too large.
+    Msgsize = 35,
+    /// Reserved.
+    Multihop = 36,
+    /// Filename too long.
+    Nametoolong = 37,
+    /// Network is down.
+    Netdown = 38,
+    /// Connection aborted by network.
+    Netreset = 39,
+    /// Network unreachable.
+    Netunreach = 40,
+    /// Too many files open in system.
+    Nfile = 41,
+    /// No buffer space available.
+    Nobufs = 42,
+    /// No such device.
+    Nodev = 43,
+    /// No such file or directory.
+    Noent = 44,
+    /// Executable file format error.
+    Noexec = 45,
+    /// No locks available.
+    Nolck = 46,
+    /// Reserved.
+    Nolink = 47,
+    /// Not enough space.
+    Nomem = 48,
+    /// No message of the desired type.
+    Nomsg = 49,
+    /// Protocol not available.
+    Noprotoopt = 50,
+    /// No space left on device.
+    Nospc = 51,
+    /// Function not supported.
+    Nosys = 52,
+    /// The socket is not connected.
+    Notconn = 53,
+    /// Not a directory or a symbolic link to a directory.
+    Notdir = 54,
+    /// Directory not empty.
+    Notempty = 55,
+    /// State not recoverable.
+    Notrecoverable = 56,
+    /// Not a socket.
+    Notsock = 57,
+    /// Not supported, or operation not supported on socket.
+    Notsup = 58,
+    /// Inappropriate I/O control operation.
+    Notty = 59,
+    /// No such device or address.
+    Nxio = 60,
+    /// Value too large to be stored in data type.
+    Overflow = 61,
+    /// Previous owner died.
+    Ownerdead = 62,
+    /// Operation not permitted.
+    Perm = 63,
+    /// Broken pipe.
+    Pipe = 64,
+    /// Protocol error.
+    Proto = 65,
+    /// Protocol not supported.
+    Protonosupport = 66,
+    /// Protocol wrong type for socket.
+    Prototype = 67,
+    /// Result too large.
+    Range = 68,
+    /// Read-only file system.
+    Rofs = 69,
+    /// Invalid seek.
+    Spipe = 70,
+    /// No such process.
+    Srch = 71,
+    /// Reserved.
+    Stale = 72,
+    /// Connection timed out.
+    Timedout = 73,
+    /// Text file busy.
+    Txtbsy = 74,
+    /// Cross-device link.
+    Xdev = 75,
+    /// Extension: Capabilities insufficient.
+    Notcapable = 76,
+}
+
+impl Errno {
+    /// Returns the WASI error code of the [`Errno`].
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+}
+
+impl From<MemoryError> for Errno {
+    fn from(error: MemoryError) -> Self {
+        match error {
+            MemoryError::InvalidUtf8 => Self::Ilseq,
+            MemoryError::StringTooLong { .. } => Self::Nametoolong,
+            _ => Self::Fault,
+        }
+    }
+}
+
+impl fmt::Display for Errno {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?} (errno {})", self.code())
+    }
+}
+
+/// The clocks of the WASI `clock_res_get` and `clock_time_get` functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClockId {
+    /// The clock measuring real time.
+    Realtime,
+    /// The monotonic clock of the system.
+    Monotonic,
+    /// The CPU-time clock associated with the current process.
+    ProcessCputime,
+    /// The CPU-time clock associated with the current thread.
+    ThreadCputime,
+}
+
+impl TryFrom<i32> for ClockId {
+    type Error = Errno;
+
+    fn try_from(id: i32) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(Self::Realtime),
+            1 => Ok(Self::Monotonic),
+            2 => Ok(Self::ProcessCputime),
+            3 => Ok(Self::ThreadCputime),
+            _ => Err(Errno::Inval),
+        }
+    }
+}
+
+/// The reference point of the offset of the WASI `fd_seek` function.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Whence {
+    /// Seek relative to the start of the file.
+    Set,
+    /// Seek relative to the current position.
+    Cur,
+    /// Seek relative to the end of the file.
+    End,
+}
+
+impl TryFrom<i32> for Whence {
+    type Error = Errno;
+
+    fn try_from(whence: i32) -> Result<Self, Self::Error> {
+        match whence {
+            0 => Ok(Self::Set),
+            1 => Ok(Self::Cur),
+            2 => Ok(Self::End),
+            _ => Err(Errno::Inval),
+        }
+    }
+}
+
+/// The type of a WASI file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Filetype {
+    /// The type of the file is unknown or different from any of the other types.
+    #[default]
+    Unknown = 0,
+    /// The file refers to a block device inode.
+    BlockDevice = 1,
+    /// The file refers to a character device inode.
+    CharacterDevice = 2,
+    /// The file refers to a directory inode.
+    Directory = 3,
+    /// The file refers to a regular file inode.
+    RegularFile = 4,
+    /// The file refers to a datagram socket.
+    SocketDgram = 5,
+    /// The file refers to a byte-stream socket.
+    SocketStream = 6,
+    /// The file refers to a symbolic link inode.
+    SymbolicLink = 7,
+}
+
+/// The attributes of a WASI file descriptor.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Fdstat {
+    /// The type of the file.
+    pub filetype: Filetype,
+    /// The file descriptor flags.
+    pub flags: FdFlags,
+    /// The rights that apply to the file descriptor.
+    pub rights_base: Rights,
+    /// The maximum rights of file descriptors opened through the file descriptor.
+    pub rights_inheriting: Rights,
+}
+
+impl Fdstat {
+    /// The size of an encoded [`Fdstat`] in bytes.
+    pub(crate) const SIZE: usize = 24;
+
+    /// Returns the [`Fdstat`] encoded in the WASI memory layout.
+    pub(crate) fn encode(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0x00; Self::SIZE];
+        bytes[0] = self.filetype as u8;
+        bytes[2..4].copy_from_slice(&self.flags.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.rights_base.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.rights_inheriting.to_le_bytes());
+        bytes
+    }
+}
+
+/// The attributes of a WASI file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Filestat {
+    /// The device ID of the device containing the file.
+    pub dev: u64,
+    /// The file serial number.
+    pub ino: u64,
+    /// The type of the file.
+    pub filetype: Filetype,
+    /// The number of hard links to the file.
+    pub nlink: u64,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The time of the last access of the file.
+    pub atim: Timestamp,
+    /// The time of the last modification of the file.
+    pub mtim: Timestamp,
+    /// The time of the last file status change.
+    pub ctim: Timestamp,
+}
+
+impl Filestat {
+    /// The size of an encoded [`Filestat`] in bytes.
+    pub(crate) const SIZE: usize = 64;
+
+    /// Returns the [`Filestat`] encoded in the WASI memory layout.
+    pub(crate) fn encode(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0x00; Self::SIZE];
+        bytes[0..8].copy_from_slice(&self.dev.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.ino.to_le_bytes());
+        bytes[16] = self.filetype as u8;
+        bytes[24..32].copy_from_slice(&self.nlink.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.size.to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.atim.to_le_bytes());
+        bytes[48..56].copy_from_slice(&self.mtim.to_le_bytes());
+        bytes[56..64].copy_from_slice(&self.ctim.to_le_bytes());
+        bytes
+    }
+}
+
+/// A directory entry as returned by the WASI `fd_readdir` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirent {
+    /// The cookie of the next directory entry.
+    pub next: u64,
+    /// The serial number of the file referred to by the directory entry.
+    pub ino: u64,
+    /// The type of the file referred to by the directory entry.
+    pub filetype: Filetype,
+    /// The name of the directory entry.
+    pub name: String,
+}
+
+impl Dirent {
+    /// The size of an encoded [`Dirent`] header without its name in bytes.
+    pub(crate) const HEADER_SIZE: usize = 24;
+
+    /// Returns the [`Dirent`] encoded in the WASI memory layout followed by its name.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![0x00; Self::HEADER_SIZE];
+        bytes[0..8].copy_from_slice(&self.next.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.ino.to_le_bytes());
+        bytes[16..20].copy_from_slice(&(self.name.len() as u32).to_le_bytes());
+        bytes[20] = self.filetype as u8;
+        bytes.extend_from_slice(self.name.as_bytes());
+        bytes
+    }
+}
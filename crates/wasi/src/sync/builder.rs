@@ -0,0 +1,174 @@
+use super::deterministic::{seeded_random, ClockMode, VirtualClock};
+use cap_rand::RngCore;
+use std::path::Path;
+use wasi_cap_std_sync::{net::Socket, Dir};
+use wasi_common::{Error, StringArrayError, WasiClocks, WasiCtx, WasiFile};
+
+/// Builds a [`WasiCtx`] backed by the host via `cap-std`.
+///
+/// This mirrors the `WasiCtxBuilder` of `wasi-cap-std-sync` and additionally allows
+/// to replace the clocks and the random number generator seen by the Wasm guest,
+/// for example by the deterministic ones of the [`deterministic`](super::deterministic) module.
+pub struct DeterministicWasiCtxBuilder {
+    inner: wasi_cap_std_sync::WasiCtxBuilder,
+    /// The clocks replacing the host clocks if any.
+    clocks: Option<WasiClocks>,
+    /// The random number generator replacing the host entropy if any.
+    random: Option<Box<dyn RngCore + Send + Sync>>,
+}
+
+impl Default for DeterministicWasiCtxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeterministicWasiCtxBuilder {
+    /// Creates a new [`DeterministicWasiCtxBuilder`] using the host clocks and entropy.
+    pub fn new() -> Self {
+        Self {
+            inner: wasi_cap_std_sync::WasiCtxBuilder::new(),
+            clocks: None,
+            random: None,
+        }
+    }
+
+    /// Applies `f` to the wrapped `wasi-cap-std-sync` builder.
+    fn map<E>(
+        self,
+        f: impl FnOnce(
+            wasi_cap_std_sync::WasiCtxBuilder,
+        ) -> Result<wasi_cap_std_sync::WasiCtxBuilder, E>,
+    ) -> Result<Self, E> {
+        Ok(Self {
+            inner: f(self.inner)?,
+            ..self
+        })
+    }
+
+    /// Applies the infallible `f` to the wrapped `wasi-cap-std-sync` builder.
+    fn infallible(
+        self,
+        f: impl FnOnce(wasi_cap_std_sync::WasiCtxBuilder) -> wasi_cap_std_sync::WasiCtxBuilder,
+    ) -> Self {
+        Self {
+            inner: f(self.inner),
+            ..self
+        }
+    }
+
+    /// Adds the environment variable `var` with `value`.
+    pub fn env(self, var: &str, value: &str) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.env(var, value))
+    }
+
+    /// Adds all environment variables in `env` given as key-value pairs.
+    pub fn envs(self, env: &[(String, String)]) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.envs(env))
+    }
+
+    /// Adds all environment variables of the host process.
+    pub fn inherit_env(self) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.inherit_env())
+    }
+
+    /// Adds the command line argument `arg`.
+    pub fn arg(self, arg: &str) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.arg(arg))
+    }
+
+    /// Adds all command line arguments in `args`.
+    pub fn args(self, args: &[String]) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.args(args))
+    }
+
+    /// Adds all command line arguments of the host process.
+    pub fn inherit_args(self) -> Result<Self, StringArrayError> {
+        self.map(|inner| inner.inherit_args())
+    }
+
+    /// Sets the standard input to `file`.
+    pub fn stdin(self, file: Box<dyn WasiFile>) -> Self {
+        self.infallible(|inner| inner.stdin(file))
+    }
+
+    /// Sets the standard output to `file`.
+    pub fn stdout(self, file: Box<dyn WasiFile>) -> Self {
+        self.infallible(|inner| inner.stdout(file))
+    }
+
+    /// Sets the standard error to `file`.
+    pub fn stderr(self, file: Box<dyn WasiFile>) -> Self {
+        self.infallible(|inner| inner.stderr(file))
+    }
+
+    /// Inherits the standard input of the host process.
+    pub fn inherit_stdin(self) -> Self {
+        self.infallible(|inner| inner.inherit_stdin())
+    }
+
+    /// Inherits the standard output of the host process.
+    pub fn inherit_stdout(self) -> Self {
+        self.infallible(|inner| inner.inherit_stdout())
+    }
+
+    /// Inherits the standard error of the host process.
+    pub fn inherit_stderr(self) -> Self {
+        self.infallible(|inner| inner.inherit_stderr())
+    }
+
+    /// Inherits the standard input, output and error of the host process.
+    pub fn inherit_stdio(self) -> Self {
+        self.infallible(|inner| inner.inherit_stdio())
+    }
+
+    /// Pre-opens the host directory `dir` as `guest_path` for the Wasm guest.
+    pub fn preopened_dir(self, dir: Dir, guest_path: impl AsRef<Path>) -> Result<Self, Error> {
+        self.map(|inner| inner.preopened_dir(dir, guest_path))
+    }
+
+    /// Pre-opens the `socket` as file descriptor `fd` for the Wasm guest.
+    pub fn preopened_socket(self, fd: u32, socket: impl Into<Socket>) -> Result<Self, Error> {
+        self.map(|inner| inner.preopened_socket(fd, socket))
+    }
+
+    /// Replaces the host clocks seen by the Wasm guest with `clocks`.
+    ///
+    /// See [`VirtualClock`] for clocks that do not
+    /// depend on the host time.
+    pub fn clocks(mut self, clocks: WasiClocks) -> Self {
+        self.clocks = Some(clocks);
+        self
+    }
+
+    /// Replaces the host entropy seen by the Wasm guest via `random_get` with `random`.
+    ///
+    /// See [`seeded_random`] for a deterministic
+    /// random number generator.
+    pub fn random(mut self, random: Box<dyn RngCore + Send + Sync>) -> Self {
+        self.random = Some(random);
+        self
+    }
+
+    /// Makes the clocks and randomness seen by the Wasm guest deterministic.
+    ///
+    /// The clocks start at the Unix epoch and advance by one millisecond per read while
+    /// the random number generator is seeded by `seed`.
+    pub fn deterministic(self, seed: u64) -> Self {
+        let step = cap_std::time::Duration::from_millis(1);
+        let clock = VirtualClock::new(std::time::UNIX_EPOCH, ClockMode::Stepping(step));
+        self.clocks(clock.into()).random(seeded_random(seed))
+    }
+
+    /// Builds the [`WasiCtx`].
+    pub fn build(self) -> WasiCtx {
+        let mut wasi_ctx = self.inner.build();
+        if let Some(clocks) = self.clocks {
+            wasi_ctx.clocks = clocks;
+        }
+        if let Some(random) = self.random {
+            wasi_ctx.random = random;
+        }
+        wasi_ctx
+    }
+}
@@ -0,0 +1,201 @@
+//! Deterministic clocks and randomness for reproducible WASI executions.
+//!
+//! By default `clock_time_get`, `clock_res_get` and `random_get` are served by the
+//! clocks and entropy of the host. For reproducible test runs or consensus the
+//! [`DeterministicWasiCtxBuilder`](super::DeterministicWasiCtxBuilder) can instead be
+//! given a [`VirtualClock`] and a [`seeded_random`] number generator:
+//!
+//! ```
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use wasmi_wasi::{
+//!     deterministic::{seeded_random, ClockMode, VirtualClock},
+//!     DeterministicWasiCtxBuilder,
+//! };
+//!
+//! let clock = VirtualClock::new(UNIX_EPOCH, ClockMode::Stepping(Duration::from_millis(1)));
+//! let wasi_ctx = DeterministicWasiCtxBuilder::new()
+//!     .clocks(clock.into())
+//!     .random(seeded_random(42))
+//!     .build();
+//! ```
+//!
+//! # Note
+//!
+//! The timeouts of `poll_oneoff` still elapse in host time.
+
+use cap_rand::{RngCore, SeedableRng};
+use cap_std::time::{Duration, Instant, SystemTime};
+use rand_chacha::ChaCha20Rng;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+    Mutex,
+    PoisonError,
+};
+use wasi_common::{WasiClocks, WasiMonotonicClock, WasiSystemClock};
+
+/// The fuel consumed by the Wasm guest of a single store as seen by a [`ClockMode::Fuel`] clock.
+///
+/// The WASI functions added via `add_to_linker_with_fuel_gauge` update the [`FuelGauge`]
+/// of the store data before they execute. Clones share the same fuel.
+#[derive(Debug, Default, Clone)]
+pub struct FuelGauge(Arc<AtomicU64>);
+
+impl FuelGauge {
+    /// Creates a new [`FuelGauge`] with no fuel consumed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the fuel consumed by the Wasm guest.
+    pub fn set(&self, fuel_consumed: u64) {
+        self.0.store(fuel_consumed, Ordering::Relaxed);
+    }
+
+    /// Returns the fuel consumed by the Wasm guest as of its latest WASI call.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returns a random number generator for `random_get` that is deterministically seeded by `seed`.
+///
+/// The generator is `ChaCha20` so that the same `seed` yields the same random bytes
+/// across platforms and versions.
+pub fn seeded_random(seed: u64) -> Box<dyn RngCore + Send + Sync> {
+    Box::new(ChaCha20Rng::seed_from_u64(seed))
+}
+
+/// The way in which a [`VirtualClock`] advances.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClockMode {
+    /// The time never advances.
+    Fixed,
+    /// The time advances by the given step after every clock read.
+    Stepping(Duration),
+    /// The time advances by the given duration per unit of fuel consumed by the Wasm guest.
+    ///
+    /// The fuel is read from the [`VirtualClock::fuel_gauge`] which requires fuel metering
+    /// and the WASI functions added via `add_to_linker_with_fuel_gauge`,
+    /// otherwise the time never advances.
+    Fuel(Duration),
+}
+
+/// A virtual realtime and monotonic clock that does not depend on the host time.
+///
+/// Both clocks start at the same time and advance together according to the [`ClockMode`].
+/// The monotonic clock starts at zero while the realtime clock starts at the given start time.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    /// The realtime at which the [`VirtualClock`] starts.
+    start: std::time::SystemTime,
+    /// The way in which the [`VirtualClock`] advances.
+    mode: ClockMode,
+    /// The fuel consumed by the Wasm guest for a [`ClockMode::Fuel`] clock.
+    fuel: FuelGauge,
+}
+
+impl VirtualClock {
+    /// Creates a new [`VirtualClock`] starting at `start` and advancing according to `mode`.
+    pub fn new(start: std::time::SystemTime, mode: ClockMode) -> Self {
+        Self {
+            start,
+            mode,
+            fuel: FuelGauge::new(),
+        }
+    }
+
+    /// Returns the [`FuelGauge`] read by a [`ClockMode::Fuel`] clock.
+    ///
+    /// Store a clone of it in the store data for `add_to_linker_with_fuel_gauge`.
+    pub fn fuel_gauge(&self) -> &FuelGauge {
+        &self.fuel
+    }
+}
+
+impl From<VirtualClock> for WasiClocks {
+    fn from(clock: VirtualClock) -> Self {
+        let creation_time = Instant::from_std(std::time::Instant::now());
+        let state = Arc::new(ClockState {
+            clock,
+            creation_time,
+            steps: Mutex::new(Duration::ZERO),
+        });
+        Self {
+            system: Box::new(VirtualSystemClock(state.clone())),
+            monotonic: Box::new(VirtualMonotonicClock(state)),
+            creation_time,
+        }
+    }
+}
+
+/// The state shared by the realtime and monotonic clocks of a [`VirtualClock`].
+#[derive(Debug)]
+struct ClockState {
+    clock: VirtualClock,
+    /// The host instant that represents the start of the monotonic clock.
+    creation_time: Instant,
+    /// The time elapsed by the steps of a [`ClockMode::Stepping`] clock.
+    steps: Mutex<Duration>,
+}
+
+impl ClockState {
+    /// Returns the time elapsed since the start of the clock and advances the clock.
+    fn elapsed(&self) -> Duration {
+        match self.clock.mode {
+            ClockMode::Fixed => Duration::ZERO,
+            ClockMode::Stepping(step) => {
+                let mut steps = self.steps.lock().unwrap_or_else(PoisonError::into_inner);
+                let elapsed = *steps;
+                *steps = steps.saturating_add(step);
+                elapsed
+            }
+            ClockMode::Fuel(per_fuel) => {
+                let fuel_consumed = self.clock.fuel.get();
+                let nanos = per_fuel
+                    .as_nanos()
+                    .saturating_mul(u128::from(fuel_consumed));
+                Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+            }
+        }
+    }
+
+    /// Returns the resolution of the clock.
+    ///
+    /// This is the smallest amount by which the clock advances but at least one nanosecond.
+    fn resolution(&self) -> Duration {
+        let resolution = match self.clock.mode {
+            ClockMode::Fixed => Duration::ZERO,
+            ClockMode::Stepping(step) => step,
+            ClockMode::Fuel(per_fuel) => per_fuel,
+        };
+        resolution.max(Duration::from_nanos(1))
+    }
+}
+
+/// The realtime clock of a [`VirtualClock`].
+struct VirtualSystemClock(Arc<ClockState>);
+
+impl WasiSystemClock for VirtualSystemClock {
+    fn resolution(&self) -> Duration {
+        self.0.resolution()
+    }
+
+    fn now(&self, _precision: Duration) -> SystemTime {
+        let now = self.0.clock.start + self.0.elapsed();
+        SystemTime::from_std(now)
+    }
+}
+
+/// The monotonic clock of a [`VirtualClock`].
+struct VirtualMonotonicClock(Arc<ClockState>);
+
+impl WasiMonotonicClock for VirtualMonotonicClock {
+    fn resolution(&self) -> Duration {
+        self.0.resolution()
+    }
+
+    fn now(&self, _precision: Duration) -> Instant {
+        self.0.creation_time + self.0.elapsed()
+    }
+}
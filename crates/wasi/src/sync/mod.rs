@@ -2,9 +2,16 @@
 //! consumers of this library from having to keep additional dependencies
 //! in sync.
 
+mod builder;
+pub mod deterministic;
 pub mod snapshots;
 
+pub use self::builder::DeterministicWasiCtxBuilder;
 pub use wasi_cap_std_sync::*;
 
 #[doc(inline)]
-pub use self::snapshots::preview_1::add_wasi_snapshot_preview1_to_linker as add_to_linker;
+pub use self::snapshots::preview_1::{
+    add_wasi_snapshot_preview1_to_linker as add_to_linker,
+    add_wasi_snapshot_preview1_to_linker_with_fuel_gauge as add_to_linker_with_fuel_gauge,
+    add_wasi_snapshot_preview1_to_linker_with_memory as add_to_linker_with_memory,
+};
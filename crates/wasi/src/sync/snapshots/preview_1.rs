@@ -1,10 +1,11 @@
-use crate::WasmiGuestMemory;
+use crate::{memory::MemoryResolver, sync::deterministic::FuelGauge, WasiMemory, WasmiGuestMemory};
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, RawWaker, RawWakerVTable, Waker},
 };
 use wasi_common::Error;
-use wasmi::{core::Trap, Caller, Extern, Linker};
+use wasmi::{core::Trap, Caller, Linker};
 
 // Creates a dummy `RawWaker`. We can only create Wakers from `RawWaker`s
 fn dummy_raw_waker() -> RawWaker {
@@ -53,6 +54,9 @@ macro_rules! impl_add_to_linker_for_funcs {
         ///
         /// `WASI` is versioned in snapshots. It's still a WIP. Currently, this crate supports `preview_1`
         /// Look [here](https://github.com/WebAssembly/WASI/blob/main/phases/snapshot/docs.md) for more details.
+        ///
+        /// The WASI functions operate on the linear memory exported as `"memory"`.
+        /// Use [`add_wasi_snapshot_preview1_to_linker_with_memory`] for other linear memories.
         pub fn add_wasi_snapshot_preview1_to_linker<T, U>(
             linker: &mut Linker<T>,
             wasi_ctx: impl Fn(&mut T) -> &mut U + Send + Sync + Copy + 'static,
@@ -60,17 +64,57 @@ macro_rules! impl_add_to_linker_for_funcs {
         where U: wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1 +
                  wasi_common::snapshots::preview_1::wasi_snapshot_preview1::UserErrorConversion
         {
+            add_wasi_snapshot_preview1_to_linker_with_memory(linker, WasiMemory::default(), wasi_ctx)
+        }
+
+        /// Adds the entire `WASI API` to the [`Linker`] operating on the given [`WasiMemory`].
+        ///
+        /// This allows to use WASI with Wasm modules that export their linear memory under
+        /// a different name or that import their linear memory without re-exporting it.
+        ///
+        /// See [`add_wasi_snapshot_preview1_to_linker`] for more information.
+        pub fn add_wasi_snapshot_preview1_to_linker_with_memory<T, U>(
+            linker: &mut Linker<T>,
+            memory: WasiMemory,
+            wasi_ctx: impl Fn(&mut T) -> &mut U + Send + Sync + Copy + 'static,
+        ) -> Result<(), Error>
+        where U: wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1 +
+                 wasi_common::snapshots::preview_1::wasi_snapshot_preview1::UserErrorConversion
+        {
+            add_wasi_snapshot_preview1_to_linker_with_fuel_gauge(linker, memory, wasi_ctx, |_| None)
+        }
+
+        /// Adds the entire `WASI API` to the [`Linker`] operating on the given [`WasiMemory`]
+        /// and recording the fuel consumed by the Wasm guest in the [`FuelGauge`] of the store.
+        ///
+        /// Before every WASI call the fuel consumed so far is stored in the [`FuelGauge`]
+        /// returned by `fuel_gauge` for the store data if any. This drives the clocks of a
+        /// [`VirtualClock`](crate::sync::deterministic::VirtualClock) in
+        /// [`ClockMode::Fuel`](crate::sync::deterministic::ClockMode::Fuel).
+        ///
+        /// See [`add_wasi_snapshot_preview1_to_linker`] for more information.
+        pub fn add_wasi_snapshot_preview1_to_linker_with_fuel_gauge<T, U>(
+            linker: &mut Linker<T>,
+            memory: WasiMemory,
+            wasi_ctx: impl Fn(&mut T) -> &mut U + Send + Sync + Copy + 'static,
+            fuel_gauge: impl Fn(&T) -> Option<&FuelGauge> + Send + Sync + Copy + 'static,
+        ) -> Result<(), Error>
+        where U: wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1 +
+                 wasi_common::snapshots::preview_1::wasi_snapshot_preview1::UserErrorConversion
+        {
+            let resolver = Arc::new(MemoryResolver::new(memory));
             $(
                 // $(#[$docs])* // TODO: find place for docs
+                let memory_resolver = resolver.clone();
                 linker.func_wrap(
                     "wasi_snapshot_preview1",
                     stringify!($fname),
                     move |mut caller: Caller<'_, T>, $($arg : $typ,)*| {
+                        if let Some(gauge) = fuel_gauge(caller.data()) {
+                            gauge.set(caller.fuel_consumed().unwrap_or(0));
+                        }
+                        let memory = memory_resolver.resolve(&caller)?;
                         let result = async {
-                            let memory = match caller.get_export("memory") {
-                                Some(Extern::Memory(m)) => m,
-                                _ => return Err(Trap::new(String::from("missing required WASI memory export"))),
-                            };
                             let(memory, ctx) = memory.data_and_store_mut(&mut caller);
                             let ctx = wasi_ctx(ctx);
                             let memory = WasmiGuestMemory::new(memory);
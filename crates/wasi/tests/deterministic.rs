@@ -0,0 +1,211 @@
+#![cfg(feature = "sync")]
+
+use std::time::{Duration, UNIX_EPOCH};
+use wasmi::{Config, Engine, Instance, Linker, Module, Store};
+use wasmi_wasi::{
+    add_to_linker_with_fuel_gauge,
+    deterministic::{seeded_random, ClockMode, FuelGauge, VirtualClock},
+    DeterministicWasiCtxBuilder,
+    WasiCtx,
+    WasiMemory,
+};
+
+/// A Wasm module that reads the WASI clocks and randomness.
+const WAT: &str = r#"
+    (module
+        (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "clock_res_get" (func $clock_res_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        ;; Returns the time of the clock `id`.
+        (func (export "clock_time_get") (param $id i32) (result i64)
+            (drop (call $clock_time_get (local.get $id) (i64.const 1) (i32.const 0)))
+            (i64.load (i32.const 0))
+        )
+        ;; Returns the resolution of the clock `id`.
+        (func (export "clock_res_get") (param $id i32) (result i64)
+            (drop (call $clock_res_get (local.get $id) (i32.const 0)))
+            (i64.load (i32.const 0))
+        )
+        ;; Returns 8 random bytes.
+        (func (export "random_get") (result i64)
+            (drop (call $random_get (i32.const 0) (i32.const 8)))
+            (i64.load (i32.const 0))
+        )
+        ;; Consumes fuel by looping `n` times.
+        (func (export "spin") (param $n i32)
+            (loop $continue
+                (br_if $continue
+                    (local.tee $n (i32.sub (local.get $n) (i32.const 1)))
+                )
+            )
+        )
+    )
+"#;
+
+/// The WASI identifier of the realtime clock.
+const REALTIME: i32 = 0;
+
+/// The WASI identifier of the monotonic clock.
+const MONOTONIC: i32 = 1;
+
+/// The store data of the tests: the WASI context and the optional [`FuelGauge`] of its clocks.
+type Data = (WasiCtx, Option<FuelGauge>);
+
+/// Instantiates the [`WAT`] module with `wasi` and fuel metering enabled.
+fn setup(wasi: WasiCtx) -> (Store<Data>, Instance) {
+    setup_with_fuel_gauge(wasi, None)
+}
+
+/// Instantiates the [`WAT`] module with `wasi` recording the consumed fuel in `fuel`.
+fn setup_with_fuel_gauge(wasi: WasiCtx, fuel: Option<FuelGauge>) -> (Store<Data>, Instance) {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<Data>>::new(&engine);
+    add_to_linker_with_fuel_gauge(
+        &mut linker,
+        WasiMemory::default(),
+        |(wasi, _)| wasi,
+        |(_, fuel)| fuel.as_ref(),
+    )
+    .unwrap();
+    let mut store = Store::new(&engine, (wasi, fuel));
+    store.add_fuel(1_000_000).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+/// Calls the exported clock function `name` for the clock `id`.
+fn clock(store: &mut Store<Data>, instance: &Instance, name: &str, id: i32) -> u64 {
+    instance
+        .get_typed_func::<i32, i64>(&*store, name)
+        .unwrap()
+        .call(store, id)
+        .unwrap() as u64
+}
+
+/// Returns 8 random bytes from the `random_get` of the Wasm guest.
+fn random(store: &mut Store<Data>, instance: &Instance) -> i64 {
+    instance
+        .get_typed_func::<(), i64>(&*store, "random_get")
+        .unwrap()
+        .call(store, ())
+        .unwrap()
+}
+
+#[test]
+fn fixed_clock() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_000);
+    let clock_ctx = VirtualClock::new(start, ClockMode::Fixed);
+    let wasi = DeterministicWasiCtxBuilder::new()
+        .clocks(clock_ctx.into())
+        .build();
+    let (mut store, instance) = setup(wasi);
+    for _ in 0..3 {
+        let realtime = clock(&mut store, &instance, "clock_time_get", REALTIME);
+        assert_eq!(realtime, 1_000_000_000_000);
+        assert_eq!(clock(&mut store, &instance, "clock_time_get", MONOTONIC), 0);
+    }
+    assert_eq!(clock(&mut store, &instance, "clock_res_get", REALTIME), 1);
+}
+
+#[test]
+fn stepping_clock() {
+    let step = Duration::from_millis(1);
+    let clock_ctx = VirtualClock::new(UNIX_EPOCH, ClockMode::Stepping(step));
+    let wasi = DeterministicWasiCtxBuilder::new()
+        .clocks(clock_ctx.into())
+        .build();
+    let (mut store, instance) = setup(wasi);
+    assert_eq!(clock(&mut store, &instance, "clock_time_get", MONOTONIC), 0);
+    assert_eq!(
+        clock(&mut store, &instance, "clock_time_get", MONOTONIC),
+        1_000_000
+    );
+    assert_eq!(
+        clock(&mut store, &instance, "clock_time_get", REALTIME),
+        2_000_000
+    );
+    assert_eq!(
+        clock(&mut store, &instance, "clock_res_get", MONOTONIC),
+        1_000_000
+    );
+}
+
+#[test]
+fn fuel_clock() {
+    let per_fuel = Duration::from_nanos(10);
+    let clock_ctx = VirtualClock::new(UNIX_EPOCH, ClockMode::Fuel(per_fuel));
+    let fuel = clock_ctx.fuel_gauge().clone();
+    let wasi = DeterministicWasiCtxBuilder::new()
+        .clocks(clock_ctx.into())
+        .build();
+    let (mut store, instance) = setup_with_fuel_gauge(wasi, Some(fuel));
+    let before = clock(&mut store, &instance, "clock_time_get", MONOTONIC);
+    let again = clock(&mut store, &instance, "clock_time_get", MONOTONIC);
+    let spin = instance.get_typed_func::<i32, ()>(&store, "spin").unwrap();
+    spin.call(&mut store, 100).unwrap();
+    let after = clock(&mut store, &instance, "clock_time_get", MONOTONIC);
+    assert!(before < again);
+    assert!(after > again + 100 * 10);
+    assert!(after <= store.fuel_consumed().unwrap() * 10);
+}
+
+#[test]
+fn fuel_clocks_are_per_store() {
+    let per_fuel = Duration::from_nanos(10);
+    let setup_fuel_clock = || {
+        let clock_ctx = VirtualClock::new(UNIX_EPOCH, ClockMode::Fuel(per_fuel));
+        let fuel = clock_ctx.fuel_gauge().clone();
+        let wasi = DeterministicWasiCtxBuilder::new()
+            .clocks(clock_ctx.into())
+            .build();
+        setup_with_fuel_gauge(wasi, Some(fuel))
+    };
+    let (mut store_a, instance_a) = setup_fuel_clock();
+    let (mut store_b, instance_b) = setup_fuel_clock();
+    let spin = instance_a
+        .get_typed_func::<i32, ()>(&store_a, "spin")
+        .unwrap();
+    spin.call(&mut store_a, 1_000).unwrap();
+    let time_a = clock(&mut store_a, &instance_a, "clock_time_get", MONOTONIC);
+    let time_b = clock(&mut store_b, &instance_b, "clock_time_get", MONOTONIC);
+    assert!(time_a > 1_000 * 10);
+    assert!(time_b < time_a);
+    assert_eq!(time_b, store_b.data().1.as_ref().unwrap().get() * 10);
+}
+
+#[test]
+fn seeded_randomness() {
+    let mut results = Vec::new();
+    for seed in [1, 1, 2] {
+        let wasi = DeterministicWasiCtxBuilder::new()
+            .random(seeded_random(seed))
+            .build();
+        let (mut store, instance) = setup(wasi);
+        results.push((random(&mut store, &instance), random(&mut store, &instance)));
+    }
+    assert_eq!(results[0], results[1]);
+    assert_ne!(results[0], results[2]);
+    assert_ne!(results[0].0, results[0].1);
+}
+
+#[test]
+fn deterministic() {
+    let run = || {
+        let wasi = DeterministicWasiCtxBuilder::new().deterministic(7).build();
+        let (mut store, instance) = setup(wasi);
+        let realtime = clock(&mut store, &instance, "clock_time_get", REALTIME);
+        (realtime, random(&mut store, &instance))
+    };
+    let (realtime, bytes) = run();
+    assert_eq!(realtime, 0);
+    assert_eq!(run(), (realtime, bytes));
+}
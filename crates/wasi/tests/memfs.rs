@@ -0,0 +1,350 @@
+#![cfg(feature = "sync")]
+
+use wasi_cap_std_sync::WasiCtxBuilder;
+use wasmi::{Config, Engine, Instance, Linker, Memory, Module, Store, WasmParams};
+use wasmi_wasi::{
+    add_to_linker,
+    memfs::{MemFs, DEFAULT_MAX_BYTES, DEFAULT_MAX_FILE_SIZE},
+    DirCaps,
+    FileCaps,
+    WasiCtx,
+};
+
+/// A Wasm module re-exporting the WASI filesystem functions under test.
+const WAT: &str = r#"
+    (module
+        (import "wasi_snapshot_preview1" "path_open" (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "path_rename" (func $path_rename (param i32 i32 i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "path_unlink_file" (func $path_unlink_file (param i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_close" (func $fd_close (param i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_filestat_set_size" (func $fd_filestat_set_size (param i32 i64) (result i32)))
+        (memory (export "memory") 1)
+        (func (export "path_open") (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)
+            (call $path_open
+                (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4)
+                (local.get 5) (local.get 6) (local.get 7) (local.get 8)
+            )
+        )
+        (func (export "path_rename") (param i32 i32 i32 i32 i32 i32) (result i32)
+            (call $path_rename
+                (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4) (local.get 5)
+            )
+        )
+        (func (export "path_unlink_file") (param i32 i32 i32) (result i32)
+            (call $path_unlink_file (local.get 0) (local.get 1) (local.get 2))
+        )
+        (func (export "fd_read") (param i32 i32 i32 i32) (result i32)
+            (call $fd_read (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+        )
+        (func (export "fd_write") (param i32 i32 i32 i32) (result i32)
+            (call $fd_write (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+        )
+        (func (export "fd_close") (param i32) (result i32)
+            (call $fd_close (local.get 0))
+        )
+        (func (export "fd_filestat_set_size") (param i32 i64) (result i32)
+            (call $fd_filestat_set_size (local.get 0) (local.get 1))
+        )
+    )
+"#;
+
+/// The file descriptor of the pre-opened [`MemFs`].
+const DIR_FD: i32 = 3;
+
+/// The `O_CREAT` flag of `path_open`.
+const O_CREAT: i32 = 1;
+
+/// All WASI rights for the `rights_base` and `rights_inheriting` of `path_open`.
+const RIGHTS_ALL: i64 = 0x1FFF_FFFF;
+
+/// The WASI `errno` for exceeded quotas.
+const ERRNO_NOSPC: i32 = 51;
+
+/// The WASI `errno` for files exceeding the maximum file size.
+const ERRNO_FBIG: i32 = 22;
+
+/// The WASI `errno` for missing capabilities of pre-opened directories.
+const ERRNO_PERM: i32 = 63;
+
+/// The WASI `errno` for missing files.
+const ERRNO_NOENT: i32 = 44;
+
+/// A Wasm guest with a pre-opened [`MemFs`].
+struct Guest {
+    store: Store<WasiCtx>,
+    instance: Instance,
+    memory: Memory,
+}
+
+impl Guest {
+    /// Instantiates the [`WAT`] module with `fs` pre-opened as `/` with the given capabilities.
+    fn new(fs: &MemFs, dir_caps: DirCaps, file_caps: FileCaps) -> Self {
+        let engine = Engine::new(&Config::default());
+        let wasm = wat::parse_str(WAT).unwrap();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut linker = <Linker<WasiCtx>>::new(&engine);
+        add_to_linker(&mut linker, |ctx| ctx).unwrap();
+        let mut wasi = WasiCtxBuilder::new().build();
+        wasi.insert_dir(
+            DIR_FD as u32,
+            Box::new(fs.root_dir()),
+            dir_caps,
+            file_caps,
+            "/".into(),
+        );
+        let mut store = Store::new(&engine, wasi);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let memory = instance.get_memory(&store, "memory").unwrap();
+        Self {
+            store,
+            instance,
+            memory,
+        }
+    }
+
+    /// Calls the exported WASI function `name` with `params` and returns its WASI error code.
+    fn call<Params>(&mut self, name: &str, params: Params) -> i32
+    where
+        Params: WasmParams,
+    {
+        self.instance
+            .get_typed_func::<Params, i32>(&self.store, name)
+            .unwrap()
+            .call(&mut self.store, params)
+            .unwrap()
+    }
+
+    /// Writes `bytes` to the guest memory at `ptr`.
+    fn write(&mut self, ptr: usize, bytes: &[u8]) {
+        self.memory.write(&mut self.store, ptr, bytes).unwrap();
+    }
+
+    /// Returns the `len` bytes of guest memory at `ptr`.
+    fn read(&self, ptr: usize, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0x00; len];
+        self.memory.read(&self.store, ptr, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Returns the little-endian `u32` of guest memory at `ptr`.
+    fn read_u32(&self, ptr: usize) -> u32 {
+        let bytes = self.read(ptr, 4);
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Opens `path` relative to the pre-opened [`MemFs`] with `oflags`.
+    ///
+    /// Returns the opened file descriptor or the WASI error code.
+    fn open(&mut self, path: &str, oflags: i32) -> Result<i32, i32> {
+        self.write(1000, path.as_bytes());
+        let params = (
+            DIR_FD,
+            0,
+            1000,
+            path.len() as i32,
+            oflags,
+            RIGHTS_ALL,
+            RIGHTS_ALL,
+            0,
+            0,
+        );
+        match self.call("path_open", params) {
+            0 => Ok(self.read_u32(0) as i32),
+            errno => Err(errno),
+        }
+    }
+
+    /// Reads up to `len` bytes from `fd`.
+    fn fd_read(&mut self, fd: i32, len: u32) -> Vec<u8> {
+        self.write(8, &2000_u32.to_le_bytes());
+        self.write(12, &len.to_le_bytes());
+        assert_eq!(self.call("fd_read", (fd, 8, 1, 16)), 0);
+        let read = self.read_u32(16) as usize;
+        self.read(2000, read)
+    }
+
+    /// Writes `bytes` to `fd` and returns the WASI error code.
+    fn fd_write(&mut self, fd: i32, bytes: &[u8]) -> i32 {
+        self.write(2000, bytes);
+        self.write(8, &2000_u32.to_le_bytes());
+        self.write(12, &(bytes.len() as u32).to_le_bytes());
+        self.call("fd_write", (fd, 8, 1, 16))
+    }
+}
+
+/// Returns a `ustar` tar header followed by the padded `contents` of an entry.
+fn tar_entry(path: &str, kind: u8, contents: &[u8]) -> Vec<u8> {
+    let mut header = [0x00; 512];
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    header[100..107].copy_from_slice(b"0000644");
+    header[124..135].copy_from_slice(format!("{:011o}", contents.len()).as_bytes());
+    header[156] = kind;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[148..156].copy_from_slice(b"        ");
+    let checksum = header.iter().map(|byte| u32::from(*byte)).sum::<u32>();
+    header[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+    let mut entry = header.to_vec();
+    entry.extend_from_slice(contents);
+    entry.resize(entry.len().next_multiple_of(512), 0x00);
+    entry
+}
+
+#[test]
+fn read_and_write_files() {
+    let fs = MemFs::from_map([("data/input.txt", "hello memfs")]).unwrap();
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("data/input.txt", 0).unwrap();
+    assert_eq!(guest.fd_read(fd, 64), b"hello memfs");
+    assert_eq!(guest.call("fd_close", fd), 0);
+    assert_eq!(guest.open("data/missing.txt", 0), Err(ERRNO_NOENT));
+    let fd = guest.open("data/output.txt", O_CREAT).unwrap();
+    assert_eq!(guest.fd_write(fd, b"written by the guest"), 0);
+    assert_eq!(guest.call("fd_close", fd), 0);
+    assert_eq!(
+        fs.read_file("data/output.txt").unwrap(),
+        b"written by the guest"
+    );
+    assert_eq!(fs.used_bytes(), 31);
+}
+
+#[test]
+fn rename_and_unlink() {
+    let fs = MemFs::from_map([("a.txt", "a"), ("b.txt", "b")]).unwrap();
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    guest.write(100, b"a.txt");
+    guest.write(200, b"dir/c.txt");
+    assert_eq!(
+        guest.call("path_rename", (DIR_FD, 100, 5, DIR_FD, 200, 9)),
+        ERRNO_NOENT
+    );
+    guest.write(200, b"c.txt");
+    assert_eq!(
+        guest.call("path_rename", (DIR_FD, 100, 5, DIR_FD, 200, 5)),
+        0
+    );
+    assert_eq!(fs.read_file("a.txt"), None);
+    assert_eq!(fs.read_file("c.txt").unwrap(), b"a");
+    // Unlinked files stay readable through open file descriptors.
+    let fd = guest.open("b.txt", 0).unwrap();
+    guest.write(100, b"b.txt");
+    assert_eq!(guest.call("path_unlink_file", (DIR_FD, 100, 5)), 0);
+    assert_eq!(fs.read_file("b.txt"), None);
+    assert_eq!(fs.used_entries(), 2);
+    assert_eq!(guest.fd_read(fd, 64), b"b");
+    assert_eq!(guest.call("fd_close", fd), 0);
+    assert_eq!(fs.used_entries(), 1);
+    assert_eq!(fs.used_bytes(), 1);
+}
+
+#[test]
+fn quotas() {
+    let mut fs = MemFs::from_map([("small.txt", "1234")]).unwrap();
+    fs.max_bytes(8).max_entries(2);
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("new.txt", O_CREAT).unwrap();
+    assert_eq!(guest.fd_write(fd, b"1234"), 0);
+    assert_eq!(guest.fd_write(fd, b"5"), ERRNO_NOSPC);
+    assert_eq!(guest.open("another.txt", O_CREAT), Err(ERRNO_NOSPC));
+    assert!(fs.clone().insert_file("another.txt", "").is_err());
+    assert_eq!(fs.used_bytes(), 8);
+    assert_eq!(fs.used_entries(), 2);
+}
+
+#[test]
+fn max_file_size() {
+    let mut fs = MemFs::new();
+    fs.max_file_size(8);
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("new.txt", O_CREAT).unwrap();
+    assert_eq!(guest.fd_write(fd, b"12345678"), 0);
+    assert_eq!(guest.fd_write(fd, b"9"), ERRNO_FBIG);
+    assert_eq!(guest.call("fd_filestat_set_size", (fd, 9_i64)), ERRNO_FBIG);
+    assert_eq!(guest.call("fd_filestat_set_size", (fd, 4_i64)), 0);
+    assert!(fs.clone().insert_file("big.txt", "123456789").is_err());
+    assert_eq!(fs.read_file("new.txt").unwrap(), b"1234");
+    assert_eq!(fs.used_bytes(), 4);
+}
+
+#[test]
+fn huge_files_are_limited_by_default() {
+    let fs = MemFs::new();
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("huge.txt", O_CREAT).unwrap();
+    assert_eq!(guest.call("fd_filestat_set_size", (fd, -1_i64)), ERRNO_FBIG);
+    assert_eq!(
+        guest.call(
+            "fd_filestat_set_size",
+            (fd, DEFAULT_MAX_FILE_SIZE as i64 + 1)
+        ),
+        ERRNO_FBIG
+    );
+    assert_eq!(fs.used_bytes(), 0);
+}
+
+#[test]
+fn huge_files_with_lifted_file_size_limit() {
+    let mut fs = MemFs::new();
+    fs.max_file_size(u64::MAX);
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("huge.txt", O_CREAT).unwrap();
+    assert_eq!(
+        guest.call("fd_filestat_set_size", (fd, DEFAULT_MAX_BYTES as i64 + 1)),
+        ERRNO_NOSPC
+    );
+    fs.max_bytes(u64::MAX);
+    assert_eq!(
+        guest.call("fd_filestat_set_size", (fd, 1_i64 << 62)),
+        ERRNO_NOSPC
+    );
+    assert_eq!(fs.used_bytes(), 0);
+}
+
+#[test]
+fn read_only_preopen() {
+    let fs = MemFs::from_map([("file.txt", "contents")]).unwrap();
+    let dir_caps = DirCaps::OPEN | DirCaps::READDIR | DirCaps::PATH_FILESTAT_GET;
+    let file_caps = FileCaps::READ | FileCaps::SEEK | FileCaps::TELL;
+    let mut guest = Guest::new(&fs, dir_caps, file_caps);
+    let fd = guest.open("file.txt", 0).unwrap();
+    assert_eq!(guest.fd_read(fd, 64), b"contents");
+    assert_ne!(guest.fd_write(fd, b"changed"), 0);
+    assert_eq!(guest.open("new.txt", O_CREAT), Err(ERRNO_PERM));
+    assert_eq!(fs.read_file("file.txt").unwrap(), b"contents");
+}
+
+#[test]
+fn from_tar() {
+    let mut archive = Vec::new();
+    archive.extend(tar_entry("./", b'5', &[]));
+    archive.extend(tar_entry("./docs/", b'5', &[]));
+    archive.extend(tar_entry("./docs/readme.md", b'0', b"# Readme"));
+    archive.extend(tar_entry("./empty/", b'5', &[]));
+    archive.extend([0x00; 1024]);
+    let fs = MemFs::from_tar(&archive).unwrap();
+    assert_eq!(fs.read_file("docs/readme.md").unwrap(), b"# Readme");
+    assert_eq!(fs.read_file("/docs/readme.md").unwrap(), b"# Readme");
+    assert_eq!(fs.read_file("empty"), None);
+    assert_eq!(fs.used_entries(), 3);
+    let mut guest = Guest::new(&fs, DirCaps::all(), FileCaps::all());
+    let fd = guest.open("docs/../docs/readme.md", 0).unwrap();
+    assert_eq!(guest.fd_read(fd, 64), b"# Readme");
+    assert!(guest.open("../outside.txt", 0).is_err());
+}
+
+#[test]
+fn from_tar_invalid() {
+    let escaping = tar_entry("../outside.txt", b'0', b"");
+    assert!(MemFs::from_tar(&escaping).is_err());
+    let symlink = tar_entry("link", b'2', b"");
+    assert!(MemFs::from_tar(&symlink).is_err());
+    let mut corrupted = tar_entry("file.txt", b'0', b"contents");
+    corrupted[0] = b'F';
+    assert!(MemFs::from_tar(&corrupted).is_err());
+}
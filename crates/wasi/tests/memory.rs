@@ -0,0 +1,131 @@
+#![cfg(feature = "sync")]
+
+use wasmi::{Engine, Instance, Linker, Memory, Module, Store};
+use wasmi_wasi::{add_to_linker_with_memory, WasiCtx, WasiCtxBuilder, WasiMemory};
+
+/// Queries the sizes of the WASI arguments into the linear memory `$mem`.
+const WAT_EXPORTED: &str = r#"
+    (module
+        (import "wasi_snapshot_preview1" "args_sizes_get"
+            (func $args_sizes_get (param i32 i32) (result i32)))
+        (memory $mem (export "mem") 1)
+        (func (export "args_sizes_get") (result i32)
+            (call $args_sizes_get (i32.const 0) (i32.const 4))
+        )
+    )
+"#;
+
+/// Queries the sizes of the WASI arguments into the imported linear memory.
+const WAT_IMPORTED: &str = r#"
+    (module
+        (import "wasi_snapshot_preview1" "args_sizes_get"
+            (func $args_sizes_get (param i32 i32) (result i32)))
+        (import "env" "memory" (memory 1))
+        (func (export "args_sizes_get") (result i32)
+            (call $args_sizes_get (i32.const 0) (i32.const 4))
+        )
+    )
+"#;
+
+/// Instantiates the `wat` module with WASI operating on `memory`.
+///
+/// Returns the imported linear memory of the module.
+fn setup(wat: &str, memory: WasiMemory) -> (Store<WasiCtx>, Instance, Memory) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let wasi = WasiCtxBuilder::new()
+        .args(&["main.wasm".into(), "--flag".into()])
+        .unwrap()
+        .build();
+    let mut store = Store::new(&engine, wasi);
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    add_to_linker_with_memory(&mut linker, memory, |ctx| ctx).unwrap();
+    let imported = Memory::new(&mut store, wasmi::MemoryType::new(1, None).unwrap()).unwrap();
+    linker.define("env", "memory", imported).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance, imported)
+}
+
+/// Calls the exported `args_sizes_get` function of the `instance`.
+fn args_sizes_get(
+    store: &mut Store<WasiCtx>,
+    instance: &Instance,
+) -> Result<i32, wasmi::core::Trap> {
+    instance
+        .get_typed_func::<(), i32>(&*store, "args_sizes_get")
+        .unwrap()
+        .call(store, ())
+}
+
+/// Returns the argument count and size written to the `memory`.
+fn read_sizes(store: &Store<WasiCtx>, memory: &Memory) -> (u32, u32) {
+    let mut bytes = [0x00; 8];
+    memory.read(store, 0, &mut bytes).unwrap();
+    let [a0, a1, a2, a3, b0, b1, b2, b3] = bytes;
+    (
+        u32::from_le_bytes([a0, a1, a2, a3]),
+        u32::from_le_bytes([b0, b1, b2, b3]),
+    )
+}
+
+#[test]
+fn exported_memory_by_name() {
+    let (mut store, instance, _) = setup(WAT_EXPORTED, WasiMemory::Export("mem".into()));
+    assert_eq!(args_sizes_get(&mut store, &instance).unwrap(), 0);
+    // Repeated calls resolve the same memory of the calling instance.
+    assert_eq!(args_sizes_get(&mut store, &instance).unwrap(), 0);
+    let memory = instance.get_memory(&store, "mem").unwrap();
+    assert_eq!(read_sizes(&store, &memory), (2, 17));
+}
+
+#[test]
+fn imported_memory_by_index() {
+    let (mut store, instance, memory) = setup(WAT_IMPORTED, WasiMemory::Index(0));
+    assert_eq!(args_sizes_get(&mut store, &instance).unwrap(), 0);
+    assert_eq!(read_sizes(&store, &memory), (2, 17));
+}
+
+#[test]
+fn missing_memory() {
+    let (mut store, instance, _) = setup(WAT_EXPORTED, WasiMemory::default());
+    let error = args_sizes_get(&mut store, &instance).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "missing required WASI memory export `memory`"
+    );
+    let (mut store, instance, _) = setup(WAT_IMPORTED, WasiMemory::Index(1));
+    let error = args_sizes_get(&mut store, &instance).unwrap_err();
+    assert_eq!(error.to_string(), "missing required WASI memory at index 1");
+}
+
+#[test]
+fn cached_memory_per_instance() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT_EXPORTED).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    add_to_linker_with_memory(&mut linker, WasiMemory::Export("mem".into()), |ctx| ctx).unwrap();
+    let wasi = WasiCtxBuilder::new().args(&["a".into()]).unwrap().build();
+    let mut store = Store::new(&engine, wasi);
+    let instances = [(); 2].map(|_| {
+        linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap()
+    });
+    for instance in &instances {
+        assert_eq!(args_sizes_get(&mut store, instance).unwrap(), 0);
+    }
+    // Calls of the second instance use its own cached memory.
+    let [first, second] = instances.map(|instance| instance.get_memory(&store, "mem").unwrap());
+    first.write(&mut store, 0, &[0xFF; 8]).unwrap();
+    assert_eq!(args_sizes_get(&mut store, &instances[1]).unwrap(), 0);
+    assert_eq!(read_sizes(&store, &first), (u32::MAX, u32::MAX));
+    assert_eq!(read_sizes(&store, &second), (1, 2));
+}
@@ -1 +1,5 @@
+mod deterministic;
+mod memfs;
+mod memory;
+mod native;
 mod wasi_wat;
@@ -0,0 +1,405 @@
+use std::collections::BTreeMap;
+use wasmi::{Config, Engine, Instance, Linker, Memory, Module, Store, WasmParams};
+use wasmi_wasi::{
+    native::{
+        add_to_linker,
+        add_to_linker_with_memory,
+        ClockId,
+        Errno,
+        Fd,
+        FdFlags,
+        LookupFlags,
+        OFlags,
+        Rights,
+        Timestamp,
+        WasiHost,
+    },
+    WasiMemory,
+};
+
+/// A [`WasiHost`] with in-memory standard I/O and a single pre-opened directory `/data`.
+#[derive(Debug, Default)]
+struct TestHost {
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    stdin: Vec<u8>,
+    stdout: Vec<u8>,
+    /// The files of the pre-opened directory `/data`.
+    files: BTreeMap<String, Vec<u8>>,
+    /// The open files by their file descriptor together with their file position.
+    open: BTreeMap<Fd, (String, usize)>,
+}
+
+/// The file descriptor of the pre-opened directory `/data`.
+const DATA_FD: Fd = 3;
+
+impl WasiHost for TestHost {
+    fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+
+    fn clock_time_get(&mut self, id: ClockId, _precision: Timestamp) -> Result<Timestamp, Errno> {
+        match id {
+            ClockId::Realtime => Ok(1_000_000),
+            ClockId::Monotonic => Ok(42),
+            _ => Err(Errno::Inval),
+        }
+    }
+
+    fn random_get(&mut self, buffer: &mut [u8]) -> Result<(), Errno> {
+        buffer.fill(0xAB);
+        Ok(())
+    }
+
+    fn fd_read(&mut self, fd: Fd, buffer: &mut [u8]) -> Result<usize, Errno> {
+        let (data, position) = match fd {
+            0 => {
+                let len = self.stdin.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&self.stdin[..len]);
+                self.stdin.drain(..len);
+                return Ok(len);
+            }
+            fd => self.open.get_mut(&fd).ok_or(Errno::Badf)?,
+        };
+        let data = &self.files[&*data][*position..];
+        let len = data.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&data[..len]);
+        *position += len;
+        Ok(len)
+    }
+
+    fn fd_write(&mut self, fd: Fd, buffer: &[u8]) -> Result<usize, Errno> {
+        match fd {
+            1 => {
+                self.stdout.extend_from_slice(buffer);
+                Ok(buffer.len())
+            }
+            _ => Err(Errno::Badf),
+        }
+    }
+
+    fn fd_close(&mut self, fd: Fd) -> Result<(), Errno> {
+        self.open.remove(&fd).map(|_| ()).ok_or(Errno::Badf)
+    }
+
+    fn fd_prestat_dir_name(&mut self, fd: Fd) -> Result<String, Errno> {
+        match fd {
+            DATA_FD => Ok(String::from("/data")),
+            _ => Err(Errno::Badf),
+        }
+    }
+
+    fn path_open(
+        &mut self,
+        fd: Fd,
+        _lookup_flags: LookupFlags,
+        path: &str,
+        _oflags: OFlags,
+        _rights_base: Rights,
+        _rights_inheriting: Rights,
+        _fdflags: FdFlags,
+    ) -> Result<Fd, Errno> {
+        if fd != DATA_FD {
+            return Err(Errno::Badf);
+        }
+        if !self.files.contains_key(path) {
+            return Err(Errno::Noent);
+        }
+        let opened = self.open.keys().last().map_or(DATA_FD + 1, |fd| fd + 1);
+        self.open.insert(opened, (path.into(), 0));
+        Ok(opened)
+    }
+}
+
+/// A Wasm module re-exporting the WASI functions under test.
+const WAT: &str = r#"
+    (module
+        (import "wasi_snapshot_preview1" "args_sizes_get" (func $args_sizes_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "args_get" (func $args_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "environ_get" (func $environ_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "clock_res_get" (func $clock_res_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_close" (func $fd_close (param i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_prestat_get" (func $fd_prestat_get (param i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_prestat_dir_name" (func $fd_prestat_dir_name (param i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "path_open" (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "poll_oneoff" (func $poll_oneoff (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+        (memory (export "memory") 1)
+        (func (export "args_sizes_get") (param i32 i32) (result i32)
+            (call $args_sizes_get (local.get 0) (local.get 1))
+        )
+        (func (export "args_get") (param i32 i32) (result i32)
+            (call $args_get (local.get 0) (local.get 1))
+        )
+        (func (export "environ_get") (param i32 i32) (result i32)
+            (call $environ_get (local.get 0) (local.get 1))
+        )
+        (func (export "clock_time_get") (param i32 i64 i32) (result i32)
+            (call $clock_time_get (local.get 0) (local.get 1) (local.get 2))
+        )
+        (func (export "clock_res_get") (param i32 i32) (result i32)
+            (call $clock_res_get (local.get 0) (local.get 1))
+        )
+        (func (export "random_get") (param i32 i32) (result i32)
+            (call $random_get (local.get 0) (local.get 1))
+        )
+        (func (export "fd_read") (param i32 i32 i32 i32) (result i32)
+            (call $fd_read (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+        )
+        (func (export "fd_write") (param i32 i32 i32 i32) (result i32)
+            (call $fd_write (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+        )
+        (func (export "fd_close") (param i32) (result i32)
+            (call $fd_close (local.get 0))
+        )
+        (func (export "fd_prestat_get") (param i32 i32) (result i32)
+            (call $fd_prestat_get (local.get 0) (local.get 1))
+        )
+        (func (export "fd_prestat_dir_name") (param i32 i32 i32) (result i32)
+            (call $fd_prestat_dir_name (local.get 0) (local.get 1) (local.get 2))
+        )
+        (func (export "path_open") (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)
+            (call $path_open
+                (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4)
+                (local.get 5) (local.get 6) (local.get 7) (local.get 8)
+            )
+        )
+        (func (export "poll_oneoff") (param i32 i32 i32 i32) (result i32)
+            (call $poll_oneoff (local.get 0) (local.get 1) (local.get 2) (local.get 3))
+        )
+        (func (export "proc_exit") (param i32)
+            (call $proc_exit (local.get 0))
+        )
+    )
+"#;
+
+/// Instantiates the [`WAT`] module with the native WASI implementation driven by `host`.
+fn setup(host: TestHost) -> (Store<TestHost>, Instance, Memory) {
+    let engine = Engine::new(&Config::default());
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<TestHost>>::new(&engine);
+    add_to_linker(&mut linker, |host| host).unwrap();
+    let mut store = Store::new(&engine, host);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    (store, instance, memory)
+}
+
+/// Calls the exported WASI function `name` with `params` and returns its WASI error code.
+fn call<Params>(store: &mut Store<TestHost>, instance: &Instance, name: &str, params: Params) -> i32
+where
+    Params: WasmParams,
+{
+    instance
+        .get_typed_func::<Params, i32>(&*store, name)
+        .unwrap()
+        .call(store, params)
+        .unwrap()
+}
+
+/// Returns the little-endian `u32` at `ptr` of the `memory`.
+fn read_u32(store: &Store<TestHost>, memory: &Memory, ptr: usize) -> u32 {
+    let mut bytes = [0x00; 4];
+    memory.read(store, ptr, &mut bytes).unwrap();
+    u32::from_le_bytes(bytes)
+}
+
+/// Returns the `len` bytes at `ptr` of the `memory`.
+fn read_bytes(store: &Store<TestHost>, memory: &Memory, ptr: usize, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0x00; len];
+    memory.read(store, ptr, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn args_and_environ() {
+    let host = TestHost {
+        args: vec!["main.wasm".into(), "--flag".into()],
+        env: vec![("KEY".into(), "value".into())],
+        ..TestHost::default()
+    };
+    let (mut store, instance, memory) = setup(host);
+    assert_eq!(call(&mut store, &instance, "args_sizes_get", (0, 4)), 0);
+    assert_eq!(read_u32(&store, &memory, 0), 2);
+    assert_eq!(read_u32(&store, &memory, 4), 17);
+    assert_eq!(call(&mut store, &instance, "args_get", (0, 100)), 0);
+    assert_eq!(read_u32(&store, &memory, 0), 100);
+    assert_eq!(read_u32(&store, &memory, 4), 110);
+    assert_eq!(read_bytes(&store, &memory, 100, 17), b"main.wasm\0--flag\0");
+    assert_eq!(call(&mut store, &instance, "environ_get", (0, 200)), 0);
+    assert_eq!(read_u32(&store, &memory, 0), 200);
+    assert_eq!(read_bytes(&store, &memory, 200, 10), b"KEY=value\0");
+}
+
+#[test]
+fn clocks_and_randomness() {
+    let (mut store, instance, memory) = setup(TestHost::default());
+    assert_eq!(
+        call(&mut store, &instance, "clock_time_get", (0, 0_i64, 8)),
+        0
+    );
+    assert_eq!(
+        read_bytes(&store, &memory, 8, 8),
+        1_000_000_u64.to_le_bytes()
+    );
+    assert_eq!(
+        call(&mut store, &instance, "clock_time_get", (1, 0_i64, 8)),
+        0
+    );
+    assert_eq!(read_bytes(&store, &memory, 8, 8), 42_u64.to_le_bytes());
+    let inval = i32::from(Errno::Inval.code());
+    assert_eq!(
+        call(&mut store, &instance, "clock_time_get", (7, 0_i64, 8)),
+        inval
+    );
+    // The `TestHost` does not implement `clock_res_get`.
+    let nosys = i32::from(Errno::Nosys.code());
+    assert_eq!(call(&mut store, &instance, "clock_res_get", (0, 8)), nosys);
+    assert_eq!(call(&mut store, &instance, "random_get", (16, 4)), 0);
+    assert_eq!(read_bytes(&store, &memory, 16, 4), [0xAB; 4]);
+    let fault = i32::from(Errno::Fault.code());
+    assert_eq!(call(&mut store, &instance, "random_get", (65535, 2)), fault);
+}
+
+#[test]
+fn stdio() {
+    let host = TestHost {
+        stdin: b"input".to_vec(),
+        ..TestHost::default()
+    };
+    let (mut store, instance, memory) = setup(host);
+    // Two I/O vectors at 0 pointing to 100..103 and 200..210.
+    for (ptr, value) in [(0, 100), (4, 3), (8, 200), (12, 10)] {
+        memory
+            .write(&mut store, ptr, &u32::to_le_bytes(value))
+            .unwrap();
+    }
+    assert_eq!(call(&mut store, &instance, "fd_read", (0, 0, 2, 16)), 0);
+    assert_eq!(read_u32(&store, &memory, 16), 5);
+    assert_eq!(read_bytes(&store, &memory, 100, 3), b"inp");
+    assert_eq!(read_bytes(&store, &memory, 200, 2), b"ut");
+    assert_eq!(call(&mut store, &instance, "fd_write", (1, 0, 2, 16)), 0);
+    assert_eq!(read_u32(&store, &memory, 16), 13);
+    assert_eq!(&store.data().stdout[..5], b"input");
+    let badf = i32::from(Errno::Badf.code());
+    assert_eq!(call(&mut store, &instance, "fd_write", (5, 0, 2, 16)), badf);
+}
+
+#[test]
+fn preopened_dirs_and_files() {
+    let mut host = TestHost::default();
+    host.files
+        .insert("hello.txt".into(), b"hello world".to_vec());
+    let (mut store, instance, memory) = setup(host);
+    assert_eq!(
+        call(&mut store, &instance, "fd_prestat_get", (DATA_FD as i32, 0)),
+        0
+    );
+    assert_eq!(read_bytes(&store, &memory, 0, 1), [0]);
+    assert_eq!(read_u32(&store, &memory, 4), 5);
+    assert_eq!(
+        call(
+            &mut store,
+            &instance,
+            "fd_prestat_dir_name",
+            (DATA_FD as i32, 16, 5)
+        ),
+        0
+    );
+    assert_eq!(read_bytes(&store, &memory, 16, 5), b"/data");
+    let nametoolong = i32::from(Errno::Nametoolong.code());
+    assert_eq!(
+        call(
+            &mut store,
+            &instance,
+            "fd_prestat_dir_name",
+            (DATA_FD as i32, 16, 4)
+        ),
+        nametoolong
+    );
+    // Guests enumerate pre-opened directories until `fd_prestat_get` fails.
+    let badf = i32::from(Errno::Badf.code());
+    assert_eq!(
+        call(
+            &mut store,
+            &instance,
+            "fd_prestat_get",
+            (DATA_FD as i32 + 1, 0)
+        ),
+        badf
+    );
+    memory.write(&mut store, 100, b"hello.txt").unwrap();
+    let params = (DATA_FD as i32, 0, 100, 9, 0, 0_i64, 0_i64, 0, 32);
+    assert_eq!(call(&mut store, &instance, "path_open", params), 0);
+    let fd = read_u32(&store, &memory, 32) as i32;
+    for (ptr, value) in [(40, 200), (44, 64)] {
+        memory
+            .write(&mut store, ptr, &u32::to_le_bytes(value))
+            .unwrap();
+    }
+    assert_eq!(call(&mut store, &instance, "fd_read", (fd, 40, 1, 48)), 0);
+    assert_eq!(read_u32(&store, &memory, 48), 11);
+    assert_eq!(read_bytes(&store, &memory, 200, 11), b"hello world");
+    assert_eq!(call(&mut store, &instance, "fd_close", fd), 0);
+    assert_eq!(call(&mut store, &instance, "fd_close", fd), badf);
+    let noent = i32::from(Errno::Noent.code());
+    let params = (DATA_FD as i32, 0, 100, 5, 0, 0_i64, 0_i64, 0, 32);
+    assert_eq!(call(&mut store, &instance, "path_open", params), noent);
+}
+
+#[test]
+fn unsupported_funcs() {
+    let (mut store, instance, _) = setup(TestHost::default());
+    let nosys = i32::from(Errno::Nosys.code());
+    assert_eq!(
+        call(&mut store, &instance, "poll_oneoff", (0, 0, 0, 0)),
+        nosys
+    );
+}
+
+#[test]
+fn proc_exit() {
+    let (mut store, instance, _) = setup(TestHost::default());
+    let error = instance
+        .get_typed_func::<i32, ()>(&store, "proc_exit")
+        .unwrap()
+        .call(&mut store, 3)
+        .unwrap_err();
+    assert_eq!(error.i32_exit_status(), Some(3));
+}
+
+#[test]
+fn memory_by_index() {
+    let engine = Engine::new(&Config::default());
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<TestHost>>::new(&engine);
+    add_to_linker_with_memory(&mut linker, WasiMemory::Index(0), |host| host).unwrap();
+    let host = TestHost {
+        args: vec!["main.wasm".into()],
+        ..TestHost::default()
+    };
+    let mut store = Store::new(&engine, host);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    assert_eq!(call(&mut store, &instance, "args_sizes_get", (0, 4)), 0);
+    assert_eq!(read_u32(&store, &memory, 0), 1);
+    assert_eq!(read_u32(&store, &memory, 4), 10);
+}
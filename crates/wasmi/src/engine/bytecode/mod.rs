@@ -302,6 +302,13 @@ pub enum Instruction {
     F32Min,
     F32Max,
     F32Copysign,
+    /// Canonicalizes the `f32` value on top of the stack if it is NaN.
+    ///
+    /// # Note
+    ///
+    /// This is a `wasmi` utility instruction emitted after `f32` operations
+    /// that may produce NaN values if deterministic execution is enabled.
+    F32CanonicalizeNan,
     F64Abs,
     F64Neg,
     F64Ceil,
@@ -316,6 +323,13 @@ pub enum Instruction {
     F64Min,
     F64Max,
     F64Copysign,
+    /// Canonicalizes the `f64` value on top of the stack if it is NaN.
+    ///
+    /// # Note
+    ///
+    /// This is a `wasmi` utility instruction emitted after `f64` operations
+    /// that may produce NaN values if deterministic execution is enabled.
+    F64CanonicalizeNan,
     I32WrapI64,
     I32TruncF32S,
     I32TruncF32U,
@@ -5,7 +5,7 @@ use alloc::vec::Vec;
 use wasmi_arena::ArenaIndex;
 
 /// A reference to a compiled function stored in the [`CodeMap`] of an [`Engine`](crate::Engine).
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CompiledFunc(u32);
 
 impl ArenaIndex for CompiledFunc {
@@ -207,11 +207,19 @@ impl CodeMap {
         instrs.get(index)
     }
 
+    /// Returns the instructions of the compiled [`CompiledFunc`].
+    #[cfg(feature = "disassembler")]
+    pub fn instrs(&self, func_body: CompiledFunc) -> &[Instruction] {
+        let start = self.header(func_body).iref.to_usize();
+        let end = self.instr_end(func_body);
+        &self.instrs[start..end]
+    }
+
     /// Returns the `end` index of the instructions of [`CompiledFunc`].
     ///
     /// This is important to synthesize how many instructions there are in
     /// the function referred to by [`CompiledFunc`].
-    #[cfg(test)]
+    #[cfg(any(test, feature = "disassembler"))]
     pub fn instr_end(&self, func_body: CompiledFunc) -> usize {
         self.headers
             .get(func_body.into_usize() + 1)
@@ -37,8 +37,20 @@ pub struct Config {
     consume_fuel: bool,
     /// The fuel consumption mode of the `wasmi` [`Engine`](crate::Engine).
     fuel_consumption_mode: FuelConsumptionMode,
+    /// Is `true` if consumed fuel is attributed to the executing Wasm functions.
+    #[cfg(feature = "fuel_accounting")]
+    fuel_accounting: bool,
     /// The configured fuel costs of all `wasmi` bytecode instructions.
     fuel_costs: FuelCosts,
+    /// Is `true` if linear memories are initialized from pre-computed memory images.
+    memory_init_cow: bool,
+    /// The strategy used to allocate Wasm instance entities.
+    allocation_strategy: InstanceAllocationStrategy,
+    /// Is `true` if the [`Engine`](crate::Engine) guarantees deterministic execution.
+    deterministic: bool,
+    /// Is `true` if executions of Wasm functions are profiled.
+    #[cfg(feature = "profiler")]
+    profiling: bool,
 }
 
 /// The fuel consumption mode of the `wasmi` [`Engine`].
@@ -89,6 +101,136 @@ pub enum FuelConsumptionMode {
     Eager,
 }
 
+/// The strategy used by the [`Engine`] to allocate linear memories, tables and stacks.
+///
+/// The default allocation strategy is [`InstanceAllocationStrategy::OnDemand`].
+///
+/// [`Engine`]: crate::Engine
+#[derive(Debug, Default, Copy, Clone)]
+pub enum InstanceAllocationStrategy {
+    /// Linear memories, tables and stacks are allocated whenever they are needed.
+    #[default]
+    OnDemand,
+    /// Linear memories, tables and stacks are taken from pre-reserved pools.
+    ///
+    /// # Note
+    ///
+    /// Pooling makes instantiation cheaper since no allocations are required
+    /// for the linear memories and tables defined by a Wasm module. Upon dropping
+    /// the [`Store`] owning them they are reset and returned to their pools.
+    /// Instantiation fails if a pool has no free slots left or if a linear
+    /// memory or table does not fit into the size limits of a pool slot.
+    ///
+    /// [`Store`]: crate::Store
+    Pooling(PoolingAllocationConfig),
+}
+
+/// The limits of the [`InstanceAllocationStrategy::Pooling`] allocation strategy.
+#[derive(Debug, Copy, Clone)]
+pub struct PoolingAllocationConfig {
+    /// The total number of linear memory slots.
+    total_memories: u32,
+    /// The maximum number of pages of a linear memory slot.
+    memory_pages: u32,
+    /// The total number of table slots.
+    total_tables: u32,
+    /// The maximum number of elements of a table slot.
+    table_elements: u32,
+    /// The total number of pre-allocated stacks.
+    total_stacks: u32,
+}
+
+impl Default for PoolingAllocationConfig {
+    fn default() -> Self {
+        Self {
+            total_memories: 100,
+            memory_pages: 160,
+            total_tables: 100,
+            table_elements: 10_000,
+            total_stacks: 4,
+        }
+    }
+}
+
+impl PoolingAllocationConfig {
+    /// Sets the total number of linear memory slots of the pool.
+    ///
+    /// # Note
+    ///
+    /// Defaults to 100.
+    pub fn total_memories(&mut self, amount: u32) -> &mut Self {
+        self.total_memories = amount;
+        self
+    }
+
+    /// Sets the maximum number of Wasm pages of each linear memory slot of the pool.
+    ///
+    /// # Note
+    ///
+    /// - Each linear memory slot reserves this amount of memory up front.
+    /// - Defaults to 160 pages which is 10 MiB.
+    pub fn memory_pages(&mut self, amount: u32) -> &mut Self {
+        self.memory_pages = amount;
+        self
+    }
+
+    /// Sets the total number of table slots of the pool.
+    ///
+    /// # Note
+    ///
+    /// Defaults to 100.
+    pub fn total_tables(&mut self, amount: u32) -> &mut Self {
+        self.total_tables = amount;
+        self
+    }
+
+    /// Sets the maximum number of elements of each table slot of the pool.
+    ///
+    /// # Note
+    ///
+    /// Defaults to 10 000.
+    pub fn table_elements(&mut self, amount: u32) -> &mut Self {
+        self.table_elements = amount;
+        self
+    }
+
+    /// Sets the total number of stacks that are pre-allocated and kept for reuse.
+    ///
+    /// # Note
+    ///
+    /// - Executions still allocate new stacks if all pooled stacks are in use.
+    /// - Defaults to 4.
+    pub fn total_stacks(&mut self, amount: u32) -> &mut Self {
+        self.total_stacks = amount;
+        self
+    }
+
+    /// Returns the total number of linear memory slots of the pool.
+    pub(crate) fn get_total_memories(&self) -> u32 {
+        self.total_memories
+    }
+
+    /// Returns the maximum number of Wasm pages of each linear memory slot of the pool.
+    pub(crate) fn get_memory_pages(&self) -> u32 {
+        self.memory_pages
+    }
+
+    /// Returns the total number of table slots of the pool.
+    pub(crate) fn get_total_tables(&self) -> u32 {
+        self.total_tables
+    }
+
+    /// Returns the maximum number of elements of each table slot of the pool.
+    pub(crate) fn get_table_elements(&self) -> u32 {
+        self.table_elements
+    }
+
+    /// Returns the total number of pre-allocated stacks.
+    pub(crate) fn get_total_stacks(&self) -> u32 {
+        self.total_stacks
+    }
+}
+
 /// Type storing all kinds of fuel costs of instructions.
 #[derive(Debug, Copy, Clone)]
 pub struct FuelCosts {
@@ -208,6 +350,13 @@ impl Default for Config {
             consume_fuel: false,
             fuel_costs: FuelCosts::default(),
             fuel_consumption_mode: FuelConsumptionMode::default(),
+            #[cfg(feature = "fuel_accounting")]
+            fuel_accounting: false,
+            memory_init_cow: false,
+            allocation_strategy: InstanceAllocationStrategy::default(),
+            deterministic: false,
+            #[cfg(feature = "profiler")]
+            profiling: false,
         }
     }
 }
@@ -400,18 +549,175 @@ impl Config {
             .then_some(self.fuel_consumption_mode)
     }
 
+    /// Enables or disables per-function fuel accounting for the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// If enabled every [`Store`] of the [`Engine`] attributes the fuel consumed by Wasm
+    /// executions to the executing Wasm functions which can be queried via [`Store::fuel_report`].
+    ///
+    /// This has no effect if fuel metering is disabled for the [`Engine`].
+    ///
+    /// Disabled by default.
+    ///
+    /// [`Store`]: crate::Store
+    /// [`Store::fuel_report`]: crate::Store::fuel_report
+    /// [`Engine`]: crate::Engine
+    #[cfg(feature = "fuel_accounting")]
+    pub fn fuel_accounting(&mut self, enable: bool) -> &mut Self {
+        self.fuel_accounting = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables per-function fuel accounting.
+    ///
+    /// Returns `false` if fuel metering is disabled for the [`Engine`].
+    ///
+    /// [`Engine`]: crate::Engine
+    #[cfg(feature = "fuel_accounting")]
+    pub(crate) fn get_fuel_accounting(&self) -> bool {
+        self.get_consume_fuel() && self.fuel_accounting
+    }
+
+    /// Configures whether linear memories are initialized from copy-on-write memory images.
+    ///
+    /// # Note
+    ///
+    /// If enabled the initial contents of all internally defined linear memories
+    /// of a [`Module`] are computed once upon [`Module`] creation if all of their
+    /// active data segments have constant offsets. Instantiating the [`Module`]
+    /// then initializes those linear memories from their images instead of
+    /// copying all data segments one by one.
+    ///
+    /// - With the `virtual_memory` crate feature on 64-bit Linux the images are
+    ///   mapped copy-on-write into each new linear memory which makes their
+    ///   initialization independent of the size of the data segments.
+    /// - On all other platforms the images are copied into the linear memories.
+    /// - This has no effect with the [`InstanceAllocationStrategy::Pooling`] strategy.
+    /// - If the host fails to create a memory image, e.g. because `memfd_create`
+    ///   is not permitted, [`Module::new`] returns an error instead of silently
+    ///   falling back to initializing from data segments.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`Module`]: crate::Module
+    /// [`Module::new`]: crate::Module::new
+    pub fn memory_init_cow(&mut self, enable: bool) -> &mut Self {
+        self.memory_init_cow = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables copy-on-write memory initialization.
+    pub(crate) fn get_memory_init_cow(&self) -> bool {
+        self.memory_init_cow
+            && matches!(
+                self.allocation_strategy,
+                InstanceAllocationStrategy::OnDemand
+            )
+    }
+
+    /// Configures the [`InstanceAllocationStrategy`] for the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// - Defaults to [`InstanceAllocationStrategy::OnDemand`].
+    /// - With [`InstanceAllocationStrategy::Pooling`] the slots of the pools are
+    ///   reserved on demand upon instantiation. Failing to reserve a slot fails
+    ///   the instantiation instead of the creation of the [`Engine`].
+    /// - With [`InstanceAllocationStrategy::Pooling`] linear memories are always
+    ///   initialized from their data segments and [`Config::memory_init_cow`] is ignored.
+    ///
+    /// [`Engine`]: crate::Engine
+    pub fn allocation_strategy(&mut self, strategy: InstanceAllocationStrategy) -> &mut Self {
+        self.allocation_strategy = strategy;
+        self
+    }
+
+    /// Returns the [`InstanceAllocationStrategy`] of the [`Config`].
+    pub(crate) fn get_allocation_strategy(&self) -> InstanceAllocationStrategy {
+        self.allocation_strategy
+    }
+
+    /// Configures the [`Engine`] for deterministic execution.
+    ///
+    /// # Note
+    ///
+    /// If enabled the execution of Wasm code yields bit-identical results on all hosts
+    /// which is required for example by consensus workloads. This
+    ///
+    /// - canonicalizes NaN values produced by float arithmetic so that their sign and
+    ///   payload bits no longer depend on the host.
+    /// - disables all Wasm proposals that are not enabled by the default [`Config`] so
+    ///   that enabling non-default proposals cannot make hosts accept different Wasm modules.
+    ///   Proposals that have been disabled explicitly stay disabled.
+    /// - aborts the execution with the host's [`MemoryError`] if `memory.grow` fails due to
+    ///   host resource exhaustion instead of returning an error code to the Wasm code.
+    ///   This includes growing beyond the slot size of the [`InstanceAllocationStrategy::Pooling`]
+    ///   strategy. The [`MemoryError`] is a host error and not a Wasm trap since whether it
+    ///   occurs depends on the host. Growing beyond the limits of a linear memory still
+    ///   returns an error code.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`Engine`]: crate::Engine
+    /// [`MemoryError`]: crate::errors::MemoryError
+    pub fn deterministic(&mut self, enable: bool) -> &mut Self {
+        self.deterministic = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables deterministic execution.
+    pub(crate) fn get_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Enables or disables profiling of Wasm executions for the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// If enabled every [`Store`] of the [`Engine`] records the Wasm call tree
+    /// together with the number of executed instructions per call stack which
+    /// can be queried via [`Store::profile`].
+    ///
+    /// Profiling slows down execution and is disabled by default.
+    ///
+    /// [`Engine`]: crate::Engine
+    /// [`Store`]: crate::Store
+    /// [`Store::profile`]: crate::Store::profile
+    #[cfg(feature = "profiler")]
+    pub fn profiling(&mut self, enable: bool) -> &mut Self {
+        self.profiling = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables profiling of Wasm executions.
+    #[cfg(feature = "profiler")]
+    pub(crate) fn get_profiling(&self) -> bool {
+        self.profiling
+    }
+
     /// Returns the [`WasmFeatures`] represented by the [`Config`].
+    ///
+    /// # Note
+    ///
+    /// If deterministic execution is enabled only the Wasm proposals that are
+    /// enabled by both the [`Config`] and the default [`Config`] are returned.
     pub(crate) fn wasm_features(&self) -> WasmFeatures {
+        let allowed = match self.deterministic {
+            true => Self::default(),
+            false => *self,
+        };
         WasmFeatures {
-            multi_value: self.multi_value,
-            mutable_global: self.mutable_global,
-            saturating_float_to_int: self.saturating_float_to_int,
-            sign_extension: self.sign_extension,
-            bulk_memory: self.bulk_memory,
-            reference_types: self.reference_types,
-            tail_call: self.tail_call,
-            extended_const: self.extended_const,
-            floats: self.floats,
+            multi_value: self.multi_value && allowed.multi_value,
+            mutable_global: self.mutable_global && allowed.mutable_global,
+            saturating_float_to_int: self.saturating_float_to_int
+                && allowed.saturating_float_to_int,
+            sign_extension: self.sign_extension && allowed.sign_extension,
+            bulk_memory: self.bulk_memory && allowed.bulk_memory,
+            reference_types: self.reference_types && allowed.reference_types,
+            tail_call: self.tail_call && allowed.tail_call,
+            extended_const: self.extended_const && allowed.extended_const,
+            floats: self.floats && allowed.floats,
             component_model: false,
             simd: false,
             relaxed_simd: false,
@@ -0,0 +1,295 @@
+//! Human readable rendering of the `wasmi` bytecode of compiled functions.
+
+use super::{
+    bytecode::{BranchOffset, DropKeep, Instruction},
+    CompiledFunc,
+    EngineResources,
+};
+use crate::Module;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display, Write as _};
+
+/// The disassembled `wasmi` bytecode of a compiled function.
+///
+/// Returned by [`Engine::disassemble`] and rendered via its [`Display`] implementation.
+///
+/// [`Engine::disassemble`]: crate::Engine::disassemble
+#[derive(Debug, Clone)]
+pub struct Disassembly {
+    /// The function index of the disassembled function within its [`Module`].
+    func_index: u32,
+    /// The number of local variables of the function excluding its parameters.
+    len_locals: usize,
+    /// The maximum value stack height of the function.
+    max_stack_height: usize,
+    /// The rendered instructions in the order of their position.
+    instrs: Vec<String>,
+}
+
+impl Disassembly {
+    /// Returns the function index of the disassembled function within its [`Module`].
+    pub fn func_index(&self) -> u32 {
+        self.func_index
+    }
+
+    /// Returns the number of instructions of the disassembled function.
+    pub fn len_instrs(&self) -> usize {
+        self.instrs.len()
+    }
+
+    /// Returns the rendered instructions of the disassembled function.
+    pub fn instrs(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.instrs.iter().map(String::as_str)
+    }
+}
+
+impl Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "func[{}]: locals: {}, max stack height: {}",
+            self.func_index, self.len_locals, self.max_stack_height
+        )?;
+        let width = self.instrs.len().saturating_sub(1).to_string().len();
+        for (index, instr) in self.instrs.iter().enumerate() {
+            writeln!(f, "  {index:>width$}: {instr}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Disassembles the internal function at `func_index` of the `module`.
+///
+/// Returns `None` if `func_index` does not refer to an internal function of the `module`.
+pub fn disassemble(res: &EngineResources, module: &Module, func_index: u32) -> Option<Disassembly> {
+    let len_imported = module.len_funcs() - module.internal_funcs().len();
+    let internal_index = (func_index as usize).checked_sub(len_imported)?;
+    let (_, func_body) = module.internal_funcs().nth(internal_index)?;
+    let header = res.code_map.header(func_body);
+    let instrs = res.code_map.instrs(func_body);
+    let resolve_func = |func_body: CompiledFunc| -> Option<usize> {
+        module
+            .internal_funcs()
+            .position(|(_, compiled)| compiled == func_body)
+            .map(|position| position + len_imported)
+    };
+    let mut rendered = Vec::with_capacity(instrs.len());
+    // The number of upcoming instructions that only store parameters of a preceding instruction.
+    let mut len_params = 0;
+    // The number of upcoming `br_table` targets which always occupy two instructions each.
+    let mut len_targets = 0;
+    for (index, instr) in instrs.iter().enumerate() {
+        let mut line = String::new();
+        if len_params > 0 {
+            len_params -= 1;
+            line.push_str("  ^ ");
+            render_param(&mut line, instr);
+        } else {
+            len_params = render_instr(&mut line, res, index, instr, &resolve_func);
+            if len_targets > 0 {
+                len_targets -= 1;
+                len_params = 1;
+            }
+            if let Instruction::BrTable(targets) = instr {
+                len_targets = targets.to_usize();
+            }
+        }
+        rendered.push(line);
+    }
+    Some(Disassembly {
+        func_index,
+        len_locals: header.len_locals(),
+        max_stack_height: header.max_stack_height(),
+        instrs: rendered,
+    })
+}
+
+/// Returns the absolute branch target of the instruction at `index` with `offset`.
+fn branch_target(index: usize, offset: BranchOffset) -> i64 {
+    index as i64 + i64::from(offset.to_i32())
+}
+
+/// Renders the [`DropKeep`] into `line` if it is not a no-op.
+fn render_drop_keep(line: &mut String, drop_keep: DropKeep) {
+    if !drop_keep.is_noop() {
+        let _ = write!(
+            line,
+            " (drop: {}, keep: {})",
+            drop_keep.drop(),
+            drop_keep.keep()
+        );
+    }
+}
+
+/// Renders the parameter stored in `instr` for a preceding instruction into `line`.
+fn render_param(line: &mut String, instr: &Instruction) {
+    match *instr {
+        Instruction::Return(drop_keep) => {
+            let _ = write!(
+                line,
+                "drop: {}, keep: {}",
+                drop_keep.drop(),
+                drop_keep.keep()
+            );
+        }
+        Instruction::TableGet(table) => {
+            let _ = write!(line, "table[{}]", table.to_u32());
+        }
+        instr => {
+            let _ = write!(line, "{instr:?}");
+        }
+    }
+}
+
+/// Renders the `instr` at `index` into `line`.
+///
+/// Returns the number of subsequent instructions that only store parameters of `instr`.
+fn render_instr(
+    line: &mut String,
+    res: &EngineResources,
+    index: usize,
+    instr: &Instruction,
+    resolve_func: &dyn Fn(CompiledFunc) -> Option<usize>,
+) -> usize {
+    let mut len_params = 0;
+    let _ = match *instr {
+        Instruction::LocalGet(depth) => write!(line, "LocalGet depth={}", depth.to_usize()),
+        Instruction::LocalSet(depth) => write!(line, "LocalSet depth={}", depth.to_usize()),
+        Instruction::LocalTee(depth) => write!(line, "LocalTee depth={}", depth.to_usize()),
+        Instruction::Br(offset) => write!(line, "Br -> {}", branch_target(index, offset)),
+        Instruction::BrIfEqz(offset) => {
+            write!(line, "BrIfEqz -> {}", branch_target(index, offset))
+        }
+        Instruction::BrIfNez(offset) => {
+            write!(line, "BrIfNez -> {}", branch_target(index, offset))
+        }
+        Instruction::BrAdjust(offset) => {
+            len_params = 1;
+            write!(line, "BrAdjust -> {}", branch_target(index, offset))
+        }
+        Instruction::BrAdjustIfNez(offset) => {
+            len_params = 1;
+            write!(line, "BrAdjustIfNez -> {}", branch_target(index, offset))
+        }
+        Instruction::BrTable(targets) => write!(line, "BrTable targets={}", targets.to_usize()),
+        Instruction::ConsumeFuel(fuel) => write!(line, "ConsumeFuel {}", fuel.to_u64()),
+        Instruction::Return(drop_keep) => {
+            line.push_str("Return");
+            render_drop_keep(line, drop_keep);
+            Ok(())
+        }
+        Instruction::ReturnIfNez(drop_keep) => {
+            line.push_str("ReturnIfNez");
+            render_drop_keep(line, drop_keep);
+            Ok(())
+        }
+        Instruction::ReturnCallInternal(func_body) => {
+            len_params = 1;
+            render_call(line, "ReturnCallInternal", func_body, resolve_func)
+        }
+        Instruction::ReturnCall(func) => {
+            len_params = 1;
+            write!(line, "ReturnCall func[{}]", func.to_u32())
+        }
+        Instruction::ReturnCallIndirect(signature) => {
+            len_params = 2;
+            write!(line, "ReturnCallIndirect signature[{}]", signature.to_u32())
+        }
+        Instruction::CallInternal(func_body) => {
+            render_call(line, "CallInternal", func_body, resolve_func)
+        }
+        Instruction::Call(func) => write!(line, "Call func[{}]", func.to_u32()),
+        Instruction::CallIndirect(signature) => {
+            len_params = 1;
+            write!(line, "CallIndirect signature[{}]", signature.to_u32())
+        }
+        Instruction::GlobalGet(global) => write!(line, "GlobalGet global[{}]", global.to_u32()),
+        Instruction::GlobalSet(global) => write!(line, "GlobalSet global[{}]", global.to_u32()),
+        Instruction::I32Load(offset)
+        | Instruction::I64Load(offset)
+        | Instruction::F32Load(offset)
+        | Instruction::F64Load(offset)
+        | Instruction::I32Load8S(offset)
+        | Instruction::I32Load8U(offset)
+        | Instruction::I32Load16S(offset)
+        | Instruction::I32Load16U(offset)
+        | Instruction::I64Load8S(offset)
+        | Instruction::I64Load8U(offset)
+        | Instruction::I64Load16S(offset)
+        | Instruction::I64Load16U(offset)
+        | Instruction::I64Load32S(offset)
+        | Instruction::I64Load32U(offset)
+        | Instruction::I32Store(offset)
+        | Instruction::I64Store(offset)
+        | Instruction::F32Store(offset)
+        | Instruction::F64Store(offset)
+        | Instruction::I32Store8(offset)
+        | Instruction::I32Store16(offset)
+        | Instruction::I64Store8(offset)
+        | Instruction::I64Store16(offset)
+        | Instruction::I64Store32(offset) => {
+            write!(line, "{} offset={}", name_of(instr), offset.into_inner())
+        }
+        Instruction::MemoryInit(segment) | Instruction::DataDrop(segment) => {
+            write!(line, "{} data[{}]", name_of(instr), segment.to_u32())
+        }
+        Instruction::TableCopy(table) => {
+            len_params = 1;
+            write!(line, "TableCopy table[{}]", table.to_u32())
+        }
+        Instruction::TableSize(table)
+        | Instruction::TableGrow(table)
+        | Instruction::TableFill(table)
+        | Instruction::TableGet(table)
+        | Instruction::TableSet(table) => {
+            write!(line, "{} table[{}]", name_of(instr), table.to_u32())
+        }
+        Instruction::TableInit(segment) => {
+            len_params = 1;
+            write!(line, "TableInit elem[{}]", segment.to_u32())
+        }
+        Instruction::ElemDrop(segment) => write!(line, "ElemDrop elem[{}]", segment.to_u32()),
+        Instruction::RefFunc(func) => write!(line, "RefFunc func[{}]", func.to_u32()),
+        Instruction::Const32(bytes) => {
+            let bits = u32::from_ne_bytes(bytes);
+            write!(line, "Const32 0x{bits:08X} (i32: {})", bits as i32)
+        }
+        Instruction::I64Const32(value) => write!(line, "I64Const32 {value}"),
+        Instruction::ConstRef(cref) => match res.const_pool.get(cref) {
+            Some(value) => write!(
+                line,
+                "ConstRef const[{}] = 0x{:016X}",
+                cref.to_usize(),
+                value.to_bits()
+            ),
+            None => write!(line, "ConstRef const[{}] = <unresolved>", cref.to_usize()),
+        },
+        _ => write!(line, "{instr:?}"),
+    };
+    len_params
+}
+
+/// Renders a call to the compiled `func_body` named `name` into `line`.
+fn render_call(
+    line: &mut String,
+    name: &str,
+    func_body: CompiledFunc,
+    resolve_func: &dyn Fn(CompiledFunc) -> Option<usize>,
+) -> fmt::Result {
+    match resolve_func(func_body) {
+        Some(func_index) => write!(line, "{name} func[{func_index}]"),
+        None => write!(line, "{name} {func_body:?}"),
+    }
+}
+
+/// Returns the name of the [`Instruction`] without its parameters.
+fn name_of(instr: &Instruction) -> String {
+    let mut name = alloc::format!("{instr:?}");
+    if let Some(end) = name.find('(') {
+        name.truncate(end);
+    }
+    name
+}
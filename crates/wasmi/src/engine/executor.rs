@@ -1,3 +1,5 @@
+#[cfg(feature = "profiler")]
+use super::profiler::Profile;
 use super::{bytecode::BranchOffset, const_pool::ConstRef, CompiledFunc, ConstPoolView};
 use crate::{
     core::TrapCode,
@@ -23,6 +25,7 @@ use crate::{
         FuncFrame,
         ValueStack,
     },
+    errors::MemoryError,
     func::FuncEntity,
     table::TableEntity,
     FuelConsumptionMode,
@@ -99,8 +102,50 @@ pub fn execute_wasm<'engine>(
     call_stack: &'engine mut CallStack,
     code_map: &'engine CodeMap,
     const_pool: ConstPoolView<'engine>,
-) -> Result<WasmOutcome, TrapCode> {
-    Executor::new(ctx, cache, value_stack, call_stack, code_map, const_pool).execute()
+) -> Result<WasmOutcome, ExecutionError> {
+    // Profiling and fuel accounting are decided once per execution so that
+    // executions without them do not pay for checking them upon every
+    // executed instruction, call or fuel charge.
+    #[cfg(feature = "profiler")]
+    if ctx.profile_mut().is_some() {
+        return execute_wasm_with::<true>(
+            ctx,
+            cache,
+            value_stack,
+            call_stack,
+            code_map,
+            const_pool,
+        );
+    }
+    execute_wasm_with::<false>(ctx, cache, value_stack, call_stack, code_map, const_pool)
+}
+
+/// Executes the Wasm function frame on top of the `call_stack` with profiling if `PROFILE` is `true`.
+///
+/// Attributes the consumed fuel to the executing Wasm functions if fuel accounting is enabled.
+#[inline(always)]
+fn execute_wasm_with<'engine, const PROFILE: bool>(
+    ctx: &mut StoreInner,
+    cache: &'engine mut InstanceCache,
+    value_stack: &'engine mut ValueStack,
+    call_stack: &'engine mut CallStack,
+    code_map: &'engine CodeMap,
+    const_pool: ConstPoolView<'engine>,
+) -> Result<WasmOutcome, ExecutionError> {
+    #[cfg(feature = "fuel_accounting")]
+    if ctx.fuel_accounting_mut().is_some() {
+        return Executor::<PROFILE, true>::new(
+            ctx,
+            cache,
+            value_stack,
+            call_stack,
+            code_map,
+            const_pool,
+        )
+        .execute();
+    }
+    Executor::<PROFILE, false>::new(ctx, cache, value_stack, call_stack, code_map, const_pool)
+        .execute()
 }
 
 /// The function signature of Wasm load operations.
@@ -115,13 +160,35 @@ type WasmStoreOp = fn(
     value: UntypedValue,
 ) -> Result<(), TrapCode>;
 
+/// An error that can occur upon Wasm execution.
+#[derive(Debug)]
+pub enum ExecutionError {
+    /// The Wasm execution trapped.
+    TrapCode(TrapCode),
+    /// The Wasm execution failed due to the host.
+    ///
+    /// # Note
+    ///
+    /// This is used upon deterministic execution for host dependent failures
+    /// that must not be observable by the executed Wasm code.
+    Host(MemoryError),
+}
+
+impl From<TrapCode> for ExecutionError {
+    #[inline]
+    fn from(trap_code: TrapCode) -> Self {
+        Self::TrapCode(trap_code)
+    }
+}
+
 /// An error that can occur upon `memory.grow` or `table.grow`.
-#[derive(Copy, Clone)]
 pub enum EntityGrowError {
     /// Usually a [`TrapCode::OutOfFuel`] trap.
     TrapCode(TrapCode),
     /// Encountered when `memory.grow` or `table.grow` fails.
     InvalidGrow,
+    /// Encountered when `memory.grow` fails due to the host upon deterministic execution.
+    Host(MemoryError),
 }
 
 impl From<TrapCode> for EntityGrowError {
@@ -135,8 +202,14 @@ impl From<TrapCode> for EntityGrowError {
 const INVALID_GROWTH_ERRCODE: u32 = u32::MAX;
 
 /// An execution context for executing a `wasmi` function frame.
+///
+/// - Records every executed instruction, call and return in the profile if `PROFILE` is `true`.
+/// - Attributes consumed fuel to the executing Wasm functions if `FUEL_ACCOUNTING` is `true`.
+///
+/// Both are only available with the `profiler` and `fuel_accounting` crate features respectively
+/// since every combination of them duplicates the instruction dispatch loop.
 #[derive(Debug)]
-struct Executor<'ctx, 'engine> {
+struct Executor<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool> {
     /// Stores the value stack of live values on the Wasm stack.
     sp: ValueStackPtr,
     /// The pointer to the currently executed instruction.
@@ -185,7 +258,9 @@ macro_rules! forward_call {
     }};
 }
 
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     /// Creates a new [`Executor`] for executing a `wasmi` function frame.
     #[inline(always)]
     pub fn new(
@@ -213,9 +288,13 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
 
     /// Executes the function frame until it returns or traps.
     #[inline(always)]
-    fn execute(mut self) -> Result<WasmOutcome, TrapCode> {
+    fn execute(mut self) -> Result<WasmOutcome, ExecutionError> {
         use Instruction as Instr;
         loop {
+            #[cfg(feature = "profiler")]
+            if PROFILE {
+                self.profile(Profile::count_instr);
+            }
             match *self.ip.get() {
                 Instr::LocalGet(local_depth) => self.visit_local_get(local_depth),
                 Instr::LocalSet(local_depth) => self.visit_local_set(local_depth),
@@ -381,6 +460,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 Instr::F32Min => self.visit_f32_min(),
                 Instr::F32Max => self.visit_f32_max(),
                 Instr::F32Copysign => self.visit_f32_copysign(),
+                Instr::F32CanonicalizeNan => self.visit_f32_canonicalize_nan(),
                 Instr::F64Abs => self.visit_f64_abs(),
                 Instr::F64Neg => self.visit_f64_neg(),
                 Instr::F64Ceil => self.visit_f64_ceil(),
@@ -395,6 +475,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 Instr::F64Min => self.visit_f64_min(),
                 Instr::F64Max => self.visit_f64_max(),
                 Instr::F64Copysign => self.visit_f64_copysign(),
+                Instr::F64CanonicalizeNan => self.visit_f64_canonicalize_nan(),
                 Instr::I32WrapI64 => self.visit_i32_wrap_i64(),
                 Instr::I32TruncF32S => self.visit_i32_trunc_f32_s()?,
                 Instr::I32TruncF32U => self.visit_i32_trunc_f32_u()?,
@@ -616,14 +697,21 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         }
         match self.ctx.resolve_func(func) {
             FuncEntity::Wasm(wasm_func) => {
-                let header = self.code_map.header(wasm_func.func_body());
+                let func_body = wasm_func.func_body();
+                let instance = *wasm_func.instance();
+                self.record_call(func_body, kind);
+                let header = self.code_map.header(func_body);
                 self.value_stack.prepare_wasm_call(header)?;
                 self.sp = self.value_stack.stack_ptr();
-                self.cache.update_instance(wasm_func.instance());
+                self.cache.update_instance(&instance);
                 self.ip = self.code_map.instr_ptr(header.iref());
                 Ok(CallOutcome::Continue)
             }
             FuncEntity::Host(_host_func) => {
+                // A tail call to a host function ends the calling Wasm function.
+                if let CallKind::Tail = kind {
+                    self.record_return();
+                }
                 self.cache.reset();
                 Ok(CallOutcome::Call {
                     host_func: *func,
@@ -649,6 +737,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
             self.call_stack
                 .push(FuncFrame::new(self.ip, self.cache.instance()))?;
         }
+        self.record_call(func, kind);
         let header = self.code_map.header(func);
         self.value_stack.prepare_wasm_call(header)?;
         self.sp = self.value_stack.stack_ptr();
@@ -664,6 +753,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     fn ret(&mut self, drop_keep: DropKeep) -> ReturnOutcome {
         self.sp.drop_keep(drop_keep);
         self.sync_stack_ptr();
+        self.record_return();
         match self.call_stack.pop() {
             Some(caller) => {
                 self.ip = caller.ip();
@@ -674,6 +764,69 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         }
     }
 
+    /// Records a call of `kind` to the Wasm function `func` for profiling and fuel accounting.
+    #[cfg_attr(
+        not(any(feature = "profiler", feature = "fuel_accounting")),
+        allow(unused_variables)
+    )]
+    #[inline(always)]
+    fn record_call(&mut self, func: CompiledFunc, kind: CallKind) {
+        #[cfg(feature = "profiler")]
+        if PROFILE {
+            self.profile(|profile| match kind {
+                CallKind::Nested => profile.call(func),
+                CallKind::Tail => profile.tail_call(func),
+            });
+        }
+        #[cfg(feature = "fuel_accounting")]
+        if FUEL_ACCOUNTING {
+            if let Some(accounting) = self.ctx.fuel_accounting_mut() {
+                match kind {
+                    CallKind::Nested => accounting.call(func),
+                    CallKind::Tail => accounting.tail_call(func),
+                }
+            }
+        }
+    }
+
+    /// Records a return from the executing Wasm function for profiling and fuel accounting.
+    #[inline(always)]
+    fn record_return(&mut self) {
+        #[cfg(feature = "profiler")]
+        if PROFILE {
+            self.profile(Profile::ret);
+        }
+        #[cfg(feature = "fuel_accounting")]
+        if FUEL_ACCOUNTING {
+            if let Some(accounting) = self.ctx.fuel_accounting_mut() {
+                accounting.ret();
+            }
+        }
+    }
+
+    /// Attributes `delta` consumed fuel to the executing Wasm function if fuel accounting is enabled.
+    #[cfg(feature = "fuel_accounting")]
+    #[inline(always)]
+    fn account_fuel(&mut self, delta: u64) {
+        if !FUEL_ACCOUNTING {
+            return;
+        }
+        if let Some(accounting) = self.ctx.fuel_accounting_mut() {
+            accounting.charge(delta);
+        }
+    }
+
+    /// Records a profiling event via `f` in the profile of the [`StoreInner`].
+    ///
+    /// Must only be called if `PROFILE` is `true`.
+    #[cfg(feature = "profiler")]
+    #[inline(always)]
+    fn profile(&mut self, f: impl FnOnce(&mut Profile)) {
+        if let Some(profile) = self.ctx.profile_mut() {
+            f(profile)
+        }
+    }
+
     /// Consume an amount of fuel specified by `delta` if `exec` succeeds.
     ///
     /// # Note
@@ -752,6 +905,8 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
             .fuel_mut()
             .consume_fuel(delta)
             .expect("remaining fuel has already been approved prior");
+        #[cfg(feature = "fuel_accounting")]
+        self.account_fuel(delta);
         Ok(result)
     }
 
@@ -771,6 +926,8 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         E: From<TrapCode>,
     {
         self.ctx.fuel_mut().consume_fuel(delta)?;
+        #[cfg(feature = "fuel_accounting")]
+        self.account_fuel(delta);
         exec(self)
     }
 
@@ -823,7 +980,9 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     }
 }
 
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     #[inline(always)]
     fn visit_unreachable(&mut self) -> Result<(), TrapCode> {
         Err(TrapCode::UnreachableCodeReached).map_err(Into::into)
@@ -835,6 +994,8 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         // these `wasmi` instructions are only generated if fuel metering
         // is enabled to begin with.
         self.ctx.fuel_mut().consume_fuel(block_fuel.to_u64())?;
+        #[cfg(feature = "fuel_accounting")]
+        self.account_fuel(block_fuel.to_u64());
         self.try_next_instr()
     }
 
@@ -1077,14 +1238,14 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     }
 
     #[inline(always)]
-    fn visit_memory_grow(&mut self) -> Result<(), TrapCode> {
+    fn visit_memory_grow(&mut self) -> Result<(), ExecutionError> {
         let delta: u32 = self.sp.pop_as();
         let delta = match Pages::new(delta) {
             Some(pages) => pages,
             None => {
                 // Cannot grow memory so we push the expected error value.
                 self.sp.push_as(INVALID_GROWTH_ERRCODE);
-                return self.try_next_instr();
+                return self.try_next_instr().map_err(Into::into);
             }
         };
         let result = self.consume_fuel_with(
@@ -1099,7 +1260,17 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                     .resolve_memory_mut(memory)
                     .grow(delta)
                     .map(u32::from)
-                    .map_err(|_| EntityGrowError::InvalidGrow)?;
+                    .map_err(|error| match error {
+                        MemoryError::OutOfBoundsAllocation
+                        | MemoryError::ExceedsPoolSlot { .. }
+                            if this.ctx.engine().config().get_deterministic() =>
+                        {
+                            // Host dependent allocation failures must not be
+                            // observable by Wasm code upon deterministic execution.
+                            EntityGrowError::Host(error)
+                        }
+                        _ => EntityGrowError::InvalidGrow,
+                    })?;
                 // The `memory.grow` operation might have invalidated the cached
                 // linear memory so we need to reset it in order for the cache to
                 // reload in case it is used again.
@@ -1110,10 +1281,11 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let result = match result {
             Ok(result) => result,
             Err(EntityGrowError::InvalidGrow) => INVALID_GROWTH_ERRCODE,
-            Err(EntityGrowError::TrapCode(trap_code)) => return Err(trap_code),
+            Err(EntityGrowError::TrapCode(trap_code)) => return Err(trap_code.into()),
+            Err(EntityGrowError::Host(error)) => return Err(ExecutionError::Host(error)),
         };
         self.sp.push_as(result);
-        self.try_next_instr()
+        self.try_next_instr().map_err(Into::into)
     }
 
     #[inline(always)]
@@ -1227,6 +1399,9 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
             Ok(result) => result,
             Err(EntityGrowError::InvalidGrow) => INVALID_GROWTH_ERRCODE,
             Err(EntityGrowError::TrapCode(trap_code)) => return Err(trap_code),
+            Err(EntityGrowError::Host(_)) => {
+                unreachable!("`table.grow` has no host dependent failures")
+            }
         };
         self.sp.push_as(result);
         self.try_next_instr()
@@ -1359,7 +1534,9 @@ macro_rules! impl_visit_load {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_load! {
         fn visit_i32_load(i32_load);
         fn visit_i64_load(i64_load);
@@ -1393,7 +1570,9 @@ macro_rules! impl_visit_store {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_store! {
         fn visit_i32_store(i32_store);
         fn visit_i64_store(i64_store);
@@ -1419,7 +1598,9 @@ macro_rules! impl_visit_unary {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_unary! {
         fn visit_i32_eqz(i32_eqz);
         fn visit_i64_eqz(i64_eqz);
@@ -1439,6 +1620,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         fn visit_f32_trunc(f32_trunc);
         fn visit_f32_nearest(f32_nearest);
         fn visit_f32_sqrt(f32_sqrt);
+        fn visit_f32_canonicalize_nan(f32_canonicalize_nan);
 
         fn visit_f64_abs(f64_abs);
         fn visit_f64_neg(f64_neg);
@@ -1447,6 +1629,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         fn visit_f64_trunc(f64_trunc);
         fn visit_f64_nearest(f64_nearest);
         fn visit_f64_sqrt(f64_sqrt);
+        fn visit_f64_canonicalize_nan(f64_canonicalize_nan);
 
         fn visit_i32_wrap_i64(i32_wrap_i64);
         fn visit_i64_extend_i32_s(i64_extend_i32_s);
@@ -1490,7 +1673,9 @@ macro_rules! impl_visit_fallible_unary {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_fallible_unary! {
         fn visit_i32_trunc_f32_s(i32_trunc_f32_s);
         fn visit_i32_trunc_f32_u(i32_trunc_f32_u);
@@ -1514,7 +1699,9 @@ macro_rules! impl_visit_binary {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_binary! {
         fn visit_i32_eq(i32_eq);
         fn visit_i32_ne(i32_ne);
@@ -1604,7 +1791,9 @@ macro_rules! impl_visit_fallible_binary {
         )*
     }
 }
-impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+impl<'ctx, 'engine, const PROFILE: bool, const FUEL_ACCOUNTING: bool>
+    Executor<'ctx, 'engine, PROFILE, FUEL_ACCOUNTING>
+{
     impl_visit_fallible_binary! {
         fn visit_i32_div_s(i32_div_s);
         fn visit_i32_div_u(i32_div_u);
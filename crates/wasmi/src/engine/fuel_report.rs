@@ -0,0 +1,130 @@
+//! Per-function accounting of the fuel consumed by Wasm executions.
+
+use super::CompiledFunc;
+use crate::Module;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::cmp::Reverse;
+
+/// Attributes the fuel consumed by Wasm executions to the executing Wasm functions.
+///
+/// Keeps track of the Wasm functions on the call stack in order to know
+/// which function is executing when fuel is charged.
+#[derive(Debug, Default)]
+pub(crate) struct FuelAccounting {
+    /// The Wasm functions on the call stack with the executing function last.
+    stack: Vec<CompiledFunc>,
+    /// The fuel consumed by every Wasm function so far.
+    consumed: BTreeMap<CompiledFunc, u64>,
+}
+
+impl FuelAccounting {
+    /// Returns the number of Wasm functions on the call stack.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Removes all Wasm functions above `depth` from the call stack.
+    ///
+    /// This is used to unwind the call stack after an execution trapped.
+    pub fn unwind_to(&mut self, depth: usize) {
+        self.stack.truncate(depth);
+    }
+
+    /// Records a nested call to `func` by the executing function.
+    #[inline]
+    pub fn call(&mut self, func: CompiledFunc) {
+        self.stack.push(func);
+    }
+
+    /// Records a tail call to `func` replacing the executing function.
+    #[inline]
+    pub fn tail_call(&mut self, func: CompiledFunc) {
+        self.stack.pop();
+        self.stack.push(func);
+    }
+
+    /// Records a return from the executing function.
+    #[inline]
+    pub fn ret(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Attributes `delta` consumed fuel to the executing function.
+    #[inline]
+    pub fn charge(&mut self, delta: u64) {
+        if let Some(func) = self.stack.last() {
+            *self.consumed.entry(*func).or_default() += delta;
+        }
+    }
+
+    /// Discards the fuel attributed to all Wasm functions so far.
+    pub fn reset(&mut self) {
+        self.consumed.clear();
+    }
+
+    /// Returns the [`FuelReport`] of the fuel attributed to Wasm functions so far.
+    pub fn report(&self) -> FuelReport {
+        let mut funcs = self
+            .consumed
+            .iter()
+            .map(|(func, consumed)| FuncFuel {
+                func: *func,
+                consumed: *consumed,
+            })
+            .collect::<Vec<_>>();
+        funcs.sort_by_key(|func| Reverse(func.consumed));
+        FuelReport { funcs }
+    }
+}
+
+/// The fuel consumed by Wasm executions of a [`Store`] broken down by Wasm function.
+///
+/// Every `ConsumeFuel` block and every charge of a bulk-operation is attributed
+/// to the Wasm function that executed it. Fuel consumed synthetically via
+/// [`Store::consume_fuel`] is not attributed to any Wasm function.
+///
+/// [`Store`]: crate::Store
+/// [`Store::consume_fuel`]: crate::Store::consume_fuel
+#[derive(Debug, Clone)]
+pub struct FuelReport {
+    /// The fuel consumed per Wasm function.
+    funcs: Vec<FuncFuel>,
+}
+
+impl FuelReport {
+    /// Returns the fuel consumed by every Wasm function that consumed fuel.
+    ///
+    /// The functions are ordered by their consumed fuel starting with
+    /// the function that consumed the most fuel.
+    pub fn funcs(&self) -> &[FuncFuel] {
+        &self.funcs[..]
+    }
+
+    /// Returns the total fuel consumed by all Wasm functions.
+    pub fn total(&self) -> u64 {
+        self.funcs.iter().map(FuncFuel::consumed).sum()
+    }
+}
+
+/// The fuel consumed by a single Wasm function of a [`FuelReport`].
+#[derive(Debug, Copy, Clone)]
+pub struct FuncFuel {
+    /// The Wasm function that consumed the fuel.
+    func: CompiledFunc,
+    /// The fuel consumed by the Wasm function.
+    consumed: u64,
+}
+
+impl FuncFuel {
+    /// Returns the function index of the Wasm function within the `module`.
+    ///
+    /// Returns `None` if the Wasm function is not an internal function of the `module`.
+    pub fn func_index(&self, module: &Module) -> Option<u32> {
+        module.get_func_index(self.func)
+    }
+
+    /// Returns the fuel consumed by the Wasm function.
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+}
@@ -701,6 +701,25 @@ impl<'parser> FuncTranslator<'parser> {
         })
     }
 
+    /// Canonicalizes the NaN result of the previously translated float operation.
+    ///
+    /// # Note
+    ///
+    /// This only pushes `canonicalize` if deterministic execution is enabled
+    /// since the bits of NaN results are not deterministic otherwise.
+    fn translate_canonicalize_nan(
+        &mut self,
+        canonicalize: Instruction,
+    ) -> Result<(), TranslationError> {
+        if !self.engine().config().get_deterministic() {
+            return Ok(());
+        }
+        self.translate_if_reachable(|builder| {
+            builder.alloc.inst_builder.push_inst(canonicalize);
+            Ok(())
+        })
+    }
+
     /// Returns the target at the given `depth` together with its [`DropKeep`].
     ///
     /// # Panics
@@ -823,6 +842,27 @@ macro_rules! impl_visit_operator {
     () => {};
 }
 
+/// Implements the translation of Wasm float operations that may produce NaN values.
+///
+/// Each operation is translated via its translation method and followed by the given
+/// NaN canonicalization instruction if deterministic execution is enabled.
+macro_rules! impl_visit_float {
+    (
+        $(
+            fn $visit_ident:ident(
+                $translate_ident:ident($($value_type:ident),+; $inst:ident)
+            ) -> $canonicalize:ident;
+        )*
+    ) => {
+        $(
+            fn $visit_ident(&mut self) -> Result<(), TranslationError> {
+                self.$translate_ident($(ValueType::$value_type),+, Instruction::$inst)?;
+                self.translate_canonicalize_nan(Instruction::$canonicalize)
+            }
+        )*
+    };
+}
+
 impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     type Output = Result<(), TranslationError>;
 
@@ -2081,48 +2121,35 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         self.translate_unary_operation(ValueType::F32, Instruction::F32Neg)
     }
 
-    fn visit_f32_ceil(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F32, Instruction::F32Ceil)
-    }
-
-    fn visit_f32_floor(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F32, Instruction::F32Floor)
-    }
-
-    fn visit_f32_trunc(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F32, Instruction::F32Trunc)
-    }
-
-    fn visit_f32_nearest(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F32, Instruction::F32Nearest)
-    }
-
-    fn visit_f32_sqrt(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F32, Instruction::F32Sqrt)
-    }
-
-    fn visit_f32_add(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Add)
-    }
-
-    fn visit_f32_sub(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Sub)
-    }
+    impl_visit_float! {
+        fn visit_f32_ceil(translate_unary_operation(F32; F32Ceil)) -> F32CanonicalizeNan;
+        fn visit_f32_floor(translate_unary_operation(F32; F32Floor)) -> F32CanonicalizeNan;
+        fn visit_f32_trunc(translate_unary_operation(F32; F32Trunc)) -> F32CanonicalizeNan;
+        fn visit_f32_nearest(translate_unary_operation(F32; F32Nearest)) -> F32CanonicalizeNan;
+        fn visit_f32_sqrt(translate_unary_operation(F32; F32Sqrt)) -> F32CanonicalizeNan;
 
-    fn visit_f32_mul(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Mul)
-    }
+        fn visit_f32_add(translate_binary_operation(F32; F32Add)) -> F32CanonicalizeNan;
+        fn visit_f32_sub(translate_binary_operation(F32; F32Sub)) -> F32CanonicalizeNan;
+        fn visit_f32_mul(translate_binary_operation(F32; F32Mul)) -> F32CanonicalizeNan;
+        fn visit_f32_div(translate_binary_operation(F32; F32Div)) -> F32CanonicalizeNan;
+        fn visit_f32_min(translate_binary_operation(F32; F32Min)) -> F32CanonicalizeNan;
+        fn visit_f32_max(translate_binary_operation(F32; F32Max)) -> F32CanonicalizeNan;
 
-    fn visit_f32_div(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Div)
-    }
+        fn visit_f64_ceil(translate_unary_operation(F64; F64Ceil)) -> F64CanonicalizeNan;
+        fn visit_f64_floor(translate_unary_operation(F64; F64Floor)) -> F64CanonicalizeNan;
+        fn visit_f64_trunc(translate_unary_operation(F64; F64Trunc)) -> F64CanonicalizeNan;
+        fn visit_f64_nearest(translate_unary_operation(F64; F64Nearest)) -> F64CanonicalizeNan;
+        fn visit_f64_sqrt(translate_unary_operation(F64; F64Sqrt)) -> F64CanonicalizeNan;
 
-    fn visit_f32_min(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Min)
-    }
+        fn visit_f64_add(translate_binary_operation(F64; F64Add)) -> F64CanonicalizeNan;
+        fn visit_f64_sub(translate_binary_operation(F64; F64Sub)) -> F64CanonicalizeNan;
+        fn visit_f64_mul(translate_binary_operation(F64; F64Mul)) -> F64CanonicalizeNan;
+        fn visit_f64_div(translate_binary_operation(F64; F64Div)) -> F64CanonicalizeNan;
+        fn visit_f64_min(translate_binary_operation(F64; F64Min)) -> F64CanonicalizeNan;
+        fn visit_f64_max(translate_binary_operation(F64; F64Max)) -> F64CanonicalizeNan;
 
-    fn visit_f32_max(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F32, Instruction::F32Max)
+        fn visit_f32_demote_f64(translate_conversion(F64, F32; F32DemoteF64)) -> F32CanonicalizeNan;
+        fn visit_f64_promote_f32(translate_conversion(F32, F64; F64PromoteF32)) -> F64CanonicalizeNan;
     }
 
     fn visit_f32_copysign(&mut self) -> Result<(), TranslationError> {
@@ -2137,50 +2164,6 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         self.translate_unary_operation(ValueType::F64, Instruction::F64Neg)
     }
 
-    fn visit_f64_ceil(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F64, Instruction::F64Ceil)
-    }
-
-    fn visit_f64_floor(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F64, Instruction::F64Floor)
-    }
-
-    fn visit_f64_trunc(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F64, Instruction::F64Trunc)
-    }
-
-    fn visit_f64_nearest(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F64, Instruction::F64Nearest)
-    }
-
-    fn visit_f64_sqrt(&mut self) -> Result<(), TranslationError> {
-        self.translate_unary_operation(ValueType::F64, Instruction::F64Sqrt)
-    }
-
-    fn visit_f64_add(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Add)
-    }
-
-    fn visit_f64_sub(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Sub)
-    }
-
-    fn visit_f64_mul(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Mul)
-    }
-
-    fn visit_f64_div(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Div)
-    }
-
-    fn visit_f64_min(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Min)
-    }
-
-    fn visit_f64_max(&mut self) -> Result<(), TranslationError> {
-        self.translate_binary_operation(ValueType::F64, Instruction::F64Max)
-    }
-
     fn visit_f64_copysign(&mut self) -> Result<(), TranslationError> {
         self.translate_binary_operation(ValueType::F64, Instruction::F64Copysign)
     }
@@ -2245,10 +2228,6 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         self.translate_conversion(ValueType::I64, ValueType::F32, Instruction::F32ConvertI64U)
     }
 
-    fn visit_f32_demote_f64(&mut self) -> Result<(), TranslationError> {
-        self.translate_conversion(ValueType::F64, ValueType::F32, Instruction::F32DemoteF64)
-    }
-
     fn visit_f64_convert_i32_s(&mut self) -> Result<(), TranslationError> {
         self.translate_conversion(ValueType::I32, ValueType::F64, Instruction::F64ConvertI32S)
     }
@@ -2265,10 +2244,6 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         self.translate_conversion(ValueType::I64, ValueType::F64, Instruction::F64ConvertI64U)
     }
 
-    fn visit_f64_promote_f32(&mut self) -> Result<(), TranslationError> {
-        self.translate_conversion(ValueType::F32, ValueType::F64, Instruction::F64PromoteF32)
-    }
-
     fn visit_i32_reinterpret_f32(&mut self) -> Result<(), TranslationError> {
         self.visit_reinterpret(ValueType::F32, ValueType::I32)
     }
@@ -0,0 +1,854 @@
+//! Recording and replaying of the host function calls of Wasm executions.
+
+use super::FuncParams;
+use crate::{
+    core::{HostError, Pages, Trap, UntypedValue, ValueType},
+    func::{FuncEntity, HostFuncEntity, Trampoline, TrampolineEntity},
+    module::ImportName,
+    store::StoreInner,
+    AsContext,
+    Instance,
+    Memory,
+    StoreContextMut,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
+    mem,
+    str::FromStr,
+};
+
+/// The size in bytes of the chunks in which changes to a linear memory are recorded
+/// when they are found by comparing its contents before and after a host call.
+const CHUNK_SIZE: usize = 256;
+
+/// A log of the host function calls of Wasm executions.
+///
+/// Recorded via [`Store::record_host_calls`] and replayed via [`Store::replay_host_calls`].
+///
+/// Every host call is recorded with the import name of the host function, its parameters,
+/// the changes it made to the linear memories of the calling instance and its results or trap.
+/// The log is written and read in a line based text format via [`Display`] and [`FromStr`].
+///
+/// Only the changes to linear memories are replayed while changes to globals or tables
+/// are neither recorded nor replayed. Host functions with reference typed parameters or
+/// results cannot be recorded or replayed and trap with [`HostCallError::ReferenceTypes`].
+///
+/// [`Store::record_host_calls`]: crate::Store::record_host_calls
+/// [`Store::replay_host_calls`]: crate::Store::replay_host_calls
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HostCallLog {
+    /// The recorded host calls in the order in which they were issued.
+    calls: Vec<HostCall>,
+}
+
+impl HostCallLog {
+    /// Returns the number of recorded host calls.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Returns `true` if no host calls have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+}
+
+/// A single recorded host function call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HostCall {
+    /// The import name of the called host function within the calling instance.
+    func: ImportName,
+    /// The raw bits of the parameters of the host call.
+    params: Vec<u64>,
+    /// The new sizes in pages of the linear memories grown by the host call.
+    grown: Vec<(u32, u32)>,
+    /// The changes the host call made to the linear memories of the calling instance.
+    writes: Vec<MemoryWrite>,
+    /// The outcome of the host call.
+    outcome: HostCallOutcome,
+}
+
+/// A change made by a host call to a linear memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MemoryWrite {
+    /// The index of the linear memory within the calling instance.
+    memory: u32,
+    /// The offset of the first changed chunk.
+    offset: usize,
+    /// The contents of the changed chunks after the host call.
+    bytes: Vec<u8>,
+}
+
+/// The outcome of a recorded host call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostCallOutcome {
+    /// The host call returned the raw bits of its results.
+    Return(Vec<u64>),
+    /// The host call exited with an `i32` exit status.
+    Exit(i32),
+    /// The host call trapped with the message.
+    Trap(String),
+}
+
+/// Writes the `call` line identifying the host function `func` called with `params`.
+fn write_call(f: &mut fmt::Formatter, func: &ImportName, params: &[u64]) -> fmt::Result {
+    let module = escape(func.module(), true);
+    let name = escape(func.name(), true);
+    write!(f, "call {module} {name}")?;
+    for param in params {
+        write!(f, " {param}")?;
+    }
+    Ok(())
+}
+
+/// Displays the `call` line of a host call for error messages.
+struct DisplayCall<'a> {
+    func: &'a ImportName,
+    params: &'a [u64],
+}
+
+impl Display for DisplayCall<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_call(f, self.func, self.params)
+    }
+}
+
+impl Display for HostCallLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for call in &self.calls {
+            write_call(f, &call.func, &call.params)?;
+            writeln!(f)?;
+            for (memory, pages) in &call.grown {
+                writeln!(f, "memory {memory} {pages}")?;
+            }
+            for write in &call.writes {
+                write!(f, "write {} {} ", write.memory, write.offset)?;
+                for byte in &write.bytes {
+                    write!(f, "{byte:02x}")?;
+                }
+                writeln!(f)?;
+            }
+            match &call.outcome {
+                HostCallOutcome::Return(results) => {
+                    write!(f, "return")?;
+                    for result in results {
+                        write!(f, " {result}")?;
+                    }
+                    writeln!(f)?;
+                }
+                HostCallOutcome::Exit(status) => writeln!(f, "exit {status}")?,
+                HostCallOutcome::Trap(message) => writeln!(f, "trap {}", escape(message, false))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for HostCallLog {
+    type Err = HostCallError;
+
+    fn from_str(log: &str) -> Result<Self, Self::Err> {
+        let mut calls = Vec::new();
+        let mut current: Option<HostCall> = None;
+        for (index, line) in log.lines().enumerate() {
+            let invalid = || HostCallError::InvalidLog { line: index + 1 };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+            if kind == "call" {
+                if current.is_some() {
+                    return Err(invalid());
+                }
+                let mut fields = rest.split(' ');
+                let module = fields.next().and_then(unescape).ok_or_else(invalid)?;
+                let name = fields.next().and_then(unescape).ok_or_else(invalid)?;
+                let func = ImportName::new(&module, &name);
+                let params = fields
+                    .map(str::parse::<u64>)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| invalid())?;
+                current = Some(HostCall {
+                    func,
+                    params,
+                    grown: Vec::new(),
+                    writes: Vec::new(),
+                    outcome: HostCallOutcome::Return(Vec::new()),
+                });
+                continue;
+            }
+            let call = current.as_mut().ok_or_else(invalid)?;
+            match kind {
+                "memory" => {
+                    let (memory, pages) = rest.split_once(' ').ok_or_else(invalid)?;
+                    let memory = memory.parse().map_err(|_| invalid())?;
+                    let pages = pages.parse().map_err(|_| invalid())?;
+                    call.grown.push((memory, pages));
+                }
+                "write" => {
+                    let mut fields = rest.split(' ');
+                    let (Some(memory), Some(offset), Some(hex), None) =
+                        (fields.next(), fields.next(), fields.next(), fields.next())
+                    else {
+                        return Err(invalid());
+                    };
+                    let memory = memory.parse().map_err(|_| invalid())?;
+                    let offset = offset.parse().map_err(|_| invalid())?;
+                    let bytes = decode_hex(hex).ok_or_else(invalid)?;
+                    call.writes.push(MemoryWrite {
+                        memory,
+                        offset,
+                        bytes,
+                    });
+                }
+                "return" => {
+                    let results = rest
+                        .split_whitespace()
+                        .map(str::parse::<u64>)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| invalid())?;
+                    call.outcome = HostCallOutcome::Return(results);
+                }
+                "exit" => {
+                    let status = rest.parse().map_err(|_| invalid())?;
+                    call.outcome = HostCallOutcome::Exit(status);
+                }
+                "trap" => {
+                    let message = unescape(rest).ok_or_else(invalid)?;
+                    call.outcome = HostCallOutcome::Trap(message);
+                }
+                _ => return Err(invalid()),
+            }
+            if matches!(kind, "return" | "exit" | "trap") {
+                calls.extend(current.take());
+            }
+        }
+        if current.is_some() {
+            return Err(HostCallError::InvalidLog {
+                line: log.lines().count(),
+            });
+        }
+        Ok(Self { calls })
+    }
+}
+
+/// Decodes the lowercase or uppercase hexadecimal `hex` string into bytes.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = core::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// Escapes the backslashes, line breaks and, if `spaces` is `true`, spaces of `text`.
+///
+/// Import names are escaped including spaces since they are separated by spaces.
+fn escape(text: &str, spaces: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            ' ' if spaces => escaped.push_str("\\s"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverts the [`escape`] of a trap message or import name.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                's' => unescaped.push(' '),
+                '\\' => unescaped.push('\\'),
+                _ => return None,
+            },
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+/// An error that may occur upon replaying a [`HostCallLog`] or reading it from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostCallError {
+    /// The Wasm guest issued a host call that differs from the recorded one.
+    Mismatch {
+        /// The index of the host call within the [`HostCallLog`].
+        index: usize,
+        /// The recorded host call.
+        expected: String,
+        /// The host call issued by the Wasm guest.
+        found: String,
+    },
+    /// The Wasm guest issued more host calls than recorded.
+    Exhausted {
+        /// The number of recorded host calls.
+        recorded: usize,
+    },
+    /// The Wasm guest issued fewer host calls than recorded.
+    Unfinished {
+        /// The number of replayed host calls.
+        replayed: usize,
+        /// The number of recorded host calls.
+        recorded: usize,
+    },
+    /// The host function has reference typed parameters or results.
+    ///
+    /// References are indices local to a [`Store`](crate::Store) and thus cannot be recorded.
+    ReferenceTypes {
+        /// The import name of the host function.
+        func: String,
+    },
+    /// The recorded host call cannot be replayed for the calling instance.
+    InvalidRecord {
+        /// The index of the host call within the [`HostCallLog`].
+        index: usize,
+    },
+    /// A line of a [`HostCallLog`] in text format is malformed.
+    InvalidLog {
+        /// The number of the malformed line starting at 1.
+        line: usize,
+    },
+}
+
+impl Display for HostCallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "host call #{index} diverged from the recording: expected `{expected}` but found `{found}`"
+            ),
+            Self::Exhausted { recorded } => {
+                write!(f, "issued more host calls than the {recorded} recorded ones")
+            }
+            Self::Unfinished { replayed, recorded } => write!(
+                f,
+                "issued only {replayed} of the {recorded} recorded host calls"
+            ),
+            Self::ReferenceTypes { func } => write!(
+                f,
+                "cannot record or replay host function `{func}` with reference typed parameters or results"
+            ),
+            Self::InvalidRecord { index } => write!(
+                f,
+                "recorded host call #{index} cannot be replayed for the calling instance"
+            ),
+            Self::InvalidLog { line } => write!(f, "malformed host call log at line {line}"),
+        }
+    }
+}
+
+impl HostError for HostCallError {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HostCallError {}
+
+/// The recording or replaying state of the host calls of a [`Store`](crate::Store).
+#[derive(Debug)]
+pub(crate) enum HostCalls {
+    /// Host calls are recorded into the log.
+    Record {
+        /// The host calls recorded so far.
+        log: HostCallLog,
+        /// The number of host calls currently in progress.
+        ///
+        /// Host calls nested within other host calls are not recorded
+        /// since their effects are part of the outer host call.
+        depth: usize,
+        /// The changes to linear memories made by the host call in progress.
+        effects: MemoryEffects,
+    },
+    /// Host calls are replayed from the log.
+    Replay {
+        /// The recorded host calls.
+        log: HostCallLog,
+        /// The number of host calls replayed so far.
+        replayed: usize,
+    },
+}
+
+impl HostCalls {
+    /// Creates a new [`HostCalls`] recording host calls.
+    pub fn record() -> Self {
+        Self::Record {
+            log: HostCallLog::default(),
+            depth: 0,
+            effects: MemoryEffects::default(),
+        }
+    }
+
+    /// Stops recording or replaying and returns the [`HostCallLog`].
+    ///
+    /// # Errors
+    ///
+    /// If not all recorded host calls have been replayed.
+    pub fn finish(self) -> Result<HostCallLog, HostCallError> {
+        match self {
+            Self::Record { log, .. } => Ok(log),
+            Self::Replay { log, replayed } if replayed == log.len() => Ok(log),
+            Self::Replay { log, replayed } => Err(HostCallError::Unfinished {
+                replayed,
+                recorded: log.len(),
+            }),
+        }
+    }
+
+    /// Returns the [`MemoryEffects`] of the recorded host call in progress if any.
+    fn effects(&self) -> Option<&MemoryEffects> {
+        match self {
+            Self::Record { depth, effects, .. } if *depth > 0 => Some(effects),
+            _ => None,
+        }
+    }
+
+    /// Returns the exclusive [`MemoryEffects`] of the recorded host call in progress if any.
+    fn effects_mut(&mut self) -> Option<&mut MemoryEffects> {
+        match self {
+            Self::Record { depth, effects, .. } if *depth > 0 => Some(effects),
+            _ => None,
+        }
+    }
+}
+
+/// The changes to the linear memories of the calling instance made by a recorded host call.
+///
+/// Writes via [`Memory::write`], [`Memory::write_ptr`] and [`Memory::write_slice`] are recorded
+/// as they happen. Linear memories that are exposed as mutable slices or that may be changed by
+/// Wasm executed within the host call are copied before so that their changes are found by
+/// comparing their contents after the host call.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryEffects {
+    /// The linear memories of the calling instance and their sizes before the host call.
+    memories: Vec<(Memory, Pages)>,
+    /// The bytes written to the linear memories at their offsets in the order of the writes.
+    writes: Vec<(Memory, usize, Vec<u8>)>,
+    /// The contents of the linear memories before they were first exposed to arbitrary changes.
+    snapshots: Vec<(Memory, Vec<u8>)>,
+}
+
+impl MemoryEffects {
+    /// Returns the index of the linear `memory` within the calling instance if any.
+    fn index_of(&self, memory: &Memory) -> Option<u32> {
+        self.memories
+            .iter()
+            .position(|(other, _)| other.as_inner() == memory.as_inner())
+            .map(|index| index as u32)
+    }
+
+    /// Returns `true` if the linear `memory` has been copied for comparison.
+    fn is_snapshot(&self, memory: &Memory) -> bool {
+        self.snapshots
+            .iter()
+            .any(|(other, _)| other.as_inner() == memory.as_inner())
+    }
+
+    /// Returns `true` if changes to the linear `memory` need to be recorded as they happen.
+    fn tracks(&self, memory: &Memory) -> bool {
+        self.index_of(memory).is_some() && !self.is_snapshot(memory)
+    }
+
+    /// Returns the grown linear memories and the writes of the host call.
+    ///
+    /// The writes recorded as they happened precede the changes found by comparison
+    /// so that replaying all writes in order yields the linear memories after the host call.
+    fn finish(self, store: &StoreInner) -> (Vec<(u32, u32)>, Vec<MemoryWrite>) {
+        let mut grown = Vec::new();
+        for (index, (memory, pages)) in (0..).zip(&self.memories) {
+            let current = store.resolve_memory(memory).current_pages();
+            if current != *pages {
+                grown.push((index, u32::from(current)));
+            }
+        }
+        let mut writes = Vec::new();
+        for (memory, offset, bytes) in &self.writes {
+            if let Some(index) = self.index_of(memory) {
+                writes.push(MemoryWrite {
+                    memory: index,
+                    offset: *offset,
+                    bytes: bytes.clone(),
+                });
+            }
+        }
+        for (memory, before) in &self.snapshots {
+            if let Some(index) = self.index_of(memory) {
+                let after = store.resolve_memory(memory).data();
+                diff_memory(index, before, after, &mut writes);
+            }
+        }
+        (grown, writes)
+    }
+}
+
+/// Records that the host call in progress wrote `len` bytes at `offset` of the linear `memory`.
+///
+/// Does nothing unless host calls are recorded.
+pub(crate) fn record_memory_write(
+    store: &mut StoreInner,
+    memory: &Memory,
+    offset: usize,
+    len: usize,
+) {
+    let tracks = store
+        .host_calls
+        .as_ref()
+        .and_then(HostCalls::effects)
+        .is_some_and(|effects| effects.tracks(memory));
+    if !tracks {
+        return;
+    }
+    let bytes = store.resolve_memory(memory).data()[offset..][..len].to_vec();
+    if let Some(effects) = store.host_calls.as_mut().and_then(HostCalls::effects_mut) {
+        effects.writes.push((*memory, offset, bytes));
+    }
+}
+
+/// Copies the linear `memory` before the host call in progress may change it arbitrarily.
+///
+/// Does nothing unless host calls are recorded.
+pub(crate) fn snapshot_memory(store: &mut StoreInner, memory: &Memory) {
+    // Copied linear memories are no longer tracked so that every linear memory
+    // is copied at most once per host call no matter how often it is exposed.
+    let tracks = store
+        .host_calls
+        .as_ref()
+        .and_then(HostCalls::effects)
+        .is_some_and(|effects| effects.tracks(memory));
+    if !tracks {
+        return;
+    }
+    let bytes = store.resolve_memory(memory).data().to_vec();
+    if let Some(effects) = store.host_calls.as_mut().and_then(HostCalls::effects_mut) {
+        effects.snapshots.push((*memory, bytes));
+    }
+}
+
+/// Copies the linear memories of the calling instance before the host call in progress executes Wasm.
+///
+/// Does nothing unless host calls are recorded.
+pub(crate) fn snapshot_memories(store: &mut StoreInner) {
+    let Some(effects) = store.host_calls.as_ref().and_then(HostCalls::effects) else {
+        return;
+    };
+    let memories = effects
+        .memories
+        .iter()
+        .map(|(memory, _)| *memory)
+        .collect::<Vec<_>>();
+    for memory in &memories {
+        snapshot_memory(store, memory);
+    }
+}
+
+/// Returns the linear memories of the calling `instance` if any.
+fn instance_memories<T>(ctx: &StoreContextMut<T>, instance: Option<&Instance>) -> Vec<Memory> {
+    let Some(instance) = instance else {
+        return Vec::new();
+    };
+    let instance = ctx.as_context().store.inner.resolve_instance(instance);
+    (0..)
+        .map_while(|index| instance.get_memory(index))
+        .collect()
+}
+
+/// Returns the import name of the host function `func` within the calling `instance` if any.
+fn import_name<T>(
+    ctx: &StoreContextMut<T>,
+    instance: Option<&Instance>,
+    func: &Trampoline,
+) -> Option<ImportName> {
+    let store = &ctx.as_context().store.inner;
+    let instance = store.resolve_instance(instance?);
+    (0..)
+        .zip(instance.func_imports())
+        .find_map(|(index, name)| {
+            let imported = instance.get_func(index)?;
+            match store.resolve_func(&imported) {
+                FuncEntity::Host(host_func) if host_func.trampoline() == func => Some(name.clone()),
+                _ => None,
+            }
+        })
+}
+
+/// Returns an error if the host function `host_func` imported as `name` has reference typed parameters or results.
+///
+/// The raw bits of references are indices local to the [`Store`](crate::Store) which cannot be replayed.
+fn check_value_types<T>(
+    ctx: &StoreContextMut<T>,
+    host_func: &HostFuncEntity,
+    name: &ImportName,
+) -> Result<(), HostCallError> {
+    let has_refs = ctx
+        .store
+        .inner
+        .resolve_func_type_with(host_func.ty_dedup(), |ty| {
+            ty.params()
+                .iter()
+                .chain(ty.results())
+                .any(ValueType::is_ref)
+        });
+    if has_refs {
+        return Err(HostCallError::ReferenceTypes {
+            func: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Calls the host function `trampoline` while recording or replaying host calls.
+///
+/// - `func` refers to the host function within the [`Store`](crate::Store).
+/// - `params_results` holds the `len_params` parameters before and the `len_results` results after the call.
+///
+/// Host functions that are not imported by the calling `instance` are called without
+/// recording or replaying since they cannot be identified across executions.
+/// The host calls nested within them are recorded or replayed individually.
+///
+/// # Errors
+///
+/// - If the host function traps.
+/// - If the host call diverges from the replayed [`HostCallLog`].
+/// - If the recorded or replayed host function has reference typed parameters or results.
+pub(crate) fn call_host<T>(
+    mut ctx: StoreContextMut<T>,
+    trampoline: &TrampolineEntity<T>,
+    func: &HostFuncEntity,
+    instance: Option<&Instance>,
+    params_results: &mut [UntypedValue],
+    len_params: usize,
+    len_results: usize,
+) -> Result<(), Trap> {
+    let name = match &ctx.store.inner.host_calls {
+        Some(HostCalls::Record { depth: 0, .. }) | Some(HostCalls::Replay { .. }) => {
+            import_name(&ctx, instance, func.trampoline())
+        }
+        _ => None,
+    };
+    if let Some(name) = &name {
+        check_value_types(&ctx, func, name)?;
+    }
+    match (&mut ctx.store.inner.host_calls, name) {
+        (Some(HostCalls::Record { depth, .. }), Some(name)) => {
+            *depth += 1;
+            let result = record(
+                &mut ctx,
+                trampoline,
+                name,
+                instance,
+                params_results,
+                len_params,
+                len_results,
+            );
+            if let Some(HostCalls::Record { depth, .. }) = &mut ctx.store.inner.host_calls {
+                *depth -= 1;
+            }
+            result
+        }
+        (Some(HostCalls::Record { depth, .. }), None) if *depth > 0 => {
+            // Host calls nested within a recorded host call are part of its effects.
+            *depth += 1;
+            let params_results = FuncParams::new(params_results, len_params, len_results);
+            let result = trampoline.call(&mut ctx, instance, params_results);
+            if let Some(HostCalls::Record { depth, .. }) = &mut ctx.store.inner.host_calls {
+                *depth -= 1;
+            }
+            result.map(|_| ())
+        }
+        (Some(HostCalls::Replay { .. }), Some(name)) => replay(
+            &mut ctx,
+            name,
+            instance,
+            params_results,
+            len_params,
+            len_results,
+        ),
+        (Some(HostCalls::Record { .. }), None)
+        | (Some(HostCalls::Replay { .. }), None)
+        | (None, _) => {
+            let params_results = FuncParams::new(params_results, len_params, len_results);
+            trampoline.call(ctx, instance, params_results).map(|_| ())
+        }
+    }
+}
+
+/// Calls the host function `trampoline` and records the host call.
+fn record<T>(
+    ctx: &mut StoreContextMut<T>,
+    trampoline: &TrampolineEntity<T>,
+    func: ImportName,
+    instance: Option<&Instance>,
+    params_results: &mut [UntypedValue],
+    len_params: usize,
+    len_results: usize,
+) -> Result<(), Trap> {
+    let params = params_results[..len_params]
+        .iter()
+        .map(|param| param.to_bits())
+        .collect::<Vec<_>>();
+    let memories = instance_memories(ctx, instance)
+        .into_iter()
+        .map(|memory| (memory, memory.current_pages(&*ctx)))
+        .collect();
+    if let Some(HostCalls::Record { effects, .. }) = &mut ctx.store.inner.host_calls {
+        *effects = MemoryEffects {
+            memories,
+            ..MemoryEffects::default()
+        };
+    }
+    let result = trampoline.call(
+        &mut *ctx,
+        instance,
+        FuncParams::new(params_results, len_params, len_results),
+    );
+    let effects = match &mut ctx.store.inner.host_calls {
+        Some(HostCalls::Record { effects, .. }) => mem::take(effects),
+        _ => MemoryEffects::default(),
+    };
+    let (grown, writes) = effects.finish(&ctx.store.inner);
+    let outcome = match &result {
+        Ok(_) => HostCallOutcome::Return(
+            params_results[..len_results]
+                .iter()
+                .map(|result| result.to_bits())
+                .collect(),
+        ),
+        Err(trap) => match trap.i32_exit_status() {
+            Some(status) => HostCallOutcome::Exit(status),
+            None => HostCallOutcome::Trap(trap.to_string()),
+        },
+    };
+    if let Some(HostCalls::Record { log, .. }) = &mut ctx.store.inner.host_calls {
+        log.calls.push(HostCall {
+            func,
+            params,
+            grown,
+            writes,
+            outcome,
+        });
+    }
+    result.map(|_| ())
+}
+
+/// Pushes the [`CHUNK_SIZE`] chunks of the linear memory at `index` that differ from `before` to `writes`.
+///
+/// Adjacent changed chunks are merged into a single [`MemoryWrite`].
+/// Bytes beyond `before` are compared against zero since grown linear memory is zero initialized.
+fn diff_memory(index: u32, before: &[u8], after: &[u8], writes: &mut Vec<MemoryWrite>) {
+    for (n, chunk) in after.chunks(CHUNK_SIZE).enumerate() {
+        let offset = n * CHUNK_SIZE;
+        let len = before.len().saturating_sub(offset).min(chunk.len());
+        let (kept, grown) = chunk.split_at(len);
+        let unchanged = before.get(offset..offset + len).unwrap_or_default() == kept
+            && grown.iter().all(|byte| *byte == 0x00);
+        if unchanged {
+            continue;
+        }
+        match writes.last_mut() {
+            Some(last) if last.memory == index && last.offset + last.bytes.len() == offset => {
+                last.bytes.extend_from_slice(chunk);
+            }
+            _ => writes.push(MemoryWrite {
+                memory: index,
+                offset,
+                bytes: chunk.to_vec(),
+            }),
+        }
+    }
+}
+
+/// Replays the next recorded host call instead of calling the host function.
+///
+/// # Errors
+///
+/// - If the host call diverges from the next recorded host call.
+/// - If the recorded host call trapped.
+fn replay<T>(
+    ctx: &mut StoreContextMut<T>,
+    func: ImportName,
+    instance: Option<&Instance>,
+    params_results: &mut [UntypedValue],
+    len_params: usize,
+    len_results: usize,
+) -> Result<(), Trap> {
+    let memories = instance_memories(ctx, instance);
+    let Some(HostCalls::Replay { log, replayed }) = &mut ctx.store.inner.host_calls else {
+        unreachable!("must be replaying host calls")
+    };
+    let index = *replayed;
+    let params = params_results[..len_params]
+        .iter()
+        .map(|param| param.to_bits())
+        .collect::<Vec<_>>();
+    let call = log.calls.get(index).ok_or(HostCallError::Exhausted {
+        recorded: log.len(),
+    })?;
+    if call.func != func || call.params != params {
+        return Err(HostCallError::Mismatch {
+            index,
+            expected: DisplayCall {
+                func: &call.func,
+                params: &call.params,
+            }
+            .to_string(),
+            found: DisplayCall {
+                func: &func,
+                params: &params,
+            }
+            .to_string(),
+        }
+        .into());
+    }
+    let call = call.clone();
+    *replayed += 1;
+    let invalid = || HostCallError::InvalidRecord { index };
+    for (memory, pages) in &call.grown {
+        let memory = memories.get(*memory as usize).ok_or_else(invalid)?;
+        let current = memory.current_pages(&*ctx);
+        let additional = Pages::new(*pages)
+            .and_then(|pages| pages.checked_sub(current))
+            .ok_or_else(invalid)?;
+        memory.grow(&mut *ctx, additional).map_err(|_| invalid())?;
+    }
+    for write in &call.writes {
+        let memory = memories.get(write.memory as usize).ok_or_else(invalid)?;
+        memory
+            .data_mut(&mut *ctx)
+            .get_mut(write.offset..)
+            .and_then(|data| data.get_mut(..write.bytes.len()))
+            .ok_or_else(invalid)?
+            .copy_from_slice(&write.bytes);
+    }
+    match call.outcome {
+        HostCallOutcome::Return(results) => {
+            if results.len() != len_results {
+                return Err(invalid().into());
+            }
+            for (dst, src) in params_results.iter_mut().zip(results) {
+                *dst = UntypedValue::from(src);
+            }
+            Ok(())
+        }
+        HostCallOutcome::Exit(status) => Err(Trap::i32_exit(status)),
+        HostCallOutcome::Trap(message) => Err(Trap::new(message)),
+    }
+}
@@ -5,10 +5,17 @@ mod cache;
 pub mod code_map;
 mod config;
 mod const_pool;
+#[cfg(feature = "disassembler")]
+mod disassemble;
 pub mod executor;
+#[cfg(feature = "fuel_accounting")]
+mod fuel_report;
 mod func_args;
 mod func_builder;
 mod func_types;
+mod host_calls;
+#[cfg(feature = "profiler")]
+mod profiler;
 mod resumable;
 pub mod stack;
 mod traits;
@@ -16,10 +23,18 @@ mod traits;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "disassembler")]
+pub use self::disassemble::Disassembly;
+#[cfg(feature = "fuel_accounting")]
+pub(crate) use self::fuel_report::FuelAccounting;
+#[cfg(feature = "fuel_accounting")]
+pub use self::fuel_report::{FuelReport, FuncFuel};
+#[cfg(feature = "profiler")]
+pub use self::profiler::{FuncProfile, Profile, ProfiledFunc};
 pub use self::{
     bytecode::DropKeep,
     code_map::CompiledFunc,
-    config::{Config, FuelConsumptionMode},
+    config::{Config, FuelConsumptionMode, InstanceAllocationStrategy, PoolingAllocationConfig},
     func_builder::{
         FuncBuilder,
         FuncTranslatorAllocations,
@@ -27,6 +42,7 @@ pub use self::{
         RelativeDepth,
         TranslationError,
     },
+    host_calls::{HostCallError, HostCallLog},
     resumable::{ResumableCall, ResumableInvocation, TypedResumableCall, TypedResumableInvocation},
     stack::StackLimits,
     traits::{CallParams, CallResults},
@@ -36,18 +52,23 @@ use self::{
     cache::InstanceCache,
     code_map::CodeMap,
     const_pool::{ConstPool, ConstPoolView, ConstRef},
-    executor::{execute_wasm, WasmOutcome},
+    executor::{execute_wasm, ExecutionError, WasmOutcome},
     func_types::FuncTypeRegistry,
+    host_calls::snapshot_memories,
     resumable::ResumableCallBase,
     stack::{FuncFrame, Stack, ValueStack},
 };
 pub(crate) use self::{
     func_args::{FuncFinished, FuncParams, FuncResults},
     func_types::DedupFuncType,
+    host_calls::{record_memory_write, snapshot_memory, HostCalls},
 };
 use crate::{
     core::{Trap, TrapCode},
     func::FuncEntity,
+    memory::MemoryPool,
+    store::RootCallMarker,
+    table::TablePool,
     AsContext,
     AsContextMut,
     Func,
@@ -134,6 +155,27 @@ impl Engine {
         Arc::ptr_eq(&a.inner, &b.inner)
     }
 
+    /// Disassembles the `wasmi` bytecode of the function at `func_index` of the `module`.
+    ///
+    /// Returns `None` if `func_index` refers to an imported function or is out of bounds.
+    ///
+    /// # Note
+    ///
+    /// The rendered bytecode is meant for performance investigations
+    /// and its format is not stable across `wasmi` versions.
+    ///
+    /// # Panics
+    ///
+    /// If the `module` was not compiled by this [`Engine`].
+    #[cfg(feature = "disassembler")]
+    pub fn disassemble(&self, module: &crate::Module, func_index: u32) -> Option<Disassembly> {
+        assert!(
+            Engine::same(self, module.engine()),
+            "cannot disassemble a module that was compiled by another engine"
+        );
+        disassemble::disassemble(&self.inner.res.read(), module, func_index)
+    }
+
     /// Allocates a new function type to the [`Engine`].
     pub(super) fn alloc_func_type(&self, func_type: FuncType) -> DedupFuncType {
         self.inner.alloc_func_type(func_type)
@@ -237,6 +279,8 @@ impl Engine {
     where
         Results: CallResults,
     {
+        // Wasm executed within a recorded host call may change the caller's linear memories.
+        snapshot_memories(&mut ctx.store.inner);
         self.inner.execute_func(ctx, func, params, results)
     }
 
@@ -273,6 +317,8 @@ impl Engine {
     where
         Results: CallResults,
     {
+        // Wasm executed within a recorded host call may change the caller's linear memories.
+        snapshot_memories(&mut ctx.store.inner);
         self.inner
             .execute_func_resumable(ctx, func, params, results)
     }
@@ -310,6 +356,8 @@ impl Engine {
     where
         Results: CallResults,
     {
+        // Wasm executed within a recorded host call may change the caller's linear memories.
+        snapshot_memories(&mut ctx.store.inner);
         self.inner.resume_func(ctx, invocation, params, results)
     }
 
@@ -317,6 +365,24 @@ impl Engine {
     pub(crate) fn recycle_stack(&self, stack: Stack) {
         self.inner.recycle_stack(stack)
     }
+
+    /// Returns the [`MemoryPool`] of the [`Engine`] if any.
+    ///
+    /// # Note
+    ///
+    /// Only available for the [`InstanceAllocationStrategy::Pooling`] strategy.
+    pub(crate) fn memory_pool(&self) -> Option<&Arc<MemoryPool>> {
+        self.inner.pools.as_ref().map(|pools| &pools.memories)
+    }
+
+    /// Returns the [`TablePool`] of the [`Engine`] if any.
+    ///
+    /// # Note
+    ///
+    /// Only available for the [`InstanceAllocationStrategy::Pooling`] strategy.
+    pub(crate) fn table_pool(&self) -> Option<&Arc<TablePool>> {
+        self.inner.pools.as_ref().map(|pools| &pools.tables)
+    }
 }
 
 /// The internal state of the `wasmi` [`Engine`].
@@ -332,6 +398,35 @@ pub struct EngineInner {
     /// operate on. Therefore a Wasm engine is required to provide stacks and
     /// ideally recycles old ones since creation of a new stack is rather expensive.
     stacks: Mutex<EngineStacks>,
+    /// Pools for linear memories and tables of Wasm instances.
+    ///
+    /// Only available for the [`InstanceAllocationStrategy::Pooling`] strategy.
+    pools: Option<EnginePools>,
+}
+
+/// The pools of the pooling instance allocation strategy.
+#[derive(Debug)]
+pub struct EnginePools {
+    /// Pre-reserved linear memory slots.
+    memories: Arc<MemoryPool>,
+    /// Pre-allocated table slots.
+    tables: Arc<TablePool>,
+}
+
+impl EnginePools {
+    /// Creates new [`EnginePools`] with the given [`PoolingAllocationConfig`].
+    pub fn new(config: &PoolingAllocationConfig) -> Self {
+        Self {
+            memories: Arc::new(MemoryPool::new(
+                config.get_total_memories(),
+                config.get_memory_pages(),
+            )),
+            tables: Arc::new(TablePool::new(
+                config.get_total_tables(),
+                config.get_table_elements(),
+            )),
+        }
+    }
 }
 
 /// The engine's stacks for reuse.
@@ -350,10 +445,21 @@ pub struct EngineStacks {
 impl EngineStacks {
     /// Creates new [`EngineStacks`] with the given [`StackLimits`].
     pub fn new(config: &Config) -> Self {
-        Self {
-            stacks: Vec::new(),
-            limits: config.stack_limits(),
-            keep: config.cached_stacks(),
+        let limits = config.stack_limits();
+        match config.get_allocation_strategy() {
+            InstanceAllocationStrategy::OnDemand => Self {
+                stacks: Vec::new(),
+                limits,
+                keep: config.cached_stacks(),
+            },
+            InstanceAllocationStrategy::Pooling(pooling) => {
+                let total = pooling.get_total_stacks() as usize;
+                Self {
+                    stacks: (0..total).map(|_| Stack::new(limits)).collect(),
+                    limits,
+                    keep: config.cached_stacks().max(total),
+                }
+            }
         }
     }
 
@@ -380,6 +486,10 @@ impl EngineInner {
             config: *config,
             res: RwLock::new(EngineResources::new()),
             stacks: Mutex::new(EngineStacks::new(config)),
+            pools: match config.get_allocation_strategy() {
+                InstanceAllocationStrategy::OnDemand => None,
+                InstanceAllocationStrategy::Pooling(pooling) => Some(EnginePools::new(&pooling)),
+            },
         }
     }
 
@@ -652,7 +762,14 @@ impl<'engine> EngineExecutor<'engine> {
             FuncEntity::Wasm(wasm_func) => {
                 self.stack
                     .prepare_wasm_call(wasm_func, &self.res.code_map)?;
-                self.execute_wasm_func(ctx.as_context_mut())?;
+                let func_body = wasm_func.func_body();
+                let marker = ctx.as_context_mut().store.inner.enter_root_call(func_body);
+                let result = self.execute_wasm_func(ctx.as_context_mut());
+                if let Err(TaggedTrap::Wasm(_)) = &result {
+                    // The trapped execution did not return from its calls.
+                    ctx.as_context_mut().store.inner.unwind_root_call(marker);
+                }
+                result?;
             }
             FuncEntity::Host(host_func) => {
                 let host_func = *host_func;
@@ -694,7 +811,15 @@ impl<'engine> EngineExecutor<'engine> {
             self.stack.frames.peek().is_some(),
             "a frame must be on the call stack upon resumption"
         );
-        self.execute_wasm_func(ctx.as_context_mut())?;
+        let result = self.execute_wasm_func(ctx.as_context_mut());
+        if let Err(TaggedTrap::Wasm(_)) = &result {
+            // The caller of the resumed execution is unknown at this point.
+            ctx.as_context_mut()
+                .store
+                .inner
+                .unwind_root_call(RootCallMarker::default());
+        }
+        result?;
         let results = self.write_results_back(results);
         Ok(results)
     }
@@ -783,14 +908,17 @@ impl<'engine> EngineExecutor<'engine> {
         ctx: StoreContextMut<T>,
         cache: &mut InstanceCache,
     ) -> Result<WasmOutcome, Trap> {
-        /// Converts a [`TrapCode`] into a [`Trap`].
+        /// Converts an [`ExecutionError`] into a [`Trap`].
         ///
         /// This function exists for performance reasons since its `#[cold]`
         /// annotation has severe effects on performance.
         #[inline]
         #[cold]
-        fn make_trap(code: TrapCode) -> Trap {
-            code.into()
+        fn make_trap(error: ExecutionError) -> Trap {
+            match error {
+                ExecutionError::TrapCode(code) => code.into(),
+                ExecutionError::Host(error) => error.into(),
+            }
         }
 
         let store_inner = &mut ctx.store.inner;
@@ -0,0 +1,253 @@
+//! Instrumenting profiler recording the Wasm call tree of executions.
+
+use super::CompiledFunc;
+use crate::Module;
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::cmp::Reverse;
+
+/// The index of the root node of the call tree which does not represent a function.
+const ROOT: usize = 0;
+
+/// A Wasm function recorded by a [`Profile`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProfiledFunc(CompiledFunc);
+
+impl ProfiledFunc {
+    /// Returns the function index of the [`ProfiledFunc`] within the `module`.
+    ///
+    /// Returns `None` if the [`ProfiledFunc`] is not an internal function of the `module`.
+    pub fn func_index(&self, module: &Module) -> Option<u32> {
+        module.get_func_index(self.0)
+    }
+}
+
+/// The aggregated statistics of a single [`ProfiledFunc`] of a [`Profile`].
+#[derive(Debug, Copy, Clone)]
+pub struct FuncProfile {
+    /// The profiled function.
+    func: ProfiledFunc,
+    /// The number of times the function has been called.
+    calls: u64,
+    /// The number of instructions executed by the function itself.
+    exclusive_instrs: u64,
+    /// The number of instructions executed by the function and all of its callees.
+    inclusive_instrs: u64,
+}
+
+impl FuncProfile {
+    /// Returns the profiled function.
+    pub fn func(&self) -> ProfiledFunc {
+        self.func
+    }
+
+    /// Returns the number of times the function has been called.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Returns the number of executed instructions of the function excluding its callees.
+    pub fn exclusive_instrs(&self) -> u64 {
+        self.exclusive_instrs
+    }
+
+    /// Returns the number of executed instructions of the function including its callees.
+    ///
+    /// # Note
+    ///
+    /// Instructions of recursive calls are only accounted for once.
+    pub fn inclusive_instrs(&self) -> u64 {
+        self.inclusive_instrs
+    }
+}
+
+/// A node of the call tree of a [`Profile`].
+#[derive(Debug)]
+struct CallNode {
+    /// The called function or `None` for the root node.
+    func: Option<ProfiledFunc>,
+    /// The index of the calling node.
+    parent: usize,
+    /// The indices of the called nodes per callee.
+    children: BTreeMap<ProfiledFunc, usize>,
+    /// The number of times the function has been called from the same call stack.
+    calls: u64,
+    /// The number of instructions executed by the function from the same call stack.
+    instrs: u64,
+}
+
+impl CallNode {
+    /// Creates a new [`CallNode`] for `func` called by the `parent` node.
+    fn new(func: Option<ProfiledFunc>, parent: usize) -> Self {
+        Self {
+            func,
+            parent,
+            children: BTreeMap::new(),
+            calls: 0,
+            instrs: 0,
+        }
+    }
+}
+
+/// The profile of all Wasm executions of a [`Store`] with profiling enabled.
+///
+/// Records the tree of Wasm function calls together with the number of calls and
+/// executed `wasmi` bytecode instructions of every distinct call stack.
+///
+/// # Note
+///
+/// Calls to host functions are not recorded. Wasm functions called by host
+/// functions appear as callees of the Wasm function that called the host function.
+///
+/// [`Store`]: crate::Store
+#[derive(Debug)]
+pub struct Profile {
+    /// The nodes of the call tree with the root node at index 0.
+    nodes: Vec<CallNode>,
+    /// The index of the node of the currently executing function.
+    current: usize,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            nodes: vec![CallNode::new(None, ROOT)],
+            current: ROOT,
+        }
+    }
+}
+
+impl Profile {
+    /// Returns the index of the child node of `parent` for `func`.
+    ///
+    /// Creates the child node if it does not exist, yet.
+    fn child(&mut self, parent: usize, func: CompiledFunc) -> usize {
+        let func = ProfiledFunc(func);
+        if let Some(&child) = self.nodes[parent].children.get(&func) {
+            return child;
+        }
+        let child = self.nodes.len();
+        self.nodes.push(CallNode::new(Some(func), parent));
+        self.nodes[parent].children.insert(func, child);
+        child
+    }
+
+    /// Returns the index of the node of the currently executing function.
+    pub(crate) fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Restores the currently executing function to the node at `current`.
+    ///
+    /// This is used to unwind the call tree after an execution trapped.
+    /// Falls back to the root node if the [`Profile`] has been reset in the meantime.
+    pub(crate) fn unwind_to(&mut self, current: usize) {
+        self.current = match current < self.nodes.len() {
+            true => current,
+            false => ROOT,
+        };
+    }
+
+    /// Records a nested call to `func` by the currently executing function.
+    #[inline]
+    pub(crate) fn call(&mut self, func: CompiledFunc) {
+        self.current = self.child(self.current, func);
+        self.nodes[self.current].calls += 1;
+    }
+
+    /// Records a tail call to `func` replacing the currently executing function.
+    #[inline]
+    pub(crate) fn tail_call(&mut self, func: CompiledFunc) {
+        let parent = self.nodes[self.current].parent;
+        self.current = self.child(parent, func);
+        self.nodes[self.current].calls += 1;
+    }
+
+    /// Records a return from the currently executing function.
+    #[inline]
+    pub(crate) fn ret(&mut self) {
+        self.current = self.nodes[self.current].parent;
+    }
+
+    /// Records the execution of a single instruction by the currently executing function.
+    #[inline(always)]
+    pub(crate) fn count_instr(&mut self) {
+        self.nodes[self.current].instrs += 1;
+    }
+
+    /// Returns the call stack of the node at `index` from its outermost caller.
+    fn stack_of(&self, mut index: usize) -> Vec<ProfiledFunc> {
+        let mut stack = Vec::new();
+        while let Some(func) = self.nodes[index].func {
+            stack.push(func);
+            index = self.nodes[index].parent;
+        }
+        stack.reverse();
+        stack
+    }
+
+    /// Returns all distinct call stacks together with the number of
+    /// instructions that the innermost function executed with them.
+    ///
+    /// Call stacks are ordered from their outermost caller to the innermost callee.
+    ///
+    /// # Note
+    ///
+    /// This is the information that flamegraph tools expect as collapsed stacks.
+    pub fn stacks(&self) -> impl Iterator<Item = (Vec<ProfiledFunc>, u64)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.func.is_some() && node.instrs != 0)
+            .map(|(index, node)| (self.stack_of(index), node.instrs))
+    }
+
+    /// Returns the aggregated [`FuncProfile`] of all recorded functions.
+    ///
+    /// The [`FuncProfile`] are ordered by their number of exclusively executed instructions
+    /// starting with the function that executed the most instructions.
+    pub fn funcs(&self) -> Vec<FuncProfile> {
+        // The inclusive instruction count of every node is the sum of its own
+        // instructions and those of its children. Since children are always
+        // pushed after their parents we can accumulate them in reverse order.
+        let mut inclusive = self
+            .nodes
+            .iter()
+            .map(|node| node.instrs)
+            .collect::<Vec<_>>();
+        for index in (1..self.nodes.len()).rev() {
+            let parent = self.nodes[index].parent;
+            inclusive[parent] += inclusive[index];
+        }
+        let mut funcs = BTreeMap::<ProfiledFunc, FuncProfile>::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let Some(func) = node.func else { continue };
+            let profile = funcs.entry(func).or_insert(FuncProfile {
+                func,
+                calls: 0,
+                exclusive_instrs: 0,
+                inclusive_instrs: 0,
+            });
+            profile.calls += node.calls;
+            profile.exclusive_instrs += node.instrs;
+            if !self.is_recursive(index) {
+                profile.inclusive_instrs += inclusive[index];
+            }
+        }
+        let mut funcs = funcs.into_values().collect::<Vec<_>>();
+        funcs.sort_by_key(|func| Reverse(func.exclusive_instrs));
+        funcs
+    }
+
+    /// Returns `true` if the function of the node at `index` is also one of its callers.
+    fn is_recursive(&self, index: usize) -> bool {
+        let func = self.nodes[index].func;
+        let mut caller = self.nodes[index].parent;
+        while caller != ROOT {
+            if self.nodes[caller].func == func {
+                return true;
+            }
+            caller = self.nodes[caller].parent;
+        }
+        false
+    }
+}
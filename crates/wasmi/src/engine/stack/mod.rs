@@ -7,7 +7,7 @@ pub use self::{
 };
 use crate::{
     core::UntypedValue,
-    engine::{code_map::CodeMap, func_types::FuncTypeRegistry, FuncParams},
+    engine::{code_map::CodeMap, func_types::FuncTypeRegistry, host_calls::call_host, FuncParams},
     func::{HostFuncEntity, WasmFuncEntity},
     AsContext,
     Instance,
@@ -82,6 +82,21 @@ impl StackLimits {
             maximum_recursion_depth,
         })
     }
+
+    /// Returns the initial value stack height that the Wasm stack prepares.
+    pub fn initial_value_stack_height(&self) -> usize {
+        self.initial_value_stack_height
+    }
+
+    /// Returns the maximum value stack height in use that the Wasm stack allows.
+    pub fn maximum_value_stack_height(&self) -> usize {
+        self.maximum_value_stack_height
+    }
+
+    /// Returns the maximum number of nested calls that the Wasm stack allows.
+    pub fn maximum_recursion_depth(&self) -> usize {
+        self.maximum_recursion_depth
+    }
 }
 
 impl Default for StackLimits {
@@ -199,11 +214,6 @@ impl Stack {
         } else {
             0
         };
-        let params_results = FuncParams::new(
-            self.values.peek_as_slice_mut(max_inout),
-            len_inputs,
-            len_outputs,
-        );
         // Now we are ready to perform the host function call.
         // Note: We need to clone the host function due to some borrowing issues.
         //       This should not be a big deal since host functions usually are cheap to clone.
@@ -212,17 +222,31 @@ impl Stack {
             .store
             .resolve_trampoline(host_func.trampoline())
             .clone();
-        trampoline
-            .call(ctx, instance, params_results)
-            .map_err(|error| {
-                // Note: We drop the values that have been temporarily added to
-                //       the stack to act as parameter and result buffer for the
-                //       called host function. Since the host function failed we
-                //       need to clean up the temporary buffer values here.
-                //       This is required for resumable calls to work properly.
-                self.values.drop(delta);
-                error
-            })?;
+        let params_results = self.values.peek_as_slice_mut(max_inout);
+        let result = if ctx.store.inner.host_calls.is_some() {
+            // Host calls are recorded or replayed which is handled out of line.
+            call_host(
+                ctx,
+                &trampoline,
+                &host_func,
+                instance,
+                params_results,
+                len_inputs,
+                len_outputs,
+            )
+        } else {
+            let params_results = FuncParams::new(params_results, len_inputs, len_outputs);
+            trampoline.call(ctx, instance, params_results).map(|_| ())
+        };
+        result.map_err(|error| {
+            // Note: We drop the values that have been temporarily added to
+            //       the stack to act as parameter and result buffer for the
+            //       called host function. Since the host function failed we
+            //       need to clean up the temporary buffer values here.
+            //       This is required for resumable calls to work properly.
+            self.values.drop(delta);
+            error
+        })?;
         // If the host functions returns fewer results than it receives parameters
         // the value stack needs to be shrinked for the delta.
         if len_outputs < len_inputs {
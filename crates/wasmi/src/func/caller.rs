@@ -1,5 +1,5 @@
 use super::super::{AsContext, AsContextMut, StoreContext, StoreContextMut};
-use crate::{store::FuelError, Engine, Extern, Instance};
+use crate::{store::FuelError, Engine, Extern, GuestMemory, Instance, Memory};
 
 /// Represents the caller’s context when creating a host function via [`Func::wrap`].
 ///
@@ -34,6 +34,47 @@ impl<'a, T> Caller<'a, T> {
             .and_then(|instance| instance.get_export(self, name))
     }
 
+    /// Queries the caller for its linear memory at `index`.
+    ///
+    /// The `index` refers to the memory index space of the associated [`Instance`]
+    /// which starts with imported linear memories followed by internal ones.
+    /// Unlike [`Caller::get_export`] this also finds linear memories that are not exported.
+    ///
+    /// Returns `None` if there is no associated [`Instance`] of the caller
+    /// or if the caller has no linear memory at `index`.
+    pub fn get_memory(&self, index: u32) -> Option<Memory> {
+        self.instance
+            .and_then(|instance| instance.get_memory_by_index(self, index))
+    }
+
+    /// Returns a [`GuestMemory`] for typed and bounds checked access to the linear `memory`.
+    ///
+    /// # Panics
+    ///
+    /// If the caller does not own the linear `memory`.
+    pub fn guest_memory(&mut self, memory: Memory) -> GuestMemory<'_> {
+        GuestMemory::new(memory.data_mut(&mut *self))
+    }
+
+    /// Returns a [`GuestMemory`] for the linear `memory` and the user provided host data.
+    ///
+    /// This allows host functions to access the linear memory and their host data at the same time.
+    ///
+    /// # Panics
+    ///
+    /// If the caller does not own the linear `memory`.
+    pub fn guest_memory_and_data(&mut self, memory: Memory) -> (GuestMemory<'_>, &mut T) {
+        let (data, state) = memory.data_and_store_mut(&mut *self);
+        (GuestMemory::new(data), state)
+    }
+
+    /// Returns the [`Instance`] associated to the call if any.
+    ///
+    /// This is `None` if the host function was called from the host side.
+    pub fn instance(&self) -> Option<Instance> {
+        self.instance
+    }
+
     /// Returns a shared reference to the user provided host data.
     pub fn data(&self) -> &T {
         self.ctx.store.data()
@@ -62,7 +62,7 @@ impl ArenaIndex for TrampolineIdx {
 }
 
 /// A host function reference.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct Trampoline(Stored<TrampolineIdx>);
 
@@ -2,7 +2,7 @@ use super::InstanceEntity;
 use crate::{
     engine::DedupFuncType,
     memory::DataSegment,
-    module::FuncIdx,
+    module::{FuncIdx, ImportName},
     ElementSegment,
     Extern,
     ExternType,
@@ -20,6 +20,7 @@ pub struct InstanceEntityBuilder {
     func_types: Arc<[DedupFuncType]>,
     tables: Vec<Table>,
     funcs: Vec<Func>,
+    func_imports: Vec<ImportName>,
     memories: Vec<Memory>,
     globals: Vec<Global>,
     start_fn: Option<FuncIdx>,
@@ -60,6 +61,7 @@ impl InstanceEntityBuilder {
             func_types: module.func_types_cloned(),
             tables: vec_with_capacity_exact(len_tables),
             funcs: vec_with_capacity_exact(len_funcs),
+            func_imports: Vec::new(),
             memories: vec_with_capacity_exact(len_memories),
             globals: vec_with_capacity_exact(len_globals),
             start_fn: None,
@@ -156,6 +158,15 @@ impl InstanceEntityBuilder {
         self.funcs.push(func);
     }
 
+    /// Pushes the imported [`Func`] with its import `name` to the [`InstanceEntity`] under construction.
+    ///
+    /// Imported functions must be pushed before all other functions.
+    pub fn push_imported_func(&mut self, func: Func, name: ImportName) {
+        debug_assert_eq!(self.funcs.len(), self.func_imports.len());
+        self.funcs.push(func);
+        self.func_imports.push(name);
+    }
+
     /// Pushes a new [`Extern`] under the given `name` to the [`InstanceEntity`] under construction.
     ///
     /// # Panics
@@ -188,6 +199,7 @@ impl InstanceEntityBuilder {
             func_types: self.func_types,
             tables: self.tables.into(),
             funcs: self.funcs.into(),
+            func_imports: self.func_imports.into(),
             memories: self.memories.into(),
             globals: self.globals.into(),
             exports: self.exports,
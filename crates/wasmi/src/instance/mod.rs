@@ -14,6 +14,7 @@ use super::{
 use crate::{
     func::FuncError,
     memory::DataSegment,
+    module::ImportName,
     ElementSegment,
     Error,
     TypedFunc,
@@ -50,6 +51,8 @@ pub struct InstanceEntity {
     func_types: Arc<[DedupFuncType]>,
     tables: Box<[Table]>,
     funcs: Box<[Func]>,
+    /// The import names of the imported functions at the start of `funcs`.
+    func_imports: Box<[ImportName]>,
     memories: Box<[Memory]>,
     globals: Box<[Global]>,
     exports: BTreeMap<Box<str>, Extern>,
@@ -65,6 +68,7 @@ impl InstanceEntity {
             func_types: Arc::new([]),
             tables: [].into(),
             funcs: [].into(),
+            func_imports: [].into(),
             memories: [].into(),
             globals: [].into(),
             exports: BTreeMap::new(),
@@ -103,6 +107,13 @@ impl InstanceEntity {
         self.funcs.get(index as usize).copied()
     }
 
+    /// Returns the import names of the imported functions.
+    ///
+    /// The imported functions are the first functions of the function index space.
+    pub fn func_imports(&self) -> &[ImportName] {
+        &self.func_imports
+    }
+
     /// Returns the signature at the `index` if any.
     pub fn get_signature(&self, index: u32) -> Option<&DedupFuncType> {
         self.func_types.get(index as usize)
@@ -177,6 +188,20 @@ impl Instance {
             .get_func(index)
     }
 
+    /// Returns the linear memory at the `index` if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this [`Instance`].
+    pub(crate) fn get_memory_by_index(&self, store: impl AsContext, index: u32) -> Option<Memory> {
+        store
+            .as_context()
+            .store
+            .inner
+            .resolve_instance(self)
+            .get_memory(index)
+    }
+
     /// Returns the value exported to the given `name` if any.
     ///
     /// # Panics
@@ -107,21 +107,31 @@ pub use wasmi_core as core;
 /// Defines some errors that may occur upon interaction with `wasmi`.
 pub mod errors {
     pub use super::{
+        engine::HostCallError,
         func::FuncError,
         global::GlobalError,
         linker::LinkerError,
         memory::MemoryError,
-        module::{InstantiationError, ModuleError},
+        module::{InstantiationError, ModuleError, ModuleSection, ValidationError},
         store::FuelError,
         table::TableError,
     };
 }
 
+#[cfg(feature = "disassembler")]
+pub use self::engine::Disassembly;
+#[cfg(feature = "fuel_accounting")]
+pub use self::engine::{FuelReport, FuncFuel};
+#[cfg(feature = "profiler")]
+pub use self::engine::{FuncProfile, Profile, ProfiledFunc};
 pub use self::{
     engine::{
         Config,
         Engine,
         FuelConsumptionMode,
+        HostCallLog,
+        InstanceAllocationStrategy,
+        PoolingAllocationConfig,
         ResumableCall,
         ResumableInvocation,
         StackLimits,
@@ -146,7 +156,7 @@ pub use self::{
     global::{Global, GlobalType, Mutability},
     instance::{Export, ExportsIter, Extern, ExternType, Instance},
     linker::Linker,
-    memory::{Memory, MemoryType},
+    memory::{GuestMemory, Memory, MemoryType, Pod, WasmPtr, WasmSlice},
     module::{
         ExportType,
         ImportType,
@@ -168,3 +178,5 @@ use self::{
     store::{StoreInner, Stored},
     table::{ElementSegment, ElementSegmentEntity, ElementSegmentIdx, TableEntity, TableIdx},
 };
+#[cfg(feature = "derive")]
+pub use wasmi_derive::host_module;
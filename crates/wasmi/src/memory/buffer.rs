@@ -1,4 +1,5 @@
-use alloc::{vec, vec::Vec};
+use super::MemoryError;
+use alloc::{boxed::Box, vec, vec::Vec};
 
 /// A `Vec`-based byte buffer implementation.
 ///
@@ -25,14 +26,57 @@ impl ByteBuffer {
         }
     }
 
+    /// Creates a new byte buffer with the given initial length initialized with `image`.
+    ///
+    /// # Errors
+    ///
+    /// If the `image` does not fit into the initial length.
+    pub fn new_with_image(initial_len: usize, image: &MemoryImage) -> Result<Self, MemoryError> {
+        let mut buffer = Self::new(initial_len);
+        buffer
+            .bytes
+            .get_mut(..image.bytes.len())
+            .ok_or(MemoryError::OutOfBoundsAllocation)?
+            .copy_from_slice(&image.bytes);
+        Ok(buffer)
+    }
+
+    /// Creates a new empty byte buffer that reserves `capacity` bytes up front.
+    ///
+    /// # Errors
+    ///
+    /// This never fails for the `Vec`-based implementation.
+    pub fn with_capacity(capacity: usize) -> Result<Self, MemoryError> {
+        Ok(Self {
+            bytes: Vec::with_capacity(capacity),
+        })
+    }
+
+    /// Resets the byte buffer to zero length.
+    ///
+    /// # Note
+    ///
+    /// The reserved capacity of the byte buffer is kept for reuse.
+    pub fn reset(&mut self) {
+        self.bytes.clear();
+    }
+
     /// Grows the byte buffer to the given `new_size`.
     ///
+    /// # Errors
+    ///
+    /// If the host fails to allocate the additional bytes.
+    ///
     /// # Panics
     ///
     /// If the current size of the [`ByteBuffer`] is larger than `new_size`.
-    pub fn grow(&mut self, new_size: usize) {
+    pub fn grow(&mut self, new_size: usize) -> Result<(), MemoryError> {
         assert!(new_size >= self.len());
+        self.bytes
+            .try_reserve_exact(new_size - self.len())
+            .map_err(|_| MemoryError::OutOfBoundsAllocation)?;
         self.bytes.resize(new_size, 0x00_u8);
+        Ok(())
     }
 
     /// Returns the length of the byte buffer in bytes.
@@ -50,3 +94,27 @@ impl ByteBuffer {
         &mut self.bytes[..]
     }
 }
+
+/// A pre-initialized image of the contents of a [`ByteBuffer`].
+///
+/// # Note
+///
+/// Without OS provided virtual memory the image is simply copied
+/// into every [`ByteBuffer`] that is created from it.
+#[derive(Debug)]
+pub struct MemoryImage {
+    bytes: Box<[u8]>,
+}
+
+impl MemoryImage {
+    /// Creates a new [`MemoryImage`] from the given `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// This never fails for the `Vec`-based implementation.
+    pub fn new(bytes: &[u8]) -> Result<Self, MemoryError> {
+        Ok(Self {
+            bytes: bytes.into(),
+        })
+    }
+}
@@ -1,5 +1,7 @@
+use super::MemoryError;
+use alloc::string::ToString;
 use core::fmt::Debug;
-use wasmi_core::VirtualMemory;
+use wasmi_core::{VirtualMemory, VirtualMemoryError};
 
 /// A virtual memory based byte buffer implementation.
 ///
@@ -43,14 +45,65 @@ impl ByteBuffer {
         }
     }
 
+    /// Creates a new byte buffer with the given initial length initialized with `image`.
+    ///
+    /// # Note
+    ///
+    /// The pages of the `image` are shared copy-on-write if supported by the platform.
+    ///
+    /// # Errors
+    ///
+    /// If the underlying virtual memory cannot be initialized with the `image`.
+    pub fn new_with_image(initial_len: usize, image: &MemoryImage) -> Result<Self, MemoryError> {
+        let bytes = VirtualMemory::with_image(Self::ALLOCATION_SIZE, &image.image)
+            .map_err(image_creation_error)?;
+        Ok(Self {
+            bytes,
+            len: initial_len,
+        })
+    }
+
+    /// Creates a new empty byte buffer that reserves `capacity` bytes up front.
+    ///
+    /// # Note
+    ///
+    /// The byte buffer cannot grow beyond its `capacity`.
+    ///
+    /// # Errors
+    ///
+    /// If the virtual memory for `capacity` bytes cannot be reserved.
+    pub fn with_capacity(capacity: usize) -> Result<Self, MemoryError> {
+        let bytes =
+            VirtualMemory::new(capacity.max(1)).map_err(|_| MemoryError::OutOfBoundsAllocation)?;
+        Ok(Self { bytes, len: 0 })
+    }
+
+    /// Resets the byte buffer to zero length and zeroes its previously used bytes.
+    ///
+    /// # Note
+    ///
+    /// The reserved capacity of the byte buffer is kept for reuse.
+    pub fn reset(&mut self) {
+        self.bytes.reset(self.len);
+        self.len = 0;
+    }
+
     /// Grows the byte buffer by the given delta.
     ///
     /// # Errors
     ///
-    /// If the new length of the byte buffer would exceed the maximum supported limit.
-    pub fn grow(&mut self, new_size: usize) {
+    /// If the new length of the byte buffer would exceed the reserved virtual memory.
+    ///
+    /// # Panics
+    ///
+    /// If the current size of the [`ByteBuffer`] is larger than `new_size`.
+    pub fn grow(&mut self, new_size: usize) -> Result<(), MemoryError> {
         assert!(new_size >= self.len());
+        if new_size > self.bytes.data().len() {
+            return Err(MemoryError::OutOfBoundsAllocation);
+        }
         self.len = new_size;
+        Ok(())
     }
 
     /// Returns the length of the byte buffer in bytes.
@@ -68,3 +121,28 @@ impl ByteBuffer {
         &mut self.bytes.data_mut()[..self.len]
     }
 }
+
+/// A pre-initialized image of the contents of a [`ByteBuffer`].
+#[derive(Debug)]
+pub struct MemoryImage {
+    image: wasmi_core::MemoryImage,
+}
+
+impl MemoryImage {
+    /// Creates a new [`MemoryImage`] from the given `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// If the operating system fails to create the image.
+    pub fn new(bytes: &[u8]) -> Result<Self, MemoryError> {
+        let image = wasmi_core::MemoryImage::new(bytes).map_err(image_creation_error)?;
+        Ok(Self { image })
+    }
+}
+
+/// Converts the `error` of creating or mapping a [`MemoryImage`] into a [`MemoryError`].
+fn image_creation_error(error: VirtualMemoryError) -> MemoryError {
+    MemoryError::ImageCreation {
+        reason: error.to_string(),
+    }
+}
@@ -1,5 +1,7 @@
 use super::MemoryType;
+use alloc::string::String;
 use core::{fmt, fmt::Display};
+use wasmi_core::HostError;
 
 /// An error that may occur upon operating with virtual or linear memory.
 #[derive(Debug)]
@@ -13,6 +15,27 @@ pub enum MemoryError {
     OutOfBoundsAccess,
     /// Tried to create an invalid linear memory type.
     InvalidMemoryType,
+    /// Tried to allocate a linear memory from a pool without free slots.
+    PoolExhausted,
+    /// Tried to allocate or grow a pooled linear memory beyond the size of a pool slot.
+    ExceedsPoolSlot {
+        /// The maximum number of pages of a pool slot.
+        limit: u32,
+        /// The requested number of pages.
+        requested: u32,
+    },
+    /// Failed to create the copy-on-write image of a linear memory or to map it.
+    ImageCreation {
+        /// The description of the underlying operating system error.
+        reason: String,
+    },
+    /// Tried to read a string from linear memory that is not valid UTF-8.
+    InvalidUtf8,
+    /// Tried to read a string from linear memory that exceeds the length limit.
+    StringTooLong {
+        /// The maximum length of the string in bytes.
+        limit: u32,
+    },
     /// Occurs when `ty` is not a subtype of `other`.
     InvalidSubtype {
         /// The [`MemoryType`] which is not a subtype of `other`.
@@ -37,9 +60,39 @@ impl Display for MemoryError {
             Self::InvalidMemoryType => {
                 write!(f, "tried to create an invalid virtual memory type")
             }
+            Self::PoolExhausted => {
+                write!(f, "no free linear memory slots left in the pool")
+            }
+            Self::ExceedsPoolSlot { limit, requested } => {
+                write!(
+                    f,
+                    "linear memory with {requested} pages exceeds the \
+                    pool slot limit of {limit} pages"
+                )
+            }
+            Self::ImageCreation { reason } => {
+                write!(
+                    f,
+                    "failed to create the copy-on-write linear memory image: {reason}"
+                )
+            }
+            Self::InvalidUtf8 => {
+                write!(f, "linear memory contains invalid UTF-8")
+            }
+            Self::StringTooLong { limit } => {
+                write!(
+                    f,
+                    "string in linear memory exceeds the limit of {limit} bytes"
+                )
+            }
             Self::InvalidSubtype { ty, other } => {
                 write!(f, "memory type {ty:?} is not a subtype of {other:?}",)
             }
         }
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for MemoryError {}
+
+impl HostError for MemoryError {}
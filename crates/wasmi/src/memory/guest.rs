@@ -0,0 +1,149 @@
+use super::{ptr, MemoryError, Pod, WasmPtr, WasmSlice};
+use alloc::vec::Vec;
+
+/// A bounds checked view into the bytes of a linear memory.
+///
+/// Host functions receive a [`GuestMemory`] to safely access the linear memory
+/// of the calling Wasm guest using `u32` pointers and lengths as used by Wasm.
+///
+/// # Note
+///
+/// All accesses fail with [`MemoryError::OutOfBoundsAccess`] if they are out of bounds.
+#[derive(Debug)]
+pub struct GuestMemory<'a> {
+    /// The bytes of the linear memory.
+    data: &'a mut [u8],
+}
+
+impl<'a> GuestMemory<'a> {
+    /// Creates a new [`GuestMemory`] for the bytes of a linear memory.
+    pub fn new(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Returns a shared slice to all the bytes of the linear memory.
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Returns an exclusive slice to all the bytes of the linear memory.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Returns the `len` bytes at `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes are out of bounds of the linear memory.
+    pub fn slice(&self, ptr: u32, len: u32) -> Result<&[u8], MemoryError> {
+        let start = ptr as usize;
+        let end = start
+            .checked_add(len as usize)
+            .ok_or(MemoryError::OutOfBoundsAccess)?;
+        self.data
+            .get(start..end)
+            .ok_or(MemoryError::OutOfBoundsAccess)
+    }
+
+    /// Returns the `len` bytes at `ptr` mutably.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes are out of bounds of the linear memory.
+    pub fn slice_mut(&mut self, ptr: u32, len: u32) -> Result<&mut [u8], MemoryError> {
+        let start = ptr as usize;
+        let end = start
+            .checked_add(len as usize)
+            .ok_or(MemoryError::OutOfBoundsAccess)?;
+        self.data
+            .get_mut(start..end)
+            .ok_or(MemoryError::OutOfBoundsAccess)
+    }
+
+    /// Returns the UTF-8 encoded string of the `slice`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `slice` is longer than `limit` bytes.
+    /// - If the `slice` is out of bounds of the linear memory.
+    /// - If the `slice` is not valid UTF-8.
+    pub fn read_str(&self, slice: WasmSlice<u8>, limit: u32) -> Result<&str, MemoryError> {
+        ptr::read_str(self.data, slice, limit)
+    }
+
+    /// Reads `n` bytes at `ptr` into `buffer` where `n` is the length of `buffer`.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes are out of bounds of the linear memory.
+    pub fn read(&self, ptr: u32, buffer: &mut [u8]) -> Result<(), MemoryError> {
+        let len = u32::try_from(buffer.len()).map_err(|_| MemoryError::OutOfBoundsAccess)?;
+        buffer.copy_from_slice(self.slice(ptr, len)?);
+        Ok(())
+    }
+
+    /// Writes the `bytes` to `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes are out of bounds of the linear memory.
+    pub fn write(&mut self, ptr: u32, bytes: &[u8]) -> Result<(), MemoryError> {
+        let len = u32::try_from(bytes.len()).map_err(|_| MemoryError::OutOfBoundsAccess)?;
+        self.slice_mut(ptr, len)?.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Reads the value at `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// If the value is out of bounds of the linear memory.
+    pub fn read_ptr<T: Pod>(&self, ptr: WasmPtr<T>) -> Result<T, MemoryError> {
+        ptr::read_ptr(self.data, ptr)
+    }
+
+    /// Writes the `value` to `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// If the value is out of bounds of the linear memory.
+    pub fn write_ptr<T: Pod>(&mut self, ptr: WasmPtr<T>, value: T) -> Result<(), MemoryError> {
+        ptr::write_ptr(self.data, ptr, value)
+    }
+
+    /// Reads the values of the `slice`.
+    ///
+    /// # Errors
+    ///
+    /// If the values are out of bounds of the linear memory.
+    pub fn read_slice<T: Pod>(&self, slice: WasmSlice<T>) -> Result<Vec<T>, MemoryError> {
+        ptr::read_slice(self.data, slice)
+    }
+
+    /// Writes the `values` to `ptr`.
+    ///
+    /// # Errors
+    ///
+    /// If the values are out of bounds of the linear memory.
+    pub fn write_slice<T: Pod>(
+        &mut self,
+        ptr: WasmPtr<T>,
+        values: &[T],
+    ) -> Result<(), MemoryError> {
+        ptr::write_slice(self.data, ptr, values)
+    }
+
+    /// Returns the UTF-8 encoded and nul-terminated string at `ptr`.
+    ///
+    /// The returned string does not include the terminating nul byte.
+    ///
+    /// # Errors
+    ///
+    /// - If the string is longer than `limit` bytes without its nul byte.
+    /// - If the string is out of bounds of the linear memory.
+    /// - If the string is not valid UTF-8.
+    pub fn read_c_str(&self, ptr: WasmPtr<u8>, limit: u32) -> Result<&str, MemoryError> {
+        ptr::read_c_str(self.data, ptr, limit)
+    }
+}
@@ -8,16 +8,24 @@ mod byte_buffer;
 
 mod data;
 mod error;
+mod guest;
+mod pool;
+mod ptr;
 
 #[cfg(test)]
 mod tests;
 
 use self::byte_buffer::ByteBuffer;
+pub(crate) use self::{byte_buffer::MemoryImage, pool::MemoryPool};
 pub use self::{
     data::{DataSegment, DataSegmentEntity, DataSegmentIdx},
     error::MemoryError,
+    guest::GuestMemory,
+    ptr::{Pod, WasmPtr, WasmSlice},
 };
 use super::{AsContext, AsContextMut, StoreContext, StoreContextMut, Stored};
+use crate::engine::{record_memory_write, snapshot_memory};
+use alloc::{sync::Arc, vec::Vec};
 use wasmi_arena::ArenaIndex;
 use wasmi_core::Pages;
 
@@ -127,9 +135,26 @@ impl MemoryType {
 /// A linear memory entity.
 #[derive(Debug)]
 pub struct MemoryEntity {
-    bytes: ByteBuffer,
+    /// The underlying bytes of the linear memory.
+    ///
+    /// # Note
+    ///
+    /// This is only `None` while the memory entity is dropped
+    /// so that pooled linear memories can return their bytes
+    /// to their [`MemoryPool`].
+    bytes: Option<ByteBuffer>,
     memory_type: MemoryType,
     current_pages: Pages,
+    /// The [`MemoryPool`] that owns the bytes of a pooled linear memory.
+    pool: Option<Arc<MemoryPool>>,
+}
+
+impl Drop for MemoryEntity {
+    fn drop(&mut self) {
+        if let (Some(pool), Some(bytes)) = (self.pool.take(), self.bytes.take()) {
+            pool.release(bytes);
+        }
+    }
 }
 
 impl MemoryEntity {
@@ -140,9 +165,53 @@ impl MemoryEntity {
             .to_bytes()
             .ok_or(MemoryError::OutOfBoundsAllocation)?;
         let memory = Self {
-            bytes: ByteBuffer::new(initial_len),
+            bytes: Some(ByteBuffer::new(initial_len)),
             memory_type,
             current_pages: initial_pages,
+            pool: None,
+        };
+        Ok(memory)
+    }
+
+    /// Creates a new memory entity with the given memory type from a slot of the `pool`.
+    ///
+    /// # Errors
+    ///
+    /// - If the initial size of the memory type exceeds the size of a pool slot.
+    /// - If the `pool` has no free slots left.
+    pub fn new_pooled(
+        memory_type: MemoryType,
+        pool: &Arc<MemoryPool>,
+    ) -> Result<Self, MemoryError> {
+        let initial_pages = memory_type.initial_pages();
+        let memory = Self {
+            bytes: Some(pool.acquire(initial_pages)?),
+            memory_type,
+            current_pages: initial_pages,
+            pool: Some(pool.clone()),
+        };
+        Ok(memory)
+    }
+
+    /// Creates a new memory entity with the given memory type initialized with `image`.
+    ///
+    /// # Errors
+    ///
+    /// - If the initial size of the memory type cannot be allocated.
+    /// - If the `image` cannot be used to initialize the memory.
+    pub fn new_with_image(
+        memory_type: MemoryType,
+        image: &MemoryImage,
+    ) -> Result<Self, MemoryError> {
+        let initial_pages = memory_type.initial_pages();
+        let initial_len = initial_pages
+            .to_bytes()
+            .ok_or(MemoryError::OutOfBoundsAllocation)?;
+        let memory = Self {
+            bytes: Some(ByteBuffer::new_with_image(initial_len, image)?),
+            memory_type,
+            current_pages: initial_pages,
+            pool: None,
         };
         Ok(memory)
     }
@@ -189,24 +258,48 @@ impl MemoryEntity {
             .checked_add(additional)
             .filter(|&new_pages| new_pages <= maximum_pages)
             .ok_or(MemoryError::OutOfBoundsGrowth)?;
+        if let Some(pool) = &self.pool {
+            // Pooled linear memories cannot grow beyond the size of their slot
+            // which is a limit of the host and not of the linear memory itself.
+            if new_pages > pool.max_pages() {
+                return Err(MemoryError::ExceedsPoolSlot {
+                    limit: u32::from(pool.max_pages()),
+                    requested: u32::from(new_pages),
+                });
+            }
+        }
         let new_size = new_pages
             .to_bytes()
             .ok_or(MemoryError::OutOfBoundsAllocation)?;
         // At this point it is okay to grow the underlying virtual memory
         // by the given amount of additional pages.
-        self.bytes.grow(new_size);
+        self.bytes_mut().grow(new_size)?;
         self.current_pages = new_pages;
         Ok(current_pages)
     }
 
     /// Returns a shared slice to the bytes underlying to the byte buffer.
     pub fn data(&self) -> &[u8] {
-        self.bytes.data()
+        self.bytes().data()
     }
 
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        self.bytes.data_mut()
+        self.bytes_mut().data_mut()
+    }
+
+    /// Returns a shared reference to the underlying byte buffer.
+    fn bytes(&self) -> &ByteBuffer {
+        self.bytes
+            .as_ref()
+            .expect("the bytes of a linear memory are only taken when it is dropped")
+    }
+
+    /// Returns an exclusive reference to the underlying byte buffer.
+    fn bytes_mut(&mut self) -> &mut ByteBuffer {
+        self.bytes
+            .as_mut()
+            .expect("the bytes of a linear memory are only taken when it is dropped")
     }
 
     /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
@@ -217,9 +310,9 @@ impl MemoryEntity {
     /// If this operation accesses out of bounds linear memory.
     pub fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<(), MemoryError> {
         let len_buffer = buffer.len();
-        let slice = self
-            .data()
-            .get(offset..(offset + len_buffer))
+        let slice = offset
+            .checked_add(len_buffer)
+            .and_then(|end| self.data().get(offset..end))
             .ok_or(MemoryError::OutOfBoundsAccess)?;
         buffer.copy_from_slice(slice);
         Ok(())
@@ -233,9 +326,9 @@ impl MemoryEntity {
     /// If this operation accesses out of bounds linear memory.
     pub fn write(&mut self, offset: usize, buffer: &[u8]) -> Result<(), MemoryError> {
         let len_buffer = buffer.len();
-        let slice = self
-            .data_mut()
-            .get_mut(offset..(offset + len_buffer))
+        let slice = offset
+            .checked_add(len_buffer)
+            .and_then(|end| self.data_mut().get_mut(offset..end))
             .ok_or(MemoryError::OutOfBoundsAccess)?;
         slice.copy_from_slice(buffer);
         Ok(())
@@ -269,6 +362,36 @@ impl Memory {
         Ok(memory)
     }
 
+    /// Creates a new linear memory to the store initialized with the given `image`.
+    ///
+    /// # Errors
+    ///
+    /// If the linear memory cannot be allocated or initialized with the `image`.
+    pub(crate) fn new_with_image(
+        mut ctx: impl AsContextMut,
+        ty: MemoryType,
+        image: &MemoryImage,
+    ) -> Result<Self, MemoryError> {
+        let entity = MemoryEntity::new_with_image(ty, image)?;
+        let memory = ctx.as_context_mut().store.inner.alloc_memory(entity);
+        Ok(memory)
+    }
+
+    /// Creates a new linear memory to the store from a slot of the `pool`.
+    ///
+    /// # Errors
+    ///
+    /// If the linear memory does not fit into a slot or the `pool` is exhausted.
+    pub(crate) fn new_pooled(
+        mut ctx: impl AsContextMut,
+        ty: MemoryType,
+        pool: &Arc<MemoryPool>,
+    ) -> Result<Self, MemoryError> {
+        let entity = MemoryEntity::new_pooled(ty, pool)?;
+        let memory = ctx.as_context_mut().store.inner.alloc_memory(entity);
+        Ok(memory)
+    }
+
     /// Returns the memory type of the linear memory.
     ///
     /// # Panics
@@ -348,7 +471,9 @@ impl Memory {
     ///
     /// Panics if `ctx` does not own this [`Memory`].
     pub fn data_mut<'a, T: 'a>(&self, ctx: impl Into<StoreContextMut<'a, T>>) -> &'a mut [u8] {
-        ctx.into().store.inner.resolve_memory_mut(self).data_mut()
+        let store = ctx.into().store;
+        snapshot_memory(&mut store.inner, self);
+        store.inner.resolve_memory_mut(self).data_mut()
     }
 
     /// Returns an exclusive slice to the bytes underlying the [`Memory`], and an exclusive
@@ -361,7 +486,9 @@ impl Memory {
         &self,
         ctx: impl Into<StoreContextMut<'a, T>>,
     ) -> (&'a mut [u8], &'a mut T) {
-        let (memory, store) = ctx.into().store.resolve_memory_and_state_mut(self);
+        let store = ctx.into().store;
+        snapshot_memory(&mut store.inner, self);
+        let (memory, store) = store.resolve_memory_and_state_mut(self);
         (memory.data_mut(), store)
     }
 
@@ -404,10 +531,130 @@ impl Memory {
         offset: usize,
         buffer: &[u8],
     ) -> Result<(), MemoryError> {
-        ctx.as_context_mut()
-            .store
-            .inner
-            .resolve_memory_mut(self)
-            .write(offset, buffer)
+        let store = &mut ctx.as_context_mut().store.inner;
+        store.resolve_memory_mut(self).write(offset, buffer)?;
+        record_memory_write(store, self, offset, buffer.len());
+        Ok(())
+    }
+
+    /// Reads the value at `ptr` from the [`Memory`].
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn read_ptr<T: Pod>(&self, ctx: impl AsContext, ptr: WasmPtr<T>) -> Result<T, MemoryError> {
+        ptr::read_ptr(
+            ctx.as_context().store.inner.resolve_memory(self).data(),
+            ptr,
+        )
+    }
+
+    /// Writes the `value` to `ptr` of the [`Memory`].
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn write_ptr<T: Pod>(
+        &self,
+        mut ctx: impl AsContextMut,
+        ptr: WasmPtr<T>,
+        value: T,
+    ) -> Result<(), MemoryError> {
+        let store = &mut ctx.as_context_mut().store.inner;
+        ptr::write_ptr(store.resolve_memory_mut(self).data_mut(), ptr, value)?;
+        record_memory_write(store, self, ptr.offset() as usize, T::SIZE);
+        Ok(())
+    }
+
+    /// Reads the values of the `slice` from the [`Memory`].
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn read_slice<T: Pod>(
+        &self,
+        ctx: impl AsContext,
+        slice: WasmSlice<T>,
+    ) -> Result<Vec<T>, MemoryError> {
+        ptr::read_slice(
+            ctx.as_context().store.inner.resolve_memory(self).data(),
+            slice,
+        )
+    }
+
+    /// Writes the `values` to `ptr` of the [`Memory`].
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn write_slice<T: Pod>(
+        &self,
+        mut ctx: impl AsContextMut,
+        ptr: WasmPtr<T>,
+        values: &[T],
+    ) -> Result<(), MemoryError> {
+        let store = &mut ctx.as_context_mut().store.inner;
+        ptr::write_slice(store.resolve_memory_mut(self).data_mut(), ptr, values)?;
+        let len = T::SIZE * values.len();
+        record_memory_write(store, self, ptr.offset() as usize, len);
+        Ok(())
+    }
+
+    /// Returns the UTF-8 encoded string of the `slice` of the [`Memory`].
+    ///
+    /// # Errors
+    ///
+    /// - If the `slice` is longer than `limit` bytes.
+    /// - If this operation accesses out of bounds linear memory.
+    /// - If the `slice` is not valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn read_str<'a, T: 'a>(
+        &self,
+        ctx: impl Into<StoreContext<'a, T>>,
+        slice: WasmSlice<u8>,
+        limit: u32,
+    ) -> Result<&'a str, MemoryError> {
+        ptr::read_str(self.data(ctx), slice, limit)
+    }
+
+    /// Returns the UTF-8 encoded and nul-terminated string at `ptr` of the [`Memory`].
+    ///
+    /// The returned string does not include the terminating nul byte.
+    ///
+    /// # Errors
+    ///
+    /// - If the string is longer than `limit` bytes without its nul byte.
+    /// - If this operation accesses out of bounds linear memory.
+    /// - If the string is not valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn read_c_str<'a, T: 'a>(
+        &self,
+        ctx: impl Into<StoreContext<'a, T>>,
+        ptr: WasmPtr<u8>,
+        limit: u32,
+    ) -> Result<&'a str, MemoryError> {
+        ptr::read_c_str(self.data(ctx), ptr, limit)
     }
 }
@@ -0,0 +1,113 @@
+use super::{ByteBuffer, MemoryError};
+use alloc::vec::Vec;
+use spin::Mutex;
+use wasmi_core::Pages;
+
+/// A pool of linear memory slots.
+///
+/// # Note
+///
+/// Each slot is a [`ByteBuffer`] that reserves enough memory for the
+/// maximum number of pages of the pool. Slots are reserved on demand
+/// when they are first acquired and reset when they are released so
+/// that they can be reused by another linear memory.
+#[derive(Debug)]
+pub struct MemoryPool {
+    /// The linear memory slots of the pool.
+    slots: Mutex<MemorySlots>,
+    /// The maximum number of pages of each linear memory slot.
+    max_pages: Pages,
+}
+
+/// The linear memory slots of a [`MemoryPool`].
+#[derive(Debug)]
+struct MemorySlots {
+    /// The reserved but currently unused linear memory slots.
+    free: Vec<ByteBuffer>,
+    /// The number of linear memory slots that have not been reserved, yet.
+    unreserved: u32,
+}
+
+impl MemoryPool {
+    /// Creates a new [`MemoryPool`] with `len_slots` slots of `max_pages` each.
+    ///
+    /// # Note
+    ///
+    /// No memory is reserved until a slot is acquired.
+    pub fn new(len_slots: u32, max_pages: u32) -> Self {
+        let max_pages = Pages::new(max_pages).unwrap_or_else(Pages::max);
+        Self {
+            slots: Mutex::new(MemorySlots {
+                free: Vec::new(),
+                unreserved: len_slots,
+            }),
+            max_pages,
+        }
+    }
+
+    /// Returns the maximum number of pages of each linear memory slot.
+    pub fn max_pages(&self) -> Pages {
+        self.max_pages
+    }
+
+    /// Acquires a linear memory slot with `initial_pages` from the [`MemoryPool`].
+    ///
+    /// # Errors
+    ///
+    /// - If `initial_pages` exceeds the maximum number of pages of a slot.
+    /// - If there are no unused slots left in the [`MemoryPool`].
+    /// - If a new slot cannot be reserved.
+    pub fn acquire(&self, initial_pages: Pages) -> Result<ByteBuffer, MemoryError> {
+        if initial_pages > self.max_pages {
+            return Err(MemoryError::ExceedsPoolSlot {
+                limit: u32::from(self.max_pages),
+                requested: u32::from(initial_pages),
+            });
+        }
+        let initial_len = initial_pages
+            .to_bytes()
+            .ok_or(MemoryError::OutOfBoundsAllocation)?;
+        let free = self.slots.lock().free.pop();
+        let mut bytes = match free {
+            Some(bytes) => bytes,
+            None => self.reserve()?,
+        };
+        if let Err(error) = bytes.grow(initial_len) {
+            self.release(bytes);
+            return Err(error);
+        }
+        Ok(bytes)
+    }
+
+    /// Reserves a new linear memory slot if the [`MemoryPool`] has unreserved slots left.
+    ///
+    /// # Errors
+    ///
+    /// - If all slots of the [`MemoryPool`] have already been reserved.
+    /// - If the memory of the new slot cannot be reserved.
+    fn reserve(&self) -> Result<ByteBuffer, MemoryError> {
+        {
+            let mut slots = self.slots.lock();
+            if slots.unreserved == 0 {
+                return Err(MemoryError::PoolExhausted);
+            }
+            slots.unreserved -= 1;
+        }
+        let reserved = self
+            .max_pages
+            .to_bytes()
+            .ok_or(MemoryError::OutOfBoundsAllocation)
+            .and_then(ByteBuffer::with_capacity);
+        if reserved.is_err() {
+            // The slot can be reserved again later on.
+            self.slots.lock().unreserved += 1;
+        }
+        reserved
+    }
+
+    /// Resets and returns the linear memory slot `bytes` to the [`MemoryPool`].
+    pub fn release(&self, mut bytes: ByteBuffer) {
+        bytes.reset();
+        self.slots.lock().free.push(bytes);
+    }
+}
@@ -0,0 +1,361 @@
+use super::MemoryError;
+use crate::{
+    core::{ValueType, F32, F64},
+    WasmType,
+};
+use alloc::vec::Vec;
+use core::{array, fmt, marker::PhantomData, mem::size_of};
+use wasmi_core::UntypedValue;
+
+/// Plain data that can be copied from and to linear memory.
+///
+/// # Note
+///
+/// Values are stored in little-endian byte order as mandated by Wasm
+/// regardless of the byte order of the host platform.
+pub trait Pod: Copy {
+    /// The number of bytes of the value in linear memory.
+    ///
+    /// Must not be zero. Accessing linear memory with zero-sized [`Pod`] types,
+    /// such as `[u8; 0]`, fails to compile.
+    const SIZE: usize;
+
+    /// Decodes the value from its [`Pod::SIZE`] little-endian `bytes`.
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Encodes the value into its [`Pod::SIZE`] little-endian `bytes`.
+    fn to_le_bytes(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_pod_for_prim {
+    ( $( $prim:ty ),* $(,)? ) => {
+        $(
+            impl Pod for $prim {
+                const SIZE: usize = size_of::<$prim>();
+
+                #[inline]
+                fn from_le_bytes(bytes: &[u8]) -> Self {
+                    let mut buffer = [0x00; size_of::<$prim>()];
+                    buffer.copy_from_slice(bytes);
+                    <$prim>::from_le_bytes(buffer)
+                }
+
+                #[inline]
+                fn to_le_bytes(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&<$prim>::to_le_bytes(self));
+                }
+            }
+        )*
+    };
+}
+impl_pod_for_prim!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+macro_rules! impl_pod_for_float {
+    ( $( $float:ty = $bits:ty ),* $(,)? ) => {
+        $(
+            impl Pod for $float {
+                const SIZE: usize = <$bits as Pod>::SIZE;
+
+                #[inline]
+                fn from_le_bytes(bytes: &[u8]) -> Self {
+                    Self::from_bits(<$bits as Pod>::from_le_bytes(bytes))
+                }
+
+                #[inline]
+                fn to_le_bytes(self, bytes: &mut [u8]) {
+                    Pod::to_le_bytes(self.to_bits(), bytes)
+                }
+            }
+        )*
+    };
+}
+impl_pod_for_float!(F32 = u32, F64 = u64);
+
+impl<T, const N: usize> Pod for [T; N]
+where
+    T: Pod,
+{
+    const SIZE: usize = T::SIZE * N;
+
+    #[inline]
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        array::from_fn(|n| T::from_le_bytes(&bytes[n * T::SIZE..(n + 1) * T::SIZE]))
+    }
+
+    #[inline]
+    fn to_le_bytes(self, bytes: &mut [u8]) {
+        for (value, bytes) in self.into_iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            value.to_le_bytes(bytes);
+        }
+    }
+}
+
+/// A typed 32-bit pointer into the linear memory of a Wasm guest.
+///
+/// # Note
+///
+/// A [`WasmPtr`] is represented as `i32` by Wasm so that it can be used
+/// as parameter or result type of host functions and typed functions.
+pub struct WasmPtr<T> {
+    /// The byte offset of the pointed to value within the linear memory.
+    offset: u32,
+    /// The type of the pointed to value.
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> WasmPtr<T> {
+    /// Creates a new [`WasmPtr`] pointing to the byte `offset` of a linear memory.
+    pub fn new(offset: u32) -> Self {
+        Self {
+            offset,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the byte offset of the [`WasmPtr`] within the linear memory.
+    pub fn offset(self) -> u32 {
+        self.offset
+    }
+
+    /// Returns `true` if the [`WasmPtr`] is the null pointer.
+    pub fn is_null(self) -> bool {
+        self.offset == 0
+    }
+
+    /// Returns the [`WasmPtr`] to the same offset of another type.
+    pub fn cast<U>(self) -> WasmPtr<U> {
+        WasmPtr::new(self.offset)
+    }
+
+    /// Returns the [`WasmSlice`] of `len` values starting at the [`WasmPtr`].
+    pub fn slice(self, len: u32) -> WasmSlice<T> {
+        WasmSlice::new(self, len)
+    }
+}
+
+impl<T> WasmPtr<T>
+where
+    T: Pod,
+{
+    /// Returns the [`WasmPtr`] to the value `count` values after this one.
+    ///
+    /// # Errors
+    ///
+    /// If the resulting offset is out of bounds of the 32-bit address space.
+    pub fn add_offset(self, count: u32) -> Result<Self, MemoryError> {
+        u32::try_from(T::SIZE)
+            .ok()
+            .and_then(|size| size.checked_mul(count))
+            .and_then(|delta| self.offset.checked_add(delta))
+            .map(Self::new)
+            .ok_or(MemoryError::OutOfBoundsAccess)
+    }
+}
+
+impl<T> Clone for WasmPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WasmPtr<T> {}
+
+impl<T> PartialEq for WasmPtr<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+
+impl<T> Eq for WasmPtr<T> {}
+
+impl<T> fmt::Debug for WasmPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WasmPtr({:#x})", self.offset)
+    }
+}
+
+impl<T> From<UntypedValue> for WasmPtr<T> {
+    fn from(untyped: UntypedValue) -> Self {
+        Self::new(u32::from(untyped))
+    }
+}
+
+impl<T> From<WasmPtr<T>> for UntypedValue {
+    fn from(ptr: WasmPtr<T>) -> Self {
+        Self::from(ptr.offset)
+    }
+}
+
+impl<T> WasmType for WasmPtr<T> {
+    #[inline]
+    fn ty() -> ValueType {
+        ValueType::I32
+    }
+}
+
+impl<T> Pod for WasmPtr<T> {
+    const SIZE: usize = <u32 as Pod>::SIZE;
+
+    #[inline]
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(<u32 as Pod>::from_le_bytes(bytes))
+    }
+
+    #[inline]
+    fn to_le_bytes(self, bytes: &mut [u8]) {
+        Pod::to_le_bytes(self.offset, bytes)
+    }
+}
+
+/// A typed slice of `len` consecutive values in the linear memory of a Wasm guest.
+pub struct WasmSlice<T> {
+    /// The pointer to the first value of the slice.
+    ptr: WasmPtr<T>,
+    /// The number of values of the slice.
+    len: u32,
+}
+
+impl<T> WasmSlice<T> {
+    /// Creates a new [`WasmSlice`] of `len` values starting at `ptr`.
+    pub fn new(ptr: WasmPtr<T>, len: u32) -> Self {
+        Self { ptr, len }
+    }
+
+    /// Returns the [`WasmPtr`] to the first value of the [`WasmSlice`].
+    pub fn ptr(self) -> WasmPtr<T> {
+        self.ptr
+    }
+
+    /// Returns the number of values of the [`WasmSlice`].
+    pub fn len(self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the [`WasmSlice`] has no values.
+    pub fn is_empty(self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Clone for WasmSlice<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WasmSlice<T> {}
+
+impl<T> PartialEq for WasmSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr && self.len == other.len
+    }
+}
+
+impl<T> Eq for WasmSlice<T> {}
+
+impl<T> fmt::Debug for WasmSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WasmSlice")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Returns the bytes of `data` underlying the `len` values of type `T` at `ptr`.
+fn bytes_of<T: Pod>(data: &[u8], ptr: WasmPtr<T>, len: u32) -> Result<&[u8], MemoryError> {
+    let (start, end) = byte_range::<T>(ptr, len)?;
+    data.get(start..end).ok_or(MemoryError::OutOfBoundsAccess)
+}
+
+/// Returns the bytes of `data` underlying the `len` values of type `T` at `ptr` mutably.
+fn bytes_of_mut<T: Pod>(
+    data: &mut [u8],
+    ptr: WasmPtr<T>,
+    len: u32,
+) -> Result<&mut [u8], MemoryError> {
+    let (start, end) = byte_range::<T>(ptr, len)?;
+    data.get_mut(start..end)
+        .ok_or(MemoryError::OutOfBoundsAccess)
+}
+
+/// Returns the byte range of the `len` values of type `T` at `ptr`.
+fn byte_range<T: Pod>(ptr: WasmPtr<T>, len: u32) -> Result<(usize, usize), MemoryError> {
+    const { assert!(T::SIZE != 0, "zero-sized `Pod` types cannot be accessed") };
+    let start = ptr.offset() as usize;
+    T::SIZE
+        .checked_mul(len as usize)
+        .and_then(|len| start.checked_add(len))
+        .map(|end| (start, end))
+        .ok_or(MemoryError::OutOfBoundsAccess)
+}
+
+/// Reads the value at `ptr` from `data`.
+pub(super) fn read_ptr<T: Pod>(data: &[u8], ptr: WasmPtr<T>) -> Result<T, MemoryError> {
+    bytes_of(data, ptr, 1).map(T::from_le_bytes)
+}
+
+/// Writes the `value` to `ptr` of `data`.
+pub(super) fn write_ptr<T: Pod>(
+    data: &mut [u8],
+    ptr: WasmPtr<T>,
+    value: T,
+) -> Result<(), MemoryError> {
+    value.to_le_bytes(bytes_of_mut(data, ptr, 1)?);
+    Ok(())
+}
+
+/// Reads the values of the `slice` from `data`.
+pub(super) fn read_slice<T: Pod>(data: &[u8], slice: WasmSlice<T>) -> Result<Vec<T>, MemoryError> {
+    let bytes = bytes_of(data, slice.ptr(), slice.len())?;
+    Ok(bytes.chunks_exact(T::SIZE).map(T::from_le_bytes).collect())
+}
+
+/// Writes the `values` to `ptr` of `data`.
+pub(super) fn write_slice<T: Pod>(
+    data: &mut [u8],
+    ptr: WasmPtr<T>,
+    values: &[T],
+) -> Result<(), MemoryError> {
+    let len = u32::try_from(values.len()).map_err(|_| MemoryError::OutOfBoundsAccess)?;
+    let bytes = bytes_of_mut(data, ptr, len)?;
+    for (value, bytes) in values.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+        value.to_le_bytes(bytes);
+    }
+    Ok(())
+}
+
+/// Returns the UTF-8 encoded string of the `slice` of `data`.
+///
+/// # Errors
+///
+/// - If the `slice` is longer than `limit` bytes.
+/// - If the `slice` is out of bounds or not valid UTF-8.
+pub(super) fn read_str(data: &[u8], slice: WasmSlice<u8>, limit: u32) -> Result<&str, MemoryError> {
+    if slice.len() > limit {
+        return Err(MemoryError::StringTooLong { limit });
+    }
+    let bytes = bytes_of(data, slice.ptr(), slice.len())?;
+    core::str::from_utf8(bytes).map_err(|_| MemoryError::InvalidUtf8)
+}
+
+/// Returns the UTF-8 encoded and nul-terminated string at `ptr` of `data`.
+///
+/// The returned string does not include the terminating nul byte.
+///
+/// # Errors
+///
+/// - If the string without its nul byte is longer than `limit` bytes.
+/// - If the string is out of bounds or not valid UTF-8.
+pub(super) fn read_c_str(data: &[u8], ptr: WasmPtr<u8>, limit: u32) -> Result<&str, MemoryError> {
+    let start = ptr.offset() as usize;
+    let bytes = data.get(start..).ok_or(MemoryError::OutOfBoundsAccess)?;
+    let bytes = &bytes[..bytes.len().min((limit as usize).saturating_add(1))];
+    let Some(len) = bytes.iter().position(|byte| *byte == 0x00) else {
+        if bytes.len() > limit as usize {
+            return Err(MemoryError::StringTooLong { limit });
+        }
+        return Err(MemoryError::OutOfBoundsAccess);
+    };
+    core::str::from_utf8(&bytes[..len]).map_err(|_| MemoryError::InvalidUtf8)
+}
@@ -391,7 +391,11 @@ impl<'engine> ModuleBuilder<'engine> {
     }
 
     /// Finishes construction of the WebAssembly [`Module`].
-    pub fn finish(self) -> Module {
+    ///
+    /// # Errors
+    ///
+    /// If the images of the internal linear memories cannot be created.
+    pub fn finish(self) -> Result<Module, ModuleError> {
         Module::from_builder(self)
     }
 }
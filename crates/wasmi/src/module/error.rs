@@ -1,5 +1,5 @@
-use super::ReadError;
-use crate::engine::TranslationError;
+use super::{ReadError, ValidationError};
+use crate::{engine::TranslationError, memory::MemoryError};
 use core::{
     fmt,
     fmt::{Debug, Display},
@@ -15,6 +15,10 @@ pub enum ModuleError {
     Parser(ParserError),
     /// Encountered when there is a Wasm to `wasmi` translation error.
     Translation(TranslationError),
+    /// Encountered when standalone Wasm validation fails.
+    Validation(ValidationError),
+    /// Encountered when the copy-on-write image of a linear memory cannot be created.
+    Memory(MemoryError),
 }
 
 impl Display for ModuleError {
@@ -23,6 +27,8 @@ impl Display for ModuleError {
             ModuleError::Read(error) => Display::fmt(error, f),
             ModuleError::Parser(error) => Display::fmt(error, f),
             ModuleError::Translation(error) => Display::fmt(error, f),
+            ModuleError::Validation(error) => Display::fmt(error, f),
+            ModuleError::Memory(error) => Display::fmt(error, f),
         }
     }
 }
@@ -44,3 +50,15 @@ impl From<TranslationError> for ModuleError {
         Self::Translation(error)
     }
 }
+
+impl From<ValidationError> for ModuleError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<MemoryError> for ModuleError {
+    fn from(error: MemoryError) -> Self {
+        Self::Memory(error)
+    }
+}
@@ -0,0 +1,105 @@
+use super::{DataSegment, DataSegmentKind};
+use crate::{
+    memory::{MemoryError, MemoryImage},
+    MemoryType,
+};
+use alloc::{boxed::Box, vec::Vec};
+
+/// The pre-computed initial contents of the internal linear memories of a [`Module`].
+///
+/// # Note
+///
+/// A linear memory only has an image if all active data segments that
+/// initialize it have constant offsets and fit into its initial size.
+/// Otherwise the linear memory is initialized by applying the data segments
+/// upon instantiation which also reports out of bounds data segments.
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug, Default)]
+pub struct MemoryImages {
+    /// The memory images indexed by internal linear memory index.
+    images: Box<[Option<MemoryImage>]>,
+}
+
+impl MemoryImages {
+    /// Computes the [`MemoryImages`] of the internal linear memories.
+    ///
+    /// - `len_imported` is the number of imported linear memories.
+    /// - `memories` are the [`MemoryType`] of the internal linear memories.
+    ///
+    /// # Errors
+    ///
+    /// If the host fails to create a [`MemoryImage`], e.g. because it
+    /// does not support the underlying virtual memory operations.
+    pub fn new(
+        len_imported: usize,
+        memories: &[MemoryType],
+        data_segments: &[DataSegment],
+    ) -> Result<Self, MemoryError> {
+        let images = memories
+            .iter()
+            .enumerate()
+            .map(|(index, memory_type)| {
+                Self::compute_image(len_imported + index, memory_type, data_segments)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { images })
+    }
+
+    /// Computes the [`MemoryImage`] for the linear memory at `memory_index` if possible.
+    ///
+    /// Returns `Ok(None)` if the linear memory cannot be represented by an image.
+    ///
+    /// # Errors
+    ///
+    /// If the host fails to create the [`MemoryImage`].
+    fn compute_image(
+        memory_index: usize,
+        memory_type: &MemoryType,
+        data_segments: &[DataSegment],
+    ) -> Result<Option<MemoryImage>, MemoryError> {
+        Self::compute_image_bytes(memory_index, memory_type, data_segments)
+            .map(|bytes| MemoryImage::new(&bytes))
+            .transpose()
+    }
+
+    /// Computes the initial bytes of the linear memory at `memory_index` if possible.
+    fn compute_image_bytes(
+        memory_index: usize,
+        memory_type: &MemoryType,
+        data_segments: &[DataSegment],
+    ) -> Option<Vec<u8>> {
+        let initial_len = memory_type.initial_pages().to_bytes()?;
+        let mut segments = Vec::new();
+        for segment in data_segments {
+            let active = match segment.kind() {
+                DataSegmentKind::Active(active) => active,
+                DataSegmentKind::Passive => continue,
+            };
+            if active.memory_index().into_u32() as usize != memory_index {
+                continue;
+            }
+            let offset = u32::from(active.offset().eval_const()?) as usize;
+            let end = offset.checked_add(segment.bytes().len())?;
+            if end > initial_len {
+                return None;
+            }
+            segments.push((offset, segment.bytes()));
+        }
+        let len_image = segments
+            .iter()
+            .map(|(offset, bytes)| offset + bytes.len())
+            .max()
+            .filter(|&len| len != 0)?;
+        let mut bytes = vec![0x00_u8; len_image];
+        for (offset, segment) in segments {
+            bytes[offset..offset + segment.len()].copy_from_slice(segment);
+        }
+        Some(bytes)
+    }
+
+    /// Returns the [`MemoryImage`] of the internal linear memory at `index` if any.
+    pub fn get(&self, index: usize) -> Option<&MemoryImage> {
+        self.images.get(index).and_then(Option::as_ref)
+    }
+}
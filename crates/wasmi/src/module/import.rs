@@ -15,7 +15,7 @@ pub struct Import {
 }
 
 /// The name or namespace of an imported item.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImportName {
     /// The name of the [`Module`] that defines the imported item.
     ///
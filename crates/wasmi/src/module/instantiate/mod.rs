@@ -60,7 +60,7 @@ impl Module {
         self.extract_imports(&mut context, &mut builder, externals)?;
         self.extract_functions(&mut context, &mut builder, handle);
         self.extract_tables(&mut context, &mut builder)?;
-        self.extract_memories(&mut context, &mut builder);
+        self.extract_memories(&mut context, &mut builder)?;
         self.extract_globals(&mut context, &mut builder);
         self.extract_exports(&mut builder);
         self.extract_start_fn(&mut builder);
@@ -126,7 +126,7 @@ impl Module {
                             expected: expected_signature.clone(),
                         });
                     }
-                    builder.push_func(func);
+                    builder.push_imported_func(func, import.import_name().clone());
                 }
                 (ExternType::Table(required), Extern::Table(table)) => {
                     let imported = table.dynamic_ty(context.as_context());
@@ -189,7 +189,10 @@ impl Module {
     ) -> Result<(), InstantiationError> {
         for table_type in self.internal_tables().copied() {
             let init = Value::default(table_type.element());
-            let table = Table::new(context.as_context_mut(), table_type, init)?;
+            let table = match self.engine.table_pool() {
+                Some(pool) => Table::new_pooled(context.as_context_mut(), table_type, init, pool)?,
+                None => Table::new(context.as_context_mut(), table_type, init)?,
+            };
             builder.push_table(table);
         }
         Ok(())
@@ -204,17 +207,25 @@ impl Module {
         &self,
         context: &mut impl AsContextMut,
         builder: &mut InstanceEntityBuilder,
-    ) {
-        for memory_type in self.internal_memories().copied() {
-            let memory =
-                Memory::new(context.as_context_mut(), memory_type).unwrap_or_else(|error| {
-                    panic!(
-                        "encountered unexpected invalid memory type \
-                        {memory_type:?} after Wasm validation: {error}",
-                    )
-                });
+    ) -> Result<(), InstantiationError> {
+        for (index, memory_type) in self.internal_memories().copied().enumerate() {
+            let memory = match (self.engine.memory_pool(), self.memory_images.get(index)) {
+                (Some(pool), _) => Memory::new_pooled(context.as_context_mut(), memory_type, pool)?,
+                (None, Some(image)) => {
+                    Memory::new_with_image(context.as_context_mut(), memory_type, image)?
+                }
+                (None, None) => {
+                    Memory::new(context.as_context_mut(), memory_type).unwrap_or_else(|error| {
+                        panic!(
+                            "encountered unexpected invalid memory type \
+                            {memory_type:?} after Wasm validation: {error}",
+                        )
+                    })
+                }
+            };
             builder.push_memory(memory);
         }
+        Ok(())
     }
 
     /// Extracts the Wasm global variables from the module and stores them into the [`Store`].
@@ -339,6 +350,14 @@ impl Module {
         Ok(())
     }
 
+    /// Returns `true` if the linear memory at `memory_index` is initialized from a memory image.
+    fn has_memory_image(&self, memory_index: u32) -> bool {
+        (memory_index as usize)
+            .checked_sub(self.imports.len_memories)
+            .and_then(|index| self.memory_images.get(index))
+            .is_some()
+    }
+
     /// Initializes the [`Instance`] linear memories with the Wasm data segments of the [`Module`].
     fn initialize_memory_data(
         &self,
@@ -348,11 +367,17 @@ impl Module {
         for segment in &self.data_segments[..] {
             let bytes = segment.bytes();
             if let DataSegmentKind::Active(segment) = segment.kind() {
-                let offset_expr = segment.offset();
-                let offset =
-                    u32::from(Self::eval_init_expr(&mut *context, builder, offset_expr)) as usize;
-                let memory = builder.get_memory(segment.memory_index().into_u32());
-                memory.write(&mut *context, offset, bytes)?;
+                let memory_index = segment.memory_index().into_u32();
+                // Note: Linear memories with a memory image have already
+                //       been initialized with all of their active data segments.
+                if !self.has_memory_image(memory_index) {
+                    let offset_expr = segment.offset();
+                    let offset =
+                        u32::from(Self::eval_init_expr(&mut *context, builder, offset_expr))
+                            as usize;
+                    let memory = builder.get_memory(memory_index);
+                    memory.write(&mut *context, offset, bytes)?;
+                }
             }
             builder.push_data_segment(DataSegment::new(context.as_context_mut(), segment));
         }
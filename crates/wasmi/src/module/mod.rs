@@ -5,20 +5,24 @@ mod element;
 mod error;
 mod export;
 mod global;
+mod image;
 mod import;
 mod init_expr;
 mod instantiate;
 mod parser;
 mod read;
 mod utils;
+mod validate;
 
 use self::{
     builder::ModuleBuilder,
     export::ExternIdx,
     global::Global,
+    image::MemoryImages,
     import::{ExternTypeIdx, Import},
     parser::parse,
     read::ReadError,
+    validate::validate,
 };
 pub use self::{
     builder::ModuleResources,
@@ -30,6 +34,7 @@ pub use self::{
     instantiate::{InstancePre, InstantiationError},
     parser::ReusableAllocations,
     read::Read,
+    validate::{ModuleSection, ValidationError},
 };
 pub(crate) use self::{
     data::{DataSegment, DataSegmentKind},
@@ -65,6 +70,7 @@ pub struct Module {
     compiled_funcs: Box<[CompiledFunc]>,
     element_segments: Box<[ElementSegment]>,
     data_segments: Box<[DataSegment]>,
+    memory_images: MemoryImages,
 }
 
 /// The index of the default Wasm linear memory.
@@ -147,14 +153,50 @@ impl Module {
         parse(engine, stream).map_err(Into::into)
     }
 
+    /// Validates the Wasm module binary `wasm` for the [`Engine`] without creating a [`Module`].
+    ///
+    /// # Note
+    ///
+    /// This is cheaper than [`Module::new`] since it does not translate the function
+    /// bodies of the Wasm module and does not allocate any resources in the `engine`.
+    ///
+    /// # Errors
+    ///
+    /// If `wasm` is not a valid Wasm module for the Wasm features enabled by the [`Config`]
+    /// of the `engine`. The returned [`ValidationError`] reports the [`ModuleSection`] and
+    /// byte offset at which validation failed.
+    ///
+    /// [`Config`]: crate::Config
+    pub fn validate(engine: &Engine, wasm: &[u8]) -> Result<(), Error> {
+        validate(engine, wasm).map_err(Into::into)
+    }
+
     /// Returns the [`Engine`] used during creation of the [`Module`].
     pub fn engine(&self) -> &Engine {
         &self.engine
     }
 
     /// Creates a new [`Module`] from the [`ModuleBuilder`].
-    fn from_builder(builder: ModuleBuilder) -> Self {
-        Self {
+    ///
+    /// # Errors
+    ///
+    /// If [`Config::memory_init_cow`] is enabled and the host fails to
+    /// create the images of the internal linear memories.
+    ///
+    /// [`Config::memory_init_cow`]: crate::Config::memory_init_cow
+    fn from_builder(builder: ModuleBuilder) -> Result<Self, ModuleError> {
+        let memory_images = match builder.engine().config().get_memory_init_cow() {
+            true => {
+                let len_imported = builder.imports.memories.len();
+                MemoryImages::new(
+                    len_imported,
+                    &builder.memories[len_imported..],
+                    &builder.data_segments,
+                )?
+            }
+            false => MemoryImages::default(),
+        };
+        Ok(Self {
             engine: builder.engine().clone(),
             func_types: builder.func_types.into(),
             imports: ModuleImports::from_builder(builder.imports),
@@ -168,7 +210,8 @@ impl Module {
             compiled_funcs: builder.compiled_funcs.into(),
             element_segments: builder.element_segments.into(),
             data_segments: builder.data_segments.into(),
-        }
+            memory_images,
+        })
     }
 
     /// Returns the number of non-imported functions of the [`Module`].
@@ -227,6 +270,14 @@ impl Module {
         }
     }
 
+    /// Returns the function index of the internal function compiled to `func_body` if any.
+    pub(crate) fn get_func_index(&self, func_body: CompiledFunc) -> Option<u32> {
+        self.compiled_funcs
+            .iter()
+            .position(|compiled_func| *compiled_func == func_body)
+            .map(|position| (position + self.imports.len_funcs) as u32)
+    }
+
     /// Returns an iterator over the [`MemoryType`] of internal linear memories.
     fn internal_memories(&self) -> SliceIter<MemoryType> {
         let len_imported = self.imports.len_memories;
@@ -123,7 +123,7 @@ impl<'engine> ModuleParser<'engine> {
                 }
             }
         }
-        Ok(self.builder.finish())
+        self.builder.finish()
     }
 
     /// Pulls more bytes from the `stream` in order to produce Wasm payload.
@@ -0,0 +1,214 @@
+use super::ModuleError;
+use crate::Engine;
+use alloc::string::{String, ToString};
+use core::{fmt, fmt::Display};
+use wasmparser::{
+    BinaryReaderError as ParserError,
+    FuncValidatorAllocations,
+    Parser as WasmParser,
+    Payload,
+    ValidPayload,
+    Validator,
+};
+
+/// A section of a Wasm module binary.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModuleSection {
+    /// The magic number and version header.
+    Header,
+    /// A custom section.
+    Custom,
+    /// The type section.
+    Type,
+    /// The import section.
+    Import,
+    /// The function section.
+    Function,
+    /// The table section.
+    Table,
+    /// The memory section.
+    Memory,
+    /// The global section.
+    Global,
+    /// The export section.
+    Export,
+    /// The start section.
+    Start,
+    /// The element section.
+    Element,
+    /// The data count section.
+    DataCount,
+    /// The code section.
+    Code,
+    /// The data section.
+    Data,
+    /// A section with an unknown or unsupported identifier.
+    Unknown(u8),
+}
+
+impl ModuleSection {
+    /// Returns the [`ModuleSection`] for the section identifier `id`.
+    fn from_id(id: u8) -> Self {
+        match id {
+            0 => Self::Custom,
+            1 => Self::Type,
+            2 => Self::Import,
+            3 => Self::Function,
+            4 => Self::Table,
+            5 => Self::Memory,
+            6 => Self::Global,
+            7 => Self::Export,
+            8 => Self::Start,
+            9 => Self::Element,
+            10 => Self::Code,
+            11 => Self::Data,
+            12 => Self::DataCount,
+            id => Self::Unknown(id),
+        }
+    }
+}
+
+impl Display for ModuleSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header => write!(f, "header"),
+            Self::Custom => write!(f, "custom section"),
+            Self::Type => write!(f, "type section"),
+            Self::Import => write!(f, "import section"),
+            Self::Function => write!(f, "function section"),
+            Self::Table => write!(f, "table section"),
+            Self::Memory => write!(f, "memory section"),
+            Self::Global => write!(f, "global section"),
+            Self::Export => write!(f, "export section"),
+            Self::Start => write!(f, "start section"),
+            Self::Element => write!(f, "element section"),
+            Self::DataCount => write!(f, "data count section"),
+            Self::Code => write!(f, "code section"),
+            Self::Data => write!(f, "data section"),
+            Self::Unknown(id) => write!(f, "unknown section with id {id}"),
+        }
+    }
+}
+
+/// An error that occurred upon validating a Wasm module binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The section in which the error occurred if any.
+    section: Option<ModuleSection>,
+    /// The byte offset into the Wasm binary at which the error occurred.
+    offset: usize,
+    /// The description of the error.
+    message: String,
+}
+
+impl ValidationError {
+    /// Creates a new [`ValidationError`] from a `wasmparser` error within `section`.
+    fn new(section: Option<ModuleSection>, error: &ParserError) -> Self {
+        Self {
+            section,
+            offset: error.offset(),
+            message: error.message().to_string(),
+        }
+    }
+
+    /// Returns the [`ModuleSection`] in which the error occurred.
+    ///
+    /// Returns `None` if the error occurred outside of any section,
+    /// for example if the Wasm binary ends unexpectedly.
+    pub fn section(&self) -> Option<ModuleSection> {
+        self.section
+    }
+
+    /// Returns the byte offset into the Wasm binary at which the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.section {
+            Some(section) => write!(
+                f,
+                "invalid Wasm module at offset {:#x} in {section}: {}",
+                self.offset, self.message
+            ),
+            None => write!(
+                f,
+                "invalid Wasm module at offset {:#x}: {}",
+                self.offset, self.message
+            ),
+        }
+    }
+}
+
+/// The size in bytes of the magic number and version header of a Wasm module binary.
+const HEADER_SIZE: usize = 8;
+
+/// Returns the [`ModuleSection`] in which parsing `wasm` failed with `error`.
+///
+/// - `current` is the last parsed section and the offset of its end.
+/// - Errors beyond the `current` section occurred in the next section whose
+///   identifier is the byte right after the `current` section if any.
+fn parse_error_section(
+    wasm: &[u8],
+    current: Option<(ModuleSection, usize)>,
+    error: &ParserError,
+) -> Option<ModuleSection> {
+    let (section, end) = current?;
+    if error.offset() < end {
+        return Some(section);
+    }
+    wasm.get(end).copied().map(ModuleSection::from_id)
+}
+
+/// Validates the Wasm module binary `wasm` for the [`Engine`].
+///
+/// # Note
+///
+/// This validates the Wasm binary using the Wasm features enabled by the
+/// [`Config`] of the `engine` without translating its function bodies.
+///
+/// # Errors
+///
+/// If the Wasm binary is malformed or invalid.
+///
+/// [`Config`]: crate::Config
+pub fn validate(engine: &Engine, wasm: &[u8]) -> Result<(), ModuleError> {
+    let mut validator = Validator::new_with_features(engine.config().wasm_features());
+    let mut allocations = FuncValidatorAllocations::default();
+    let mut current = Some((ModuleSection::Header, HEADER_SIZE));
+    for payload in WasmParser::new(0).parse_all(wasm) {
+        let payload = payload.map_err(|error| {
+            ValidationError::new(parse_error_section(wasm, current, &error), &error)
+        })?;
+        current = match &payload {
+            Payload::Version { .. } => Some((ModuleSection::Header, HEADER_SIZE)),
+            Payload::CodeSectionEntry(_) => current,
+            Payload::End(_) => None,
+            payload => payload
+                .as_section()
+                .map(|(id, range)| (ModuleSection::from_id(id), range.end)),
+        };
+        let section = current.map(|(section, _end)| section);
+        let validated = validator
+            .payload(&payload)
+            .map_err(|error| ValidationError::new(section, &error))?;
+        match validated {
+            ValidPayload::Func(func, body) => {
+                let mut func = func.into_validator(allocations);
+                func.validate(&body)
+                    .map_err(|error| ValidationError::new(section, &error))?;
+                allocations = func.into_allocations();
+            }
+            ValidPayload::End(_) => return Ok(()),
+            ValidPayload::Ok | ValidPayload::Parser(_) => {}
+        }
+    }
+    Ok(())
+}
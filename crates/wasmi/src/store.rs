@@ -1,5 +1,9 @@
+#[cfg(feature = "profiler")]
+use crate::Profile;
+#[cfg(feature = "fuel_accounting")]
+use crate::{engine::FuelAccounting, FuelReport};
 use crate::{
-    engine::DedupFuncType,
+    engine::{CompiledFunc, DedupFuncType, HostCallError, HostCalls},
     externref::{ExternObject, ExternObjectEntity, ExternObjectIdx},
     func::{Trampoline, TrampolineEntity, TrampolineIdx},
     memory::DataSegment,
@@ -16,6 +20,7 @@ use crate::{
     Global,
     GlobalEntity,
     GlobalIdx,
+    HostCallLog,
     Instance,
     InstanceEntity,
     InstanceIdx,
@@ -114,6 +119,14 @@ pub struct StoreInner {
     engine: Engine,
     /// The fuel of the [`Store`].
     fuel: Fuel,
+    /// The profile of Wasm executions if profiling is enabled.
+    #[cfg(feature = "profiler")]
+    profile: Option<Profile>,
+    /// The per-function fuel accounting if enabled.
+    #[cfg(feature = "fuel_accounting")]
+    fuel_accounting: Option<FuelAccounting>,
+    /// The recording or replaying of host calls if any.
+    pub(crate) host_calls: Option<HostCalls>,
 }
 
 #[test]
@@ -221,6 +234,20 @@ impl Fuel {
     }
 }
 
+/// The call stack depths of profiling and fuel accounting before a root call into Wasm.
+///
+/// Used to unwind their call stacks if the root call traps.
+/// The default [`RootCallMarker`] unwinds their call stacks entirely.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RootCallMarker {
+    /// The current node of the [`Profile`] before the root call.
+    #[cfg(feature = "profiler")]
+    profile: usize,
+    /// The call stack depth of the [`FuelAccounting`] before the root call.
+    #[cfg(feature = "fuel_accounting")]
+    fuel_accounting: usize,
+}
+
 impl StoreInner {
     /// Creates a new [`StoreInner`] for the given [`Engine`].
     pub fn new(engine: &Engine) -> Self {
@@ -236,6 +263,14 @@ impl StoreInner {
             elems: Arena::new(),
             extern_objects: Arena::new(),
             fuel: Fuel::default(),
+            #[cfg(feature = "profiler")]
+            profile: engine.config().get_profiling().then(Profile::default),
+            #[cfg(feature = "fuel_accounting")]
+            fuel_accounting: engine
+                .config()
+                .get_fuel_accounting()
+                .then(FuelAccounting::default),
+            host_calls: None,
         }
     }
 
@@ -254,6 +289,58 @@ impl StoreInner {
         &mut self.fuel
     }
 
+    /// Returns an exclusive reference to the [`Profile`] if profiling is enabled.
+    #[cfg(feature = "profiler")]
+    #[inline(always)]
+    pub fn profile_mut(&mut self) -> Option<&mut Profile> {
+        self.profile.as_mut()
+    }
+
+    /// Returns an exclusive reference to the [`FuelAccounting`] if enabled.
+    #[cfg(feature = "fuel_accounting")]
+    #[inline(always)]
+    pub fn fuel_accounting_mut(&mut self) -> Option<&mut FuelAccounting> {
+        self.fuel_accounting.as_mut()
+    }
+
+    /// Records a root call into the Wasm function `func` for profiling and fuel accounting.
+    ///
+    /// Returns the [`RootCallMarker`] to unwind to if the root call traps.
+    #[cfg_attr(
+        not(any(feature = "profiler", feature = "fuel_accounting")),
+        allow(unused_variables, unused_mut)
+    )]
+    pub fn enter_root_call(&mut self, func: CompiledFunc) -> RootCallMarker {
+        let mut marker = RootCallMarker::default();
+        #[cfg(feature = "profiler")]
+        if let Some(profile) = &mut self.profile {
+            marker.profile = profile.current();
+            profile.call(func);
+        }
+        #[cfg(feature = "fuel_accounting")]
+        if let Some(accounting) = &mut self.fuel_accounting {
+            marker.fuel_accounting = accounting.depth();
+            accounting.call(func);
+        }
+        marker
+    }
+
+    /// Unwinds profiling and fuel accounting to the `marker` after a trapped root call.
+    #[cfg_attr(
+        not(any(feature = "profiler", feature = "fuel_accounting")),
+        allow(unused_variables)
+    )]
+    pub fn unwind_root_call(&mut self, marker: RootCallMarker) {
+        #[cfg(feature = "profiler")]
+        if let Some(profile) = &mut self.profile {
+            profile.unwind_to(marker.profile);
+        }
+        #[cfg(feature = "fuel_accounting")]
+        if let Some(accounting) = &mut self.fuel_accounting {
+            accounting.unwind_to(marker.fuel_accounting);
+        }
+    }
+
     /// Wraps an entitiy `Idx` (index type) as a [`Stored<Idx>`] type.
     ///
     /// # Note
@@ -772,6 +859,107 @@ impl<T> Store<T> {
             .map_err(|_error| FuelError::out_of_fuel())
     }
 
+    /// Returns the [`FuelReport`] of the fuel consumed by Wasm functions so far.
+    ///
+    /// Returns `None` if per-function fuel accounting is disabled via [`Config::fuel_accounting`].
+    ///
+    /// [`Config::fuel_accounting`]: crate::Config::fuel_accounting
+    #[cfg(feature = "fuel_accounting")]
+    pub fn fuel_report(&self) -> Option<FuelReport> {
+        self.inner
+            .fuel_accounting
+            .as_ref()
+            .map(FuelAccounting::report)
+    }
+
+    /// Discards the fuel attributed to Wasm functions so far if per-function fuel accounting is enabled.
+    #[cfg(feature = "fuel_accounting")]
+    pub fn reset_fuel_report(&mut self) {
+        if let Some(accounting) = &mut self.inner.fuel_accounting {
+            accounting.reset();
+        }
+    }
+
+    /// Returns the [`Profile`] of all Wasm executions of the [`Store`] so far.
+    ///
+    /// Returns `None` if profiling is disabled via [`Config::profiling`].
+    ///
+    /// [`Config::profiling`]: crate::Config::profiling
+    #[cfg(feature = "profiler")]
+    pub fn profile(&self) -> Option<&Profile> {
+        self.inner.profile.as_ref()
+    }
+
+    /// Resets the [`Profile`] of the [`Store`] if profiling is enabled.
+    ///
+    /// Subsequent Wasm executions are recorded into a fresh [`Profile`].
+    #[cfg(feature = "profiler")]
+    pub fn reset_profile(&mut self) {
+        if let Some(profile) = &mut self.inner.profile {
+            *profile = Profile::default();
+        }
+    }
+
+    /// Starts recording the host function calls of subsequent Wasm executions.
+    ///
+    /// Every host call is recorded with the import name of the host function, its parameters,
+    /// its results or trap and the changes it made to the linear memories of the calling
+    /// instance so that the recorded [`HostCallLog`] can be replayed via [`Store::replay_host_calls`].
+    ///
+    /// Discards the [`HostCallLog`] recorded or replayed so far.
+    ///
+    /// # Note
+    ///
+    /// - Host calls nested in other host calls are not recorded since their
+    ///   effects are part of the outer host call.
+    /// - Host functions that are not imported by the calling instance, such as host functions
+    ///   called via [`Func::call`] or via tables of other instances, are neither recorded nor replayed.
+    /// - Changes made by host calls to globals, tables or the linear memories
+    ///   of other instances are not recorded.
+    /// - Host functions with reference typed parameters or results cannot be recorded
+    ///   since references are local to the [`Store`]. Calling them traps with a [`HostCallError`].
+    /// - Linear memories that a host call accesses via [`Memory::data_mut`] or that may be
+    ///   changed by Wasm executed within the host call are copied in order to find their changes.
+    pub fn record_host_calls(&mut self) {
+        self.inner.host_calls = Some(HostCalls::record());
+    }
+
+    /// Replays the host function calls recorded in `log` during subsequent Wasm executions.
+    ///
+    /// Instead of calling the host functions the recorded results, traps and memory changes
+    /// are fed back to the Wasm guest. Wasm executions trap with a [`HostCallError`] if the
+    /// Wasm guest issues a host call that differs from the recorded one.
+    ///
+    /// Discards the [`HostCallLog`] recorded or replayed so far.
+    ///
+    /// # Note
+    ///
+    /// Only the message of traps is recorded. Apart from `i32` exit statuses replayed traps
+    /// are plain [`Trap`]s with the recorded message and no longer carry the typed
+    /// [`HostError`] of the host function, so [`Trap::downcast_ref`] yields `None` for them.
+    ///
+    /// [`Trap`]: crate::core::Trap
+    /// [`HostError`]: crate::core::HostError
+    /// [`Trap::downcast_ref`]: crate::core::Trap::downcast_ref
+    pub fn replay_host_calls(&mut self, log: HostCallLog) {
+        self.inner.host_calls = Some(HostCalls::Replay { log, replayed: 0 });
+    }
+
+    /// Stops recording or replaying host function calls and returns the [`HostCallLog`].
+    ///
+    /// Returns `Ok(None)` if host function calls are neither recorded nor replayed.
+    ///
+    /// # Errors
+    ///
+    /// If the Wasm guest issued fewer host calls than replayed via [`Store::replay_host_calls`].
+    pub fn finish_host_calls(&mut self) -> Result<Option<HostCallLog>, HostCallError> {
+        self.inner
+            .host_calls
+            .take()
+            .map(HostCalls::finish)
+            .transpose()
+    }
+
     /// Allocates a new [`TrampolineEntity`] and returns a [`Trampoline`] reference to it.
     pub(super) fn alloc_trampoline(&mut self, func: TrampolineEntity<T>) -> Trampoline {
         let idx = self.trampolines.alloc(func);
@@ -31,6 +31,15 @@ pub enum TableError {
     },
     /// Occur when coping elements of tables out of bounds.
     CopyOutOfBounds,
+    /// Occurs when allocating a table from a pool without free slots.
+    PoolExhausted,
+    /// Occurs when allocating a table from a pool that does not fit into a pool slot.
+    ExceedsPoolSlot {
+        /// The maximum number of elements of a pool slot.
+        limit: u32,
+        /// The requested number of table elements.
+        requested: u32,
+    },
     /// Occurs when `ty` is not a subtype of `other`.
     InvalidSubtype {
         /// The [`TableType`] which is not a subtype of `other`.
@@ -67,6 +76,16 @@ impl Display for TableError {
             Self::CopyOutOfBounds => {
                 write!(f, "out of bounds access of table elements while copying")
             }
+            Self::PoolExhausted => {
+                write!(f, "no free table slots left in the pool")
+            }
+            Self::ExceedsPoolSlot { limit, requested } => {
+                write!(
+                    f,
+                    "table with {requested} elements exceeds the \
+                    pool slot limit of {limit} elements",
+                )
+            }
             Self::InvalidSubtype { ty, other } => {
                 write!(f, "table type {ty:?} is not a subtype of {other:?}",)
             }
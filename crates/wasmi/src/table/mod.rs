@@ -1,16 +1,18 @@
+pub(crate) use self::pool::TablePool;
 pub use self::{
     element::{ElementSegment, ElementSegmentEntity, ElementSegmentIdx},
     error::TableError,
 };
 use super::{AsContext, AsContextMut, Stored};
 use crate::{module::FuncIdx, value::WithType, Func, FuncRef, Value};
-use alloc::vec::Vec;
-use core::cmp::max;
+use alloc::{sync::Arc, vec::Vec};
+use core::{cmp::max, mem};
 use wasmi_arena::ArenaIndex;
 use wasmi_core::{TrapCode, UntypedValue, ValueType};
 
 mod element;
 mod error;
+mod pool;
 
 #[cfg(test)]
 mod tests;
@@ -137,6 +139,16 @@ impl TableType {
 pub struct TableEntity {
     ty: TableType,
     elements: Vec<UntypedValue>,
+    /// The [`TablePool`] that owns the elements of a pooled table.
+    pool: Option<Arc<TablePool>>,
+}
+
+impl Drop for TableEntity {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.take() {
+            pool.release(mem::take(&mut self.elements));
+        }
+    }
 }
 
 impl TableEntity {
@@ -148,7 +160,32 @@ impl TableEntity {
     pub fn new(ty: TableType, init: Value) -> Result<Self, TableError> {
         ty.matches_element_type(init.ty())?;
         let elements = vec![init.into(); ty.minimum() as usize];
-        Ok(Self { ty, elements })
+        Ok(Self {
+            ty,
+            elements,
+            pool: None,
+        })
+    }
+
+    /// Creates a new table entity with the given resizable limits from a slot of the `pool`.
+    ///
+    /// # Errors
+    ///
+    /// - If `init` does not match the [`TableType`] element type.
+    /// - If the minimum size of the [`TableType`] exceeds the size of a pool slot.
+    /// - If the `pool` has no free slots left.
+    pub fn new_pooled(
+        ty: TableType,
+        init: Value,
+        pool: &Arc<TablePool>,
+    ) -> Result<Self, TableError> {
+        ty.matches_element_type(init.ty())?;
+        let elements = pool.acquire(ty.minimum(), init.into())?;
+        Ok(Self {
+            ty,
+            elements,
+            pool: Some(pool.clone()),
+        })
     }
 
     /// Returns the resizable limits of the table.
@@ -202,7 +239,11 @@ impl TableEntity {
     ///
     /// If the table is grown beyond its maximum limits.
     pub fn grow_untyped(&mut self, delta: u32, init: UntypedValue) -> Result<u32, TableError> {
-        let maximum = self.ty.maximum().unwrap_or(u32::MAX);
+        let mut maximum = self.ty.maximum().unwrap_or(u32::MAX);
+        if let Some(pool) = &self.pool {
+            // Pooled tables cannot grow beyond the size of their slot.
+            maximum = maximum.min(pool.max_elements());
+        }
         let current = self.size();
         let new_len = current
             .checked_add(delta)
@@ -478,6 +519,23 @@ impl Table {
         Ok(table)
     }
 
+    /// Creates a new table to the store from a slot of the `pool`.
+    ///
+    /// # Errors
+    ///
+    /// - If `init` does not match the [`TableType`] element type.
+    /// - If the table does not fit into a slot or the `pool` is exhausted.
+    pub(crate) fn new_pooled(
+        mut ctx: impl AsContextMut,
+        ty: TableType,
+        init: Value,
+        pool: &Arc<TablePool>,
+    ) -> Result<Self, TableError> {
+        let entity = TableEntity::new_pooled(ty, init, pool)?;
+        let table = ctx.as_context_mut().store.inner.alloc_table(entity);
+        Ok(table)
+    }
+
     /// Returns the type and limits of the table.
     ///
     /// # Panics
@@ -0,0 +1,85 @@
+use super::TableError;
+use alloc::vec::Vec;
+use spin::Mutex;
+use wasmi_core::UntypedValue;
+
+/// A pool of table slots.
+///
+/// # Note
+///
+/// Each slot is a vector that reserves enough memory for the maximum
+/// number of elements of the pool. Slots are allocated on demand when
+/// they are first acquired and cleared when they are released so that
+/// they can be reused by another table.
+#[derive(Debug)]
+pub struct TablePool {
+    /// The table slots of the pool.
+    slots: Mutex<TableSlots>,
+    /// The maximum number of elements of each table slot.
+    max_elements: u32,
+}
+
+/// The table slots of a [`TablePool`].
+#[derive(Debug)]
+struct TableSlots {
+    /// The allocated but currently unused table slots.
+    free: Vec<Vec<UntypedValue>>,
+    /// The number of table slots that have not been allocated, yet.
+    unallocated: u32,
+}
+
+impl TablePool {
+    /// Creates a new [`TablePool`] with `len_slots` slots of `max_elements` each.
+    ///
+    /// # Note
+    ///
+    /// No memory is allocated until a slot is acquired.
+    pub fn new(len_slots: u32, max_elements: u32) -> Self {
+        Self {
+            slots: Mutex::new(TableSlots {
+                free: Vec::new(),
+                unallocated: len_slots,
+            }),
+            max_elements,
+        }
+    }
+
+    /// Returns the maximum number of elements of each table slot.
+    pub fn max_elements(&self) -> u32 {
+        self.max_elements
+    }
+
+    /// Acquires a table slot with `len` elements initialized to `init` from the [`TablePool`].
+    ///
+    /// # Errors
+    ///
+    /// - If `len` exceeds the maximum number of elements of a slot.
+    /// - If there are no unused slots left in the [`TablePool`].
+    pub fn acquire(&self, len: u32, init: UntypedValue) -> Result<Vec<UntypedValue>, TableError> {
+        if len > self.max_elements {
+            return Err(TableError::ExceedsPoolSlot {
+                limit: self.max_elements,
+                requested: len,
+            });
+        }
+        let mut elements = {
+            let mut slots = self.slots.lock();
+            match slots.free.pop() {
+                Some(elements) => elements,
+                None if slots.unallocated > 0 => {
+                    slots.unallocated -= 1;
+                    Vec::with_capacity(self.max_elements as usize)
+                }
+                None => return Err(TableError::PoolExhausted),
+            }
+        };
+        elements.resize(len as usize, init);
+        Ok(elements)
+    }
+
+    /// Clears and returns the table slot `elements` to the [`TablePool`].
+    pub fn release(&self, mut elements: Vec<UntypedValue>) {
+        elements.clear();
+        self.slots.lock().free.push(elements);
+    }
+}
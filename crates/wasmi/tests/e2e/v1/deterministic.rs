@@ -0,0 +1,385 @@
+//! Tests for the deterministic execution profile.
+
+use wasmi::{
+    errors::MemoryError,
+    Config,
+    Engine,
+    Instance,
+    InstanceAllocationStrategy,
+    Linker,
+    Module,
+    PoolingAllocationConfig,
+    Store,
+};
+
+/// The bits of the canonical `f32` NaN.
+const F32_CANONICAL_NAN: u32 = 0x7FC0_0000;
+
+/// The bits of the canonical `f64` NaN.
+const F64_CANONICAL_NAN: u64 = 0x7FF8_0000_0000_0000;
+
+/// Creates an [`Engine`] with deterministic execution set to `deterministic`.
+fn engine(deterministic: bool) -> Engine {
+    let mut config = Config::default();
+    config.deterministic(deterministic);
+    Engine::new(&config)
+}
+
+/// Instantiates the `wat` source within a new [`Store`] of the `engine`.
+fn instantiate(engine: &Engine, wat: &str) -> (Store<()>, Instance) {
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(engine, &wasm[..]).unwrap();
+    let mut store = Store::new(engine, ());
+    let linker = <Linker<()>>::new(engine);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+const WAT: &str = r#"
+    (module
+        (func (export "f32.add") (param i32 i32) (result i32)
+            (i32.reinterpret_f32 (f32.add (f32.reinterpret_i32 (local.get 0)) (f32.reinterpret_i32 (local.get 1))))
+        )
+        (func (export "f32.div") (param i32 i32) (result i32)
+            (i32.reinterpret_f32 (f32.div (f32.reinterpret_i32 (local.get 0)) (f32.reinterpret_i32 (local.get 1))))
+        )
+        (func (export "f32.min") (param i32 i32) (result i32)
+            (i32.reinterpret_f32 (f32.min (f32.reinterpret_i32 (local.get 0)) (f32.reinterpret_i32 (local.get 1))))
+        )
+        (func (export "f32.sqrt") (param i32) (result i32)
+            (i32.reinterpret_f32 (f32.sqrt (f32.reinterpret_i32 (local.get 0))))
+        )
+        (func (export "f32.neg") (param i32) (result i32)
+            (i32.reinterpret_f32 (f32.neg (f32.reinterpret_i32 (local.get 0))))
+        )
+        (func (export "f32.demote") (param i64) (result i32)
+            (i32.reinterpret_f32 (f32.demote_f64 (f64.reinterpret_i64 (local.get 0))))
+        )
+        (func (export "f64.mul") (param i64 i64) (result i64)
+            (i64.reinterpret_f64 (f64.mul (f64.reinterpret_i64 (local.get 0)) (f64.reinterpret_i64 (local.get 1))))
+        )
+        (func (export "f64.sub") (param i64 i64) (result i64)
+            (i64.reinterpret_f64 (f64.sub (f64.reinterpret_i64 (local.get 0)) (f64.reinterpret_i64 (local.get 1))))
+        )
+        (func (export "f64.nearest") (param i64) (result i64)
+            (i64.reinterpret_f64 (f64.nearest (f64.reinterpret_i64 (local.get 0))))
+        )
+        (func (export "f64.promote") (param i32) (result i64)
+            (i64.reinterpret_f64 (f64.promote_f32 (f32.reinterpret_i32 (local.get 0))))
+        )
+    )
+"#;
+
+/// Input bits for `f32` operations including NaNs with sign and payload bits.
+const F32_INPUTS: &[u32] = &[
+    0x0000_0000, // 0.0
+    0x8000_0000, // -0.0
+    0x3F80_0000, // 1.0
+    0xBF80_0000, // -1.0
+    0x7F80_0000, // inf
+    0xFF80_0000, // -inf
+    0x0000_0001, // smallest subnormal
+    0x7F7F_FFFF, // max
+    0x7FC0_0000, // canonical NaN
+    0xFFC0_0000, // negative NaN
+    0x7FA0_0001, // signaling NaN with payload
+    0xFFFF_FFFF, // negative NaN with full payload
+];
+
+/// Input bits for `f64` operations including NaNs with sign and payload bits.
+const F64_INPUTS: &[u64] = &[
+    0x0000_0000_0000_0000, // 0.0
+    0x8000_0000_0000_0000, // -0.0
+    0x3FF0_0000_0000_0000, // 1.0
+    0xBFF0_0000_0000_0000, // -1.0
+    0x7FF0_0000_0000_0000, // inf
+    0xFFF0_0000_0000_0000, // -inf
+    0x0000_0000_0000_0001, // smallest subnormal
+    0x7FEF_FFFF_FFFF_FFFF, // max
+    0x7FF8_0000_0000_0000, // canonical NaN
+    0xFFF8_0000_0000_0000, // negative NaN
+    0x7FF4_0000_0000_0001, // signaling NaN with payload
+    0xFFFF_FFFF_FFFF_FFFF, // negative NaN with full payload
+];
+
+/// Returns the expected deterministic result for the `f32` result `bits` of the default engine.
+fn expected_f32(bits: u32) -> u32 {
+    match f32::from_bits(bits).is_nan() {
+        true => F32_CANONICAL_NAN,
+        false => bits,
+    }
+}
+
+/// Returns the expected deterministic result for the `f64` result `bits` of the default engine.
+fn expected_f64(bits: u64) -> u64 {
+    match f64::from_bits(bits).is_nan() {
+        true => F64_CANONICAL_NAN,
+        false => bits,
+    }
+}
+
+#[test]
+fn cross_check_f32_operations() {
+    let (mut default_store, default_instance) = instantiate(&engine(false), WAT);
+    let (mut store, instance) = instantiate(&engine(true), WAT);
+    for name in ["f32.add", "f32.div", "f32.min"] {
+        let default_func = default_instance
+            .get_typed_func::<(u32, u32), u32>(&default_store, name)
+            .unwrap();
+        let func = instance
+            .get_typed_func::<(u32, u32), u32>(&store, name)
+            .unwrap();
+        for &lhs in F32_INPUTS {
+            for &rhs in F32_INPUTS {
+                let default_result = default_func.call(&mut default_store, (lhs, rhs)).unwrap();
+                let result = func.call(&mut store, (lhs, rhs)).unwrap();
+                assert_eq!(
+                    result,
+                    expected_f32(default_result),
+                    "{name}({lhs:#010X}, {rhs:#010X})"
+                );
+            }
+        }
+    }
+    let default_sqrt = default_instance
+        .get_typed_func::<u32, u32>(&default_store, "f32.sqrt")
+        .unwrap();
+    let sqrt = instance
+        .get_typed_func::<u32, u32>(&store, "f32.sqrt")
+        .unwrap();
+    let default_promote = default_instance
+        .get_typed_func::<u32, u64>(&default_store, "f64.promote")
+        .unwrap();
+    let promote = instance
+        .get_typed_func::<u32, u64>(&store, "f64.promote")
+        .unwrap();
+    for &input in F32_INPUTS {
+        let default_result = default_sqrt.call(&mut default_store, input).unwrap();
+        let result = sqrt.call(&mut store, input).unwrap();
+        assert_eq!(
+            result,
+            expected_f32(default_result),
+            "f32.sqrt({input:#010X})"
+        );
+        let default_result = default_promote.call(&mut default_store, input).unwrap();
+        let result = promote.call(&mut store, input).unwrap();
+        assert_eq!(
+            result,
+            expected_f64(default_result),
+            "f64.promote({input:#010X})"
+        );
+    }
+}
+
+#[test]
+fn cross_check_f64_operations() {
+    let (mut default_store, default_instance) = instantiate(&engine(false), WAT);
+    let (mut store, instance) = instantiate(&engine(true), WAT);
+    for name in ["f64.mul", "f64.sub"] {
+        let default_func = default_instance
+            .get_typed_func::<(u64, u64), u64>(&default_store, name)
+            .unwrap();
+        let func = instance
+            .get_typed_func::<(u64, u64), u64>(&store, name)
+            .unwrap();
+        for &lhs in F64_INPUTS {
+            for &rhs in F64_INPUTS {
+                let default_result = default_func.call(&mut default_store, (lhs, rhs)).unwrap();
+                let result = func.call(&mut store, (lhs, rhs)).unwrap();
+                assert_eq!(
+                    result,
+                    expected_f64(default_result),
+                    "{name}({lhs:#018X}, {rhs:#018X})"
+                );
+            }
+        }
+    }
+    let default_nearest = default_instance
+        .get_typed_func::<u64, u64>(&default_store, "f64.nearest")
+        .unwrap();
+    let nearest = instance
+        .get_typed_func::<u64, u64>(&store, "f64.nearest")
+        .unwrap();
+    let default_demote = default_instance
+        .get_typed_func::<u64, u32>(&default_store, "f32.demote")
+        .unwrap();
+    let demote = instance
+        .get_typed_func::<u64, u32>(&store, "f32.demote")
+        .unwrap();
+    for &input in F64_INPUTS {
+        let default_result = default_nearest.call(&mut default_store, input).unwrap();
+        let result = nearest.call(&mut store, input).unwrap();
+        assert_eq!(
+            result,
+            expected_f64(default_result),
+            "f64.nearest({input:#018X})"
+        );
+        let default_result = default_demote.call(&mut default_store, input).unwrap();
+        let result = demote.call(&mut store, input).unwrap();
+        assert_eq!(
+            result,
+            expected_f32(default_result),
+            "f32.demote({input:#018X})"
+        );
+    }
+}
+
+#[test]
+fn nan_results_are_canonical() {
+    let (mut store, instance) = instantiate(&engine(true), WAT);
+    let f32_binary = |store: &mut Store<()>, name: &str, lhs: u32, rhs: u32| {
+        instance
+            .get_typed_func::<(u32, u32), u32>(&*store, name)
+            .unwrap()
+            .call(store, (lhs, rhs))
+            .unwrap()
+    };
+    assert_eq!(
+        f32_binary(&mut store, "f32.add", 0xFFFF_FFFF, 0x3F80_0000),
+        F32_CANONICAL_NAN
+    );
+    assert_eq!(
+        f32_binary(&mut store, "f32.div", 0x0000_0000, 0x8000_0000),
+        F32_CANONICAL_NAN
+    );
+    assert_eq!(
+        f32_binary(&mut store, "f32.min", 0x3F80_0000, 0x7FA0_0001),
+        F32_CANONICAL_NAN
+    );
+    let f32_sqrt = instance
+        .get_typed_func::<u32, u32>(&store, "f32.sqrt")
+        .unwrap();
+    assert_eq!(
+        f32_sqrt.call(&mut store, 0xBF80_0000).unwrap(),
+        F32_CANONICAL_NAN
+    );
+    let f32_demote = instance
+        .get_typed_func::<u64, u32>(&store, "f32.demote")
+        .unwrap();
+    assert_eq!(
+        f32_demote.call(&mut store, 0xFFFF_FFFF_FFFF_FFFF).unwrap(),
+        F32_CANONICAL_NAN
+    );
+    let f64_sub = instance
+        .get_typed_func::<(u64, u64), u64>(&store, "f64.sub")
+        .unwrap();
+    assert_eq!(
+        f64_sub
+            .call(&mut store, (0x7FF0_0000_0000_0000, 0x7FF0_0000_0000_0000))
+            .unwrap(),
+        F64_CANONICAL_NAN
+    );
+    let f64_mul = instance
+        .get_typed_func::<(u64, u64), u64>(&store, "f64.mul")
+        .unwrap();
+    assert_eq!(
+        f64_mul
+            .call(&mut store, (0xFFF8_0000_0000_0000, 0x3FF0_0000_0000_0000))
+            .unwrap(),
+        F64_CANONICAL_NAN
+    );
+    let f64_promote = instance
+        .get_typed_func::<u32, u64>(&store, "f64.promote")
+        .unwrap();
+    assert_eq!(
+        f64_promote.call(&mut store, 0x7FA0_0001).unwrap(),
+        F64_CANONICAL_NAN
+    );
+}
+
+#[test]
+fn bitwise_operations_preserve_nan_bits() {
+    let (mut store, instance) = instantiate(&engine(true), WAT);
+    let neg = instance
+        .get_typed_func::<u32, u32>(&store, "f32.neg")
+        .unwrap();
+    assert_eq!(neg.call(&mut store, 0x7FA0_0001).unwrap(), 0xFFA0_0001);
+}
+
+#[test]
+fn feature_set_is_locked() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func $f (result i32) (i32.const 0))
+            (func (result i32) (return_call $f))
+        )
+        "#,
+    )
+    .unwrap();
+    let mut config = Config::default();
+    config.wasm_tail_call(true);
+    Module::new(&Engine::new(&config), &wasm[..]).unwrap();
+    config.deterministic(true);
+    Module::new(&Engine::new(&config), &wasm[..]).unwrap_err();
+}
+
+#[test]
+fn disabled_proposals_stay_disabled() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func (param f32) (result f32) (local.get 0))
+        )
+        "#,
+    )
+    .unwrap();
+    let mut config = Config::default();
+    config.floats(false).deterministic(true);
+    Module::new(&Engine::new(&config), &wasm[..]).unwrap_err();
+    config.floats(true);
+    Module::new(&Engine::new(&config), &wasm[..]).unwrap();
+}
+
+#[test]
+fn memory_grow_beyond_pool_slot_fails_with_host_error() {
+    let wat = r#"
+        (module
+            (memory 1 4)
+            (func (export "grow") (param i32) (result i32)
+                (memory.grow (local.get 0))
+            )
+        )
+    "#;
+    let mut pooling = PoolingAllocationConfig::default();
+    pooling.total_memories(1).memory_pages(2);
+    let mut config = Config::default();
+    config
+        .allocation_strategy(InstanceAllocationStrategy::Pooling(pooling))
+        .deterministic(true);
+    let (mut store, instance) = instantiate(&Engine::new(&config), wat);
+    let grow = instance.get_typed_func::<i32, i32>(&store, "grow").unwrap();
+    assert_eq!(grow.call(&mut store, 1).unwrap(), 1);
+    // Growing beyond the maximum of the linear memory is deterministic.
+    assert_eq!(grow.call(&mut store, 3).unwrap(), -1);
+    // Growing beyond the pool slot is host dependent and therefore a host error.
+    let error = grow.call(&mut store, 1).unwrap_err();
+    assert!(error.trap_code().is_none());
+    assert!(matches!(
+        error.downcast_ref::<MemoryError>(),
+        Some(MemoryError::ExceedsPoolSlot {
+            limit: 2,
+            requested: 3
+        })
+    ));
+}
+
+#[test]
+fn memory_grow_beyond_limits_returns_error_code() {
+    let wat = r#"
+        (module
+            (memory 1 2)
+            (func (export "grow") (param i32) (result i32)
+                (memory.grow (local.get 0))
+            )
+        )
+    "#;
+    let (mut store, instance) = instantiate(&engine(true), wat);
+    let grow = instance.get_typed_func::<i32, i32>(&store, "grow").unwrap();
+    assert_eq!(grow.call(&mut store, 2).unwrap(), -1);
+    assert_eq!(grow.call(&mut store, 1).unwrap(), 1);
+}
@@ -0,0 +1,89 @@
+//! Tests for the `wasmi` bytecode disassembler.
+
+use wasmi::{Config, Engine, Module};
+
+/// Compiles the `wat` source with an engine using the given `config`.
+fn compile(config: &Config, wat: &str) -> (Engine, Module) {
+    let wasm = wat::parse_str(wat).unwrap();
+    let engine = Engine::new(config);
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    (engine, module)
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "host" (func $host (param i32) (result i32)))
+        (func $inc (param i32) (result i32)
+            (i32.add (local.get 0) (i32.const 1))
+        )
+        (func (export "loop") (param i32) (result i64)
+            (block $exit
+                (loop $continue
+                    (br_if $exit (i32.eqz (local.get 0)))
+                    (local.set 0 (call $inc (i32.sub (local.get 0) (i32.const 2))))
+                    (br $continue)
+                )
+            )
+            (i64.const 0x7FFF_FFFF_FFFF)
+        )
+    )
+"#;
+
+#[test]
+fn disassemble_internal_funcs() {
+    let (engine, module) = compile(&Config::default(), WAT);
+    let inc = engine.disassemble(&module, 1).unwrap();
+    assert_eq!(inc.func_index(), 1);
+    let instrs = inc.instrs().collect::<Vec<_>>();
+    assert!(instrs.contains(&"LocalGet depth=1"));
+    assert!(instrs.contains(&"I32Add"));
+    let disassembly = engine.disassemble(&module, 2).unwrap().to_string();
+    assert!(disassembly.starts_with("func[2]: "));
+    assert!(disassembly.contains("BrIfNez -> "));
+    assert!(disassembly.contains("CallInternal func[1]"));
+    assert!(disassembly.contains("ConstRef const[0] = 0x00007FFFFFFFFFFF"));
+}
+
+#[test]
+fn disassemble_resolves_branch_targets() {
+    let (engine, module) = compile(&Config::default(), WAT);
+    let disassembly = engine.disassemble(&module, 2).unwrap();
+    let instrs = disassembly.instrs().collect::<Vec<_>>();
+    for instr in &instrs {
+        if let Some((_, target)) = instr.split_once(" -> ") {
+            let target = target.parse::<usize>().unwrap();
+            assert!(
+                target < instrs.len(),
+                "branch target {target} out of bounds"
+            );
+        }
+    }
+}
+
+#[test]
+fn disassemble_fuel_blocks() {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let (engine, module) = compile(&config, WAT);
+    let disassembly = engine.disassemble(&module, 2).unwrap();
+    let fuel_blocks = disassembly
+        .instrs()
+        .filter(|instr| instr.starts_with("ConsumeFuel "))
+        .count();
+    assert!(fuel_blocks >= 2);
+}
+
+#[test]
+#[should_panic]
+fn disassemble_module_of_other_engine() {
+    let (_, module) = compile(&Config::default(), WAT);
+    let engine = Engine::default();
+    engine.disassemble(&module, 1);
+}
+
+#[test]
+fn disassemble_non_internal_funcs() {
+    let (engine, module) = compile(&Config::default(), WAT);
+    assert!(engine.disassemble(&module, 0).is_none());
+    assert!(engine.disassemble(&module, 3).is_none());
+}
@@ -0,0 +1,120 @@
+//! Tests for the per-function fuel accounting of `wasmi`.
+
+use wasmi::{Config, Engine, FuelReport, Linker, Module, Store};
+
+const WAT: &str = r#"
+    (module
+        (memory 1)
+        (func $fill (param i32)
+            (memory.fill (i32.const 0) (i32.const 0) (local.get 0))
+        )
+        (func $leaf (param i32) (result i32)
+            (i32.add (local.get 0) (i32.const 1))
+        )
+        (func (export "run") (param i32) (result i32)
+            (call $fill (local.get 0))
+            (call $leaf (local.get 0))
+        )
+        (func (export "tail") (param i32) (result i32)
+            (return_call $leaf (local.get 0))
+        )
+        (func (export "trap") (result i32)
+            (drop (call $leaf (i32.const 0)))
+            (unreachable)
+        )
+    )
+"#;
+
+/// Instantiates the [`WAT`] module with fuel metering and fuel accounting set to `accounting`.
+fn setup(accounting: bool) -> (Store<()>, Module, wasmi::Instance) {
+    let mut config = Config::default();
+    config
+        .consume_fuel(true)
+        .fuel_accounting(accounting)
+        .wasm_tail_call(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    store.add_fuel(1_000_000).unwrap();
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, module, instance)
+}
+
+/// Returns the fuel consumed per function index of the `report`.
+fn consumed(report: &FuelReport, module: &Module) -> Vec<(u32, u64)> {
+    let mut consumed = report
+        .funcs()
+        .iter()
+        .map(|func| (func.func_index(module).unwrap(), func.consumed()))
+        .collect::<Vec<_>>();
+    consumed.sort();
+    consumed
+}
+
+#[test]
+fn fuel_accounting_disabled_by_default() {
+    let (store, _, _) = setup(false);
+    assert!(store.fuel_report().is_none());
+}
+
+#[test]
+fn fuel_accounting_requires_fuel_metering() {
+    let mut config = Config::default();
+    config.fuel_accounting(true);
+    let store = Store::new(&Engine::new(&config), ());
+    assert!(store.fuel_report().is_none());
+}
+
+#[test]
+fn fuel_report_attributes_fuel_to_funcs() {
+    let (mut store, module, instance) = setup(true);
+    let run = instance.get_typed_func::<i32, i32>(&store, "run").unwrap();
+    run.call(&mut store, 1000).unwrap();
+    let report = store.fuel_report().unwrap();
+    assert_eq!(report.total(), store.fuel_consumed().unwrap());
+    let consumed = consumed(&report, &module);
+    assert_eq!(
+        consumed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        vec![0, 1, 2],
+    );
+    // `$fill` is charged for the bulk-operation on top of its instructions.
+    let fill = consumed[0].1;
+    let leaf = consumed[1].1;
+    assert!(fill > leaf);
+    assert_eq!(report.funcs()[0].func_index(&module), Some(0));
+}
+
+#[test]
+fn fuel_report_follows_tail_calls() {
+    let (mut store, module, instance) = setup(true);
+    let tail = instance.get_typed_func::<i32, i32>(&store, "tail").unwrap();
+    tail.call(&mut store, 1).unwrap();
+    let report = store.fuel_report().unwrap();
+    assert_eq!(report.total(), store.fuel_consumed().unwrap());
+    let consumed = consumed(&report, &module);
+    assert_eq!(
+        consumed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        vec![1, 3],
+    );
+}
+
+#[test]
+fn fuel_report_unwinds_traps() {
+    let (mut store, module, instance) = setup(true);
+    let trap = instance.get_typed_func::<(), i32>(&store, "trap").unwrap();
+    trap.call(&mut store, ()).unwrap_err();
+    store.reset_fuel_report();
+    assert_eq!(store.fuel_report().unwrap().total(), 0);
+    let run = instance.get_typed_func::<i32, i32>(&store, "run").unwrap();
+    run.call(&mut store, 0).unwrap();
+    let consumed = consumed(&store.fuel_report().unwrap(), &module);
+    assert_eq!(
+        consumed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        vec![0, 1, 2],
+    );
+}
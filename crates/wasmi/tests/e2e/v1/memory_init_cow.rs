@@ -0,0 +1,150 @@
+//! Tests for initializing linear memories from copy-on-write memory images.
+
+use wasmi::{Config, Engine, Extern, Global, Instance, Linker, Module, Mutability, Store, Value};
+
+/// Creates a [`Module`] from the `wat` source using an [`Engine`] with memory images enabled.
+fn create_module(wat: &str) -> Module {
+    let mut config = Config::default();
+    config.memory_init_cow(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(wat).unwrap();
+    Module::new(&engine, &wasm[..]).unwrap()
+}
+
+/// Instantiates the [`Module`] with an imported global variable `env.offset`.
+fn instantiate(store: &mut Store<()>, module: &Module) -> Result<Instance, wasmi::Error> {
+    let mut linker = <Linker<()>>::new(store.engine());
+    let offset = Global::new(&mut *store, Value::I32(16), Mutability::Const);
+    linker.define("env", "offset", offset)?;
+    linker.instantiate(&mut *store, module)?.start(&mut *store)
+}
+
+/// Returns the first `len` bytes of the exported linear memory of the [`Instance`].
+fn memory_bytes(store: &Store<()>, instance: Instance, len: usize) -> Vec<u8> {
+    let memory = instance.get_memory(store, "memory").unwrap();
+    memory.data(store)[..len].to_vec()
+}
+
+#[test]
+fn initializes_data_segments() {
+    let module = create_module(
+        r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\01\02\03\04")
+            (data (i32.const 2) "\05\06")
+            (data (i32.const 65534) "\07\08")
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    let instance = instantiate(&mut store, &module).unwrap();
+    assert_eq!(memory_bytes(&store, instance, 6), [1, 2, 5, 6, 0, 0]);
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    assert_eq!(&memory.data(&store)[65534..], [7, 8]);
+}
+
+#[test]
+fn instances_do_not_share_writes() {
+    let module = create_module(
+        r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\01\02\03\04")
+            (func (export "store") (param i32 i32)
+                (i32.store8 (local.get 0) (local.get 1))
+            )
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    let a = instantiate(&mut store, &module).unwrap();
+    let b = instantiate(&mut store, &module).unwrap();
+    a.get_typed_func::<(i32, i32), ()>(&store, "store")
+        .unwrap()
+        .call(&mut store, (1, 42))
+        .unwrap();
+    assert_eq!(memory_bytes(&store, a, 4), [1, 42, 3, 4]);
+    assert_eq!(memory_bytes(&store, b, 4), [1, 2, 3, 4]);
+    let c = instantiate(&mut store, &module).unwrap();
+    assert_eq!(memory_bytes(&store, c, 4), [1, 2, 3, 4]);
+}
+
+#[test]
+fn grows_with_zeroed_pages() {
+    let module = create_module(
+        r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 65535) "\01")
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    let instance = instantiate(&mut store, &module).unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    memory
+        .grow(&mut store, wasmi::core::Pages::new(1).unwrap())
+        .unwrap();
+    let data = memory.data(&store);
+    assert_eq!(data[65535], 1);
+    assert!(data[65536..].iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn non_constant_offsets_fall_back() {
+    let module = create_module(
+        r#"
+        (module
+            (import "env" "offset" (global $offset i32))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\01\02")
+            (data (global.get $offset) "\03\04")
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    let instance = instantiate(&mut store, &module).unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    assert_eq!(&memory.data(&store)[..2], [1, 2]);
+    assert_eq!(&memory.data(&store)[16..18], [3, 4]);
+}
+
+#[test]
+fn out_of_bounds_segments_still_fail() {
+    let module = create_module(
+        r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 65535) "\01\02")
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    assert!(instantiate(&mut store, &module).is_err());
+}
+
+#[test]
+fn passive_segments_are_not_applied() {
+    let module = create_module(
+        r#"
+        (module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "\01")
+            (data "\02\03")
+            (func (export "init")
+                (memory.init 1 (i32.const 1) (i32.const 0) (i32.const 2))
+            )
+        )
+    "#,
+    );
+    let mut store = Store::new(module.engine(), ());
+    let instance = instantiate(&mut store, &module).unwrap();
+    assert_eq!(memory_bytes(&store, instance, 3), [1, 0, 0]);
+    let init = instance
+        .get_export(&store, "init")
+        .and_then(Extern::into_func)
+        .unwrap();
+    init.call(&mut store, &[], &mut []).unwrap();
+    assert_eq!(memory_bytes(&store, instance, 3), [1, 2, 3]);
+}
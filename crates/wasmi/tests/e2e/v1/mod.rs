@@ -1,5 +1,17 @@
+mod deterministic;
+#[cfg(feature = "disassembler")]
+mod disassemble;
 mod fuel_consumption_mode;
 mod fuel_metering;
+#[cfg(feature = "fuel_accounting")]
+mod fuel_report;
 mod func;
 mod host_calls_wasm;
+mod memory_init_cow;
+mod module_validate;
+mod pooling_allocator;
+#[cfg(feature = "profiler")]
+mod profiler;
+mod record_replay;
 mod resumable_call;
+mod wasm_ptr;
@@ -0,0 +1,98 @@
+//! Tests for standalone Wasm module validation.
+
+use wasmi::{
+    errors::{ModuleError, ModuleSection, ValidationError},
+    Config,
+    Engine,
+    Error,
+    Module,
+};
+
+/// Validates the `wasm` binary and returns the [`ValidationError`] if any.
+fn validate(engine: &Engine, wasm: &[u8]) -> Result<(), ValidationError> {
+    match Module::validate(engine, wasm) {
+        Ok(()) => Ok(()),
+        Err(Error::Module(ModuleError::Validation(error))) => Err(error),
+        Err(error) => panic!("unexpected error: {error}"),
+    }
+}
+
+#[test]
+fn valid_module() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (memory 1)
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    validate(&Engine::default(), &wasm).unwrap();
+}
+
+#[test]
+fn invalid_function_body() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func (result i32)
+                (i64.const 0)
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let error = validate(&Engine::default(), &wasm).unwrap_err();
+    assert_eq!(error.section(), Some(ModuleSection::Code));
+    assert!(error.offset() > 0 && error.offset() < wasm.len());
+    assert!(error.message().contains("type mismatch"));
+}
+
+#[test]
+fn malformed_header() {
+    let error = validate(&Engine::default(), b"\0asm\x02\0\0\0").unwrap_err();
+    assert_eq!(error.section(), Some(ModuleSection::Header));
+    assert!(error.message().contains("version"));
+}
+
+#[test]
+fn disabled_feature() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func (param i32) (result i32)
+                (i32.extend8_s (local.get 0))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let mut config = Config::default();
+    config.wasm_sign_extension(false);
+    let engine = Engine::new(&config);
+    let error = validate(&engine, &wasm).unwrap_err();
+    assert_eq!(error.section(), Some(ModuleSection::Code));
+    validate(&Engine::default(), &wasm).unwrap();
+}
+
+#[test]
+fn missing_code_section() {
+    let mut wasm = wat::parse_str("(module (func))").unwrap();
+    // Remove the code section which is the last section of the module.
+    let code_section = wasm.iter().rposition(|&byte| byte == 0x0A).unwrap();
+    wasm.truncate(code_section);
+    let error = validate(&Engine::default(), &wasm).unwrap_err();
+    assert_eq!(error.section(), None);
+}
+
+#[test]
+fn malformed_section_header() {
+    let mut wasm = wat::parse_str("(module (type (func)))").unwrap();
+    // Append a function section whose size exceeds the end of the Wasm binary.
+    wasm.extend_from_slice(&[0x03, 0x05, 0x01]);
+    let error = validate(&Engine::default(), &wasm).unwrap_err();
+    assert_eq!(error.section(), Some(ModuleSection::Function));
+}
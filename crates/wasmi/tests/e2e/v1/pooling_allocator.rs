@@ -0,0 +1,154 @@
+//! Tests for the pooling instance allocation strategy.
+
+use wasmi::{
+    errors::{InstantiationError, MemoryError, TableError},
+    Config,
+    Engine,
+    Error,
+    Instance,
+    InstanceAllocationStrategy,
+    Linker,
+    Module,
+    PoolingAllocationConfig,
+    Store,
+};
+
+/// Creates an [`Engine`] using the pooling allocation strategy with small limits.
+fn pooling_engine() -> Engine {
+    let mut pooling = PoolingAllocationConfig::default();
+    pooling
+        .total_memories(2)
+        .memory_pages(2)
+        .total_tables(2)
+        .table_elements(10)
+        .total_stacks(1);
+    let mut config = Config::default();
+    config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+    Engine::new(&config)
+}
+
+/// Creates a [`Module`] from the `wat` source.
+fn create_module(engine: &Engine, wat: &str) -> Module {
+    let wasm = wat::parse_str(wat).unwrap();
+    Module::new(engine, &wasm[..]).unwrap()
+}
+
+/// Instantiates the [`Module`] within the [`Store`].
+fn instantiate(store: &mut Store<()>, module: &Module) -> Result<Instance, Error> {
+    let linker = <Linker<()>>::new(store.engine());
+    linker.instantiate(&mut *store, module)?.start(&mut *store)
+}
+
+const WAT: &str = r#"
+    (module
+        (memory (export "memory") 1 4)
+        (table (export "table") 1 20 funcref)
+        (data (i32.const 0) "\01\02")
+        (func (export "store") (param i32 i32)
+            (i32.store8 (local.get 0) (local.get 1))
+        )
+        (func (export "grow_memory") (param i32) (result i32)
+            (memory.grow (local.get 0))
+        )
+        (func (export "grow_table") (param i32) (result i32)
+            (table.grow (ref.null func) (local.get 0))
+        )
+    )
+"#;
+
+#[test]
+fn slots_are_exhausted() {
+    let engine = pooling_engine();
+    let module = create_module(&engine, WAT);
+    let mut store = Store::new(&engine, ());
+    instantiate(&mut store, &module).unwrap();
+    instantiate(&mut store, &module).unwrap();
+    let error = instantiate(&mut store, &module).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Instantiation(InstantiationError::Table(TableError::PoolExhausted))
+    ));
+}
+
+#[test]
+fn slots_are_reset_and_reused() {
+    let engine = pooling_engine();
+    let module = create_module(&engine, WAT);
+    for _ in 0..5 {
+        let mut store = Store::new(&engine, ());
+        let instance = instantiate(&mut store, &module).unwrap();
+        let memory = instance.get_memory(&store, "memory").unwrap();
+        assert_eq!(&memory.data(&store)[..4], [1, 2, 0, 0]);
+        instance
+            .get_typed_func::<(i32, i32), ()>(&store, "store")
+            .unwrap()
+            .call(&mut store, (3, 42))
+            .unwrap();
+        assert_eq!(&memory.data(&store)[..4], [1, 2, 0, 42]);
+    }
+}
+
+#[test]
+fn growth_is_limited_by_slots() {
+    let engine = pooling_engine();
+    let module = create_module(&engine, WAT);
+    let mut store = Store::new(&engine, ());
+    let instance = instantiate(&mut store, &module).unwrap();
+    let grow_memory = instance
+        .get_typed_func::<i32, i32>(&store, "grow_memory")
+        .unwrap();
+    assert_eq!(grow_memory.call(&mut store, 1).unwrap(), 1);
+    assert_eq!(grow_memory.call(&mut store, 1).unwrap(), -1);
+    let grow_table = instance
+        .get_typed_func::<i32, i32>(&store, "grow_table")
+        .unwrap();
+    assert_eq!(grow_table.call(&mut store, 9).unwrap(), 1);
+    assert_eq!(grow_table.call(&mut store, 1).unwrap(), -1);
+}
+
+#[test]
+fn oversized_memories_are_rejected() {
+    let engine = pooling_engine();
+    let module = create_module(&engine, r#"(module (memory 3))"#);
+    let mut store = Store::new(&engine, ());
+    let error = instantiate(&mut store, &module).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Instantiation(InstantiationError::Memory(MemoryError::ExceedsPoolSlot {
+            limit: 2,
+            requested: 3,
+        }))
+    ));
+}
+
+#[test]
+fn oversized_tables_are_rejected() {
+    let engine = pooling_engine();
+    let module = create_module(&engine, r#"(module (table 11 funcref))"#);
+    let mut store = Store::new(&engine, ());
+    let error = instantiate(&mut store, &module).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Instantiation(InstantiationError::Table(TableError::ExceedsPoolSlot {
+            limit: 10,
+            requested: 11,
+        }))
+    ));
+}
+
+#[test]
+fn slots_are_reserved_on_demand() {
+    // Reserving all slots upfront would require petabytes of memory.
+    let mut pooling = PoolingAllocationConfig::default();
+    pooling
+        .total_memories(1_000_000)
+        .memory_pages(65_536)
+        .total_tables(1_000_000)
+        .table_elements(1_000_000);
+    let mut config = Config::default();
+    config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+    let engine = Engine::new(&config);
+    let module = create_module(&engine, "(module)");
+    let mut store = Store::new(&engine, ());
+    instantiate(&mut store, &module).unwrap();
+}
@@ -0,0 +1,187 @@
+//! Tests for the `wasmi` instrumenting profiler.
+
+use wasmi::{Caller, Config, Engine, Func, Linker, Module, Store};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "host" (func $host (param i32)))
+        (func $leaf (param i32) (result i32)
+            (i32.add (local.get 0) (i32.const 1))
+        )
+        (func $middle (param i32) (result i32)
+            (call $leaf (call $leaf (local.get 0)))
+        )
+        (func $fact (param i32) (result i32)
+            (if (result i32) (i32.eqz (local.get 0))
+                (then (i32.const 1))
+                (else
+                    (i32.mul
+                        (local.get 0)
+                        (call $fact (i32.sub (local.get 0) (i32.const 1)))
+                    )
+                )
+            )
+        )
+        (func (export "run") (param i32) (result i32)
+            (call $host (local.get 0))
+            (i32.add (call $middle (local.get 0)) (call $leaf (local.get 0)))
+        )
+        (func (export "fact") (param i32) (result i32)
+            (call $fact (local.get 0))
+        )
+        (func (export "trap") (result i32)
+            (drop (call $leaf (i32.const 0)))
+            (unreachable)
+        )
+        (func (export "callback") (param i32) (result i32)
+            (call $leaf (local.get 0))
+        )
+    )
+"#;
+
+/// Instantiates the [`WAT`] module with profiling set to `profiling`.
+///
+/// The imported host function calls back into the exported `callback` function.
+fn setup(profiling: bool) -> (Store<()>, Module, wasmi::Instance) {
+    let mut config = Config::default();
+    config.profiling(profiling);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let host = Func::wrap(&mut store, |mut caller: Caller<()>, input: i32| {
+        let callback = caller
+            .get_export("callback")
+            .and_then(|export| export.into_func())
+            .unwrap()
+            .typed::<i32, i32>(&caller)
+            .unwrap();
+        callback.call(&mut caller, input).unwrap();
+    });
+    let mut linker = <Linker<()>>::new(&engine);
+    linker.define("env", "host", host).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, module, instance)
+}
+
+/// Returns the recorded call stacks as function indices.
+fn stacks(store: &Store<()>, module: &Module) -> Vec<(Vec<u32>, u64)> {
+    let mut stacks = store
+        .profile()
+        .unwrap()
+        .stacks()
+        .map(|(stack, instrs)| {
+            let stack = stack
+                .iter()
+                .map(|func| func.func_index(module).unwrap())
+                .collect::<Vec<_>>();
+            (stack, instrs)
+        })
+        .collect::<Vec<_>>();
+    stacks.sort();
+    stacks
+}
+
+#[test]
+fn profiling_disabled_by_default() {
+    let (store, _, _) = setup(false);
+    assert!(store.profile().is_none());
+}
+
+#[test]
+fn profile_records_call_tree() {
+    let (mut store, module, instance) = setup(true);
+    let run = instance.get_typed_func::<i32, i32>(&store, "run").unwrap();
+    assert_eq!(run.call(&mut store, 1).unwrap(), 5);
+    let stacks = stacks(&store, &module);
+    let call_paths = stacks
+        .iter()
+        .map(|(stack, _)| stack.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        call_paths,
+        vec![
+            // `run`
+            vec![4],
+            // `run` -> `leaf`
+            vec![4, 1],
+            // `run` -> `middle`
+            vec![4, 2],
+            // `run` -> `middle` -> `leaf`
+            vec![4, 2, 1],
+            // `run` -> host -> `callback`
+            vec![4, 7],
+            // `run` -> host -> `callback` -> `leaf`
+            vec![4, 7, 1],
+        ]
+    );
+    assert!(stacks.iter().all(|(_, instrs)| *instrs != 0));
+    let funcs = store.profile().unwrap().funcs();
+    let leaf = funcs
+        .iter()
+        .find(|func| func.func().func_index(&module) == Some(1))
+        .unwrap();
+    assert_eq!(leaf.calls(), 4);
+    assert_eq!(leaf.exclusive_instrs(), leaf.inclusive_instrs());
+    let run = funcs
+        .iter()
+        .find(|func| func.func().func_index(&module) == Some(4))
+        .unwrap();
+    assert_eq!(run.calls(), 1);
+    let total = stacks.iter().map(|(_, instrs)| instrs).sum::<u64>();
+    assert_eq!(run.inclusive_instrs(), total);
+    assert!(run.exclusive_instrs() < run.inclusive_instrs());
+}
+
+#[test]
+fn profile_handles_recursion() {
+    let (mut store, module, instance) = setup(true);
+    let fact = instance.get_typed_func::<i32, i32>(&store, "fact").unwrap();
+    assert_eq!(fact.call(&mut store, 3).unwrap(), 6);
+    let funcs = store.profile().unwrap().funcs();
+    let fact = funcs
+        .iter()
+        .find(|func| func.func().func_index(&module) == Some(3))
+        .unwrap();
+    assert_eq!(fact.calls(), 4);
+    let total = stacks(&store, &module)
+        .iter()
+        .filter(|(stack, _)| stack.len() > 1)
+        .map(|(_, instrs)| instrs)
+        .sum::<u64>();
+    assert_eq!(fact.inclusive_instrs(), total);
+    assert_eq!(fact.exclusive_instrs(), total);
+}
+
+#[test]
+fn profile_unwinds_traps() {
+    let (mut store, module, instance) = setup(true);
+    let trap = instance.get_typed_func::<(), i32>(&store, "trap").unwrap();
+    trap.call(&mut store, ()).unwrap_err();
+    let fact = instance.get_typed_func::<i32, i32>(&store, "fact").unwrap();
+    fact.call(&mut store, 0).unwrap();
+    let call_paths = stacks(&store, &module)
+        .into_iter()
+        .map(|(stack, _)| stack)
+        .collect::<Vec<_>>();
+    assert_eq!(call_paths, vec![vec![5], vec![5, 3], vec![6], vec![6, 1]]);
+}
+
+#[test]
+fn reset_profile_discards_recordings() {
+    let (mut store, module, instance) = setup(true);
+    let fact = instance.get_typed_func::<i32, i32>(&store, "fact").unwrap();
+    fact.call(&mut store, 2).unwrap();
+    store.reset_profile();
+    assert_eq!(stacks(&store, &module), vec![]);
+    fact.call(&mut store, 0).unwrap();
+    let call_paths = stacks(&store, &module)
+        .into_iter()
+        .map(|(stack, _)| stack)
+        .collect::<Vec<_>>();
+    assert_eq!(call_paths, vec![vec![5], vec![5, 3]]);
+}
@@ -0,0 +1,427 @@
+//! Tests for the recording and replaying of host function calls.
+
+use wasmi::{
+    core::Trap,
+    errors::HostCallError,
+    Caller,
+    Engine,
+    Extern,
+    ExternRef,
+    Func,
+    FuncRef,
+    HostCallLog,
+    Instance,
+    Linker,
+    Module,
+    Store,
+    Value,
+};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "read" (func $read (param i32 i32) (result i32)))
+        (import "env" "now" (func $now (result i64)))
+        (import "env" "exit" (func $exit (param i32)))
+        (memory (export "memory") 1)
+        ;; Reads `len` bytes to offset 16 and returns them added to two clock readings.
+        (func (export "run") (param $len i32) (result i64)
+            (drop (call $read (i32.const 16) (local.get $len)))
+            (i64.add
+                (i64.add (call $now) (call $now))
+                (i64.extend_i32_u (i32.load (i32.const 16)))
+            )
+        )
+        (func (export "exit") (param i32)
+            (call $exit (local.get 0))
+        )
+    )
+"#;
+
+/// The host state counting the host calls and feeding the Wasm guest.
+#[derive(Debug, Default)]
+struct Host {
+    /// The number of host calls so far.
+    calls: u32,
+    /// The value of the next clock reading.
+    clock: i64,
+}
+
+/// Instantiates the [`WAT`] module with host functions serving `host`.
+fn setup(host: Host) -> (Store<Host>, Instance) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, host);
+    let mut linker = <Linker<Host>>::new(&engine);
+    linker
+        .func_wrap(
+            "env",
+            "read",
+            |mut caller: Caller<Host>, ptr: u32, len: u32| -> u32 {
+                caller.data_mut().calls += 1;
+                let seed = caller.data().clock as u8;
+                let memory = match caller.get_export("memory") {
+                    Some(Extern::Memory(memory)) => memory,
+                    _ => panic!("missing exported memory"),
+                };
+                let bytes = (0..len as u8).map(|n| seed + n).collect::<Vec<_>>();
+                memory.write(&mut caller, ptr as usize, &bytes).unwrap();
+                len
+            },
+        )
+        .unwrap();
+    linker
+        .func_wrap("env", "now", |mut caller: Caller<Host>| -> i64 {
+            let host = caller.data_mut();
+            host.calls += 1;
+            host.clock += 1;
+            host.clock
+        })
+        .unwrap();
+    linker
+        .func_wrap(
+            "env",
+            "exit",
+            |mut caller: Caller<Host>, status: i32| -> Result<(), Trap> {
+                caller.data_mut().calls += 1;
+                Err(Trap::i32_exit(status))
+            },
+        )
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+/// Calls the exported `run` function reading `len` bytes.
+fn run(store: &mut Store<Host>, instance: &Instance, len: i32) -> Result<i64, Trap> {
+    instance
+        .get_typed_func::<i32, i64>(&*store, "run")
+        .unwrap()
+        .call(store, len)
+}
+
+/// Returns the bytes read into the linear memory of the `instance`.
+fn read_bytes(store: &Store<Host>, instance: &Instance) -> [u8; 4] {
+    let mut bytes = [0x00; 4];
+    instance
+        .get_memory(store, "memory")
+        .unwrap()
+        .read(store, 16, &mut bytes)
+        .unwrap();
+    bytes
+}
+
+/// Records a single `run` of the [`WAT`] module with `len` bytes read.
+///
+/// Returns the [`HostCallLog`] in text format and the result of the `run`.
+fn record(len: i32) -> (String, i64) {
+    let (mut store, instance) = setup(Host {
+        calls: 0,
+        clock: 10,
+    });
+    store.record_host_calls();
+    let result = run(&mut store, &instance, len).unwrap();
+    let log = store.finish_host_calls().unwrap().unwrap();
+    assert_eq!(log.len(), 3);
+    assert_eq!(store.data().calls, 3);
+    (log.to_string(), result)
+}
+
+#[test]
+fn host_calls_are_not_recorded_by_default() {
+    let (mut store, instance) = setup(Host::default());
+    run(&mut store, &instance, 4).unwrap();
+    assert_eq!(store.finish_host_calls().unwrap(), None);
+}
+
+#[test]
+fn replay_host_calls() {
+    let (log, recorded) = record(4);
+    let log = log.parse::<HostCallLog>().unwrap();
+    assert_eq!(log.to_string().parse::<HostCallLog>().unwrap(), log);
+    let (mut store, instance) = setup(Host {
+        calls: 0,
+        clock: 1_000,
+    });
+    store.replay_host_calls(log.clone());
+    assert_eq!(run(&mut store, &instance, 4).unwrap(), recorded);
+    assert_eq!(read_bytes(&store, &instance), [10, 11, 12, 13]);
+    assert_eq!(store.data().calls, 0);
+    assert_eq!(store.finish_host_calls().unwrap(), Some(log));
+    // Without replaying the host functions are called again.
+    assert_ne!(run(&mut store, &instance, 4).unwrap(), recorded);
+    assert_eq!(store.data().calls, 3);
+}
+
+#[test]
+fn replay_detects_diverging_host_calls() {
+    let (log, _) = record(4);
+    let (mut store, instance) = setup(Host::default());
+    store.replay_host_calls(log.parse().unwrap());
+    let error = run(&mut store, &instance, 2).unwrap_err();
+    let error = error.downcast_ref::<HostCallError>().unwrap();
+    assert!(matches!(error, HostCallError::Mismatch { index: 0, .. }));
+    assert_eq!(store.data().calls, 0);
+}
+
+#[test]
+fn replay_detects_missing_and_extra_host_calls() {
+    let (log, _) = record(4);
+    let log = log.parse::<HostCallLog>().unwrap();
+    let (mut store, instance) = setup(Host::default());
+    store.replay_host_calls(log.clone());
+    run(&mut store, &instance, 4).unwrap();
+    let error = run(&mut store, &instance, 4).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<HostCallError>(),
+        Some(&HostCallError::Exhausted { recorded: 3 })
+    );
+    store.replay_host_calls(log);
+    assert_eq!(
+        store.finish_host_calls(),
+        Err(HostCallError::Unfinished {
+            replayed: 0,
+            recorded: 3,
+        })
+    );
+}
+
+#[test]
+fn replay_host_traps() {
+    let (mut store, instance) = setup(Host::default());
+    let exit = instance.get_typed_func::<i32, ()>(&store, "exit").unwrap();
+    store.record_host_calls();
+    let error = exit.call(&mut store, 42).unwrap_err();
+    assert_eq!(error.i32_exit_status(), Some(42));
+    let log = store.finish_host_calls().unwrap().unwrap();
+    assert_eq!(log.to_string(), "call env exit 42\nexit 42\n");
+    store.replay_host_calls(log);
+    let error = exit.call(&mut store, 42).unwrap_err();
+    assert_eq!(error.i32_exit_status(), Some(42));
+    assert_eq!(store.data().calls, 1);
+}
+
+#[test]
+fn invalid_host_call_logs() {
+    let invalid_logs = [
+        ("return 0\n", 1),
+        ("call env\nreturn\n", 1),
+        ("call env read x\nreturn\n", 1),
+        ("call env read 16 4\ncall env now\nreturn\n", 2),
+        ("call env read 16 4\nwrite 0 16 abc\nreturn\n", 2),
+        ("call env now\nreturn\ncall env now\n", 3),
+        ("call env now\nunknown\n", 2),
+    ];
+    for (log, line) in invalid_logs {
+        assert_eq!(
+            log.parse::<HostCallLog>(),
+            Err(HostCallError::InvalidLog { line }),
+            "{log:?}"
+        );
+    }
+}
+
+const WAT_EFFECTS: &str = r#"
+    (module
+        (import "env" "fill" (func $fill (param i32 i32)))
+        (import "env" "callback" (func $callback (param i32)))
+        (memory (export "memory") 1)
+        ;; Stores `value` at offset 64.
+        (func (export "store") (param $value i32)
+            (i32.store (i32.const 64) (local.get $value))
+        )
+        (func (export "run")
+            (call $fill (i32.const 300) (i32.const 7))
+            (call $callback (i32.const 42))
+        )
+    )
+"#;
+
+/// Instantiates the [`WAT_EFFECTS`] module with host functions changing its linear memory.
+///
+/// - `fill` writes two bytes via separate [`Memory::data_mut`] calls.
+/// - `callback` calls back into the exported `store` Wasm function.
+///
+/// The host functions are defined in reverse order and after an unrelated host
+/// function if `reordered` is `true` so that they are allocated differently.
+fn setup_effects(reordered: bool) -> (Store<Host>, Instance) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT_EFFECTS).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new(&engine);
+    let fill = |mut caller: Caller<Host>, ptr: u32, value: u32| {
+        caller.data_mut().calls += 1;
+        let memory = match caller.get_export("memory") {
+            Some(Extern::Memory(memory)) => memory,
+            _ => panic!("missing exported memory"),
+        };
+        memory.data_mut(&mut caller)[ptr as usize] = value as u8;
+        memory.data_mut(&mut caller)[ptr as usize + 1] = value as u8 + 1;
+    };
+    let callback = |mut caller: Caller<Host>, value: i32| -> Result<(), Trap> {
+        caller.data_mut().calls += 1;
+        caller
+            .get_export("store")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<i32, ()>(&caller)
+            .unwrap()
+            .call(&mut caller, value)
+    };
+    if reordered {
+        linker
+            .func_wrap("env", "unused", |_: Caller<Host>| {})
+            .unwrap();
+        linker.func_wrap("env", "callback", callback).unwrap();
+        linker.func_wrap("env", "fill", fill).unwrap();
+    } else {
+        linker.func_wrap("env", "fill", fill).unwrap();
+        linker.func_wrap("env", "callback", callback).unwrap();
+    }
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+#[test]
+fn replay_memory_changes_of_mutable_slices_and_wasm_callbacks() {
+    let (mut store, instance) = setup_effects(false);
+    let run = instance.get_typed_func::<(), ()>(&store, "run").unwrap();
+    store.record_host_calls();
+    run.call(&mut store, ()).unwrap();
+    let log = store.finish_host_calls().unwrap().unwrap();
+    assert_eq!(log.len(), 2);
+    // The linear memory is copied only once per host call and thus its changes found only once.
+    assert_eq!(log.to_string().matches("write 0 256 ").count(), 1);
+    let (mut store, instance) = setup_effects(true);
+    let run = instance.get_typed_func::<(), ()>(&store, "run").unwrap();
+    store.replay_host_calls(log);
+    run.call(&mut store, ()).unwrap();
+    store.finish_host_calls().unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    assert_eq!(memory.data(&store)[300..302], [7, 8]);
+    assert_eq!(memory.data(&store)[64..68], 42_i32.to_le_bytes());
+    assert_eq!(store.data().calls, 0);
+}
+
+const WAT_INDIRECT: &str = r#"
+    (module
+        (import "env" "now" (func $now (result i64)))
+        (type $outer (func (result i64)))
+        (table (export "table") 1 funcref)
+        ;; Calls the host function in the table which calls back into `now`.
+        (func (export "run") (result i64)
+            (call_indirect (type $outer) (i32.const 0))
+        )
+        (func (export "now") (result i64)
+            (call $now)
+        )
+    )
+"#;
+
+/// Instantiates the [`WAT_INDIRECT`] module with a non-imported host function in its table.
+fn setup_indirect() -> (Store<Host>, Instance) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT_INDIRECT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new(&engine);
+    linker
+        .func_wrap("env", "now", |mut caller: Caller<Host>| -> i64 {
+            caller.data_mut().calls += 1;
+            caller.data_mut().clock += 10;
+            caller.data().clock
+        })
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let outer = Func::wrap(&mut store, |caller: Caller<Host>| -> Result<i64, Trap> {
+        let now = caller
+            .get_export("now")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(), i64>(&caller)
+            .unwrap();
+        now.call(caller, ())
+    });
+    let table = instance.get_table(&store, "table").unwrap();
+    table
+        .set(&mut store, 0, Value::FuncRef(FuncRef::new(outer)))
+        .unwrap();
+    (store, instance)
+}
+
+#[test]
+fn record_host_calls_nested_within_non_imported_host_functions() {
+    let (mut store, instance) = setup_indirect();
+    let run = instance.get_typed_func::<(), i64>(&store, "run").unwrap();
+    store.record_host_calls();
+    assert_eq!(run.call(&mut store, ()).unwrap(), 10);
+    let log = store.finish_host_calls().unwrap().unwrap();
+    assert_eq!(log.to_string(), "call env now\nreturn 10\n");
+    let (mut store, instance) = setup_indirect();
+    let run = instance.get_typed_func::<(), i64>(&store, "run").unwrap();
+    store.replay_host_calls(log);
+    assert_eq!(run.call(&mut store, ()).unwrap(), 10);
+    store.finish_host_calls().unwrap();
+    assert_eq!(store.data().calls, 0);
+}
+
+#[test]
+fn reference_typed_host_calls_are_rejected() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "host" (func $host (param externref)))
+            (func (export "run")
+                (call $host (ref.null extern))
+            )
+        )
+    "#,
+    )
+    .unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new(&engine);
+    linker
+        .func_wrap("env", "host", |mut caller: Caller<Host>, _: ExternRef| {
+            caller.data_mut().calls += 1;
+        })
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let run = instance.get_typed_func::<(), ()>(&store, "run").unwrap();
+    store.record_host_calls();
+    let error = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<HostCallError>(),
+        Some(&HostCallError::ReferenceTypes {
+            func: String::from("env::host")
+        })
+    );
+    assert!(store.finish_host_calls().unwrap().unwrap().is_empty());
+    store.replay_host_calls(HostCallLog::default());
+    assert!(run.call(&mut store, ()).is_err());
+    assert_eq!(store.data().calls, 0);
+}
+
+#[test]
+fn import_names_are_escaped() {
+    let log = "call my\\smodule a\\\\b\\n 1 2\nreturn 3\n";
+    assert_eq!(log.parse::<HostCallLog>().unwrap().to_string(), log);
+}
@@ -0,0 +1,190 @@
+//! Tests for the typed access of linear memory via [`WasmPtr`] and [`WasmSlice`].
+
+use wasmi::{
+    core::{Trap, F32},
+    errors::MemoryError,
+    Caller,
+    Engine,
+    Extern,
+    GuestMemory,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Store,
+    WasmPtr,
+    WasmSlice,
+};
+
+/// Creates a [`Store`] with a single page [`Memory`].
+fn setup() -> (Store<()>, Memory) {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(1, None).unwrap()).unwrap();
+    (store, memory)
+}
+
+#[test]
+fn read_and_write_values() {
+    let (mut store, memory) = setup();
+    let ptr = WasmPtr::<u32>::new(8);
+    memory.write_ptr(&mut store, ptr, 0x1122_3344).unwrap();
+    assert_eq!(memory.read_ptr(&store, ptr).unwrap(), 0x1122_3344);
+    // Values are stored in little-endian byte order.
+    assert_eq!(&memory.data(&store)[8..12], &[0x44, 0x33, 0x22, 0x11]);
+    let ptr = ptr.add_offset(1).unwrap().cast::<F32>();
+    memory.write_ptr(&mut store, ptr, F32::from(1.5)).unwrap();
+    assert_eq!(memory.read_ptr(&store, ptr).unwrap(), F32::from(1.5));
+    let ptr = WasmPtr::<[i16; 3]>::new(32);
+    memory.write_ptr(&mut store, ptr, [-1, 2, -3]).unwrap();
+    assert_eq!(memory.read_ptr(&store, ptr).unwrap(), [-1, 2, -3]);
+    let ptr = WasmPtr::<WasmPtr<u8>>::new(64);
+    memory
+        .write_ptr(&mut store, ptr, WasmPtr::new(100))
+        .unwrap();
+    assert_eq!(memory.read_ptr(&store, ptr).unwrap(), WasmPtr::new(100));
+}
+
+#[test]
+fn read_and_write_slices() {
+    let (mut store, memory) = setup();
+    let ptr = WasmPtr::<u64>::new(16);
+    memory
+        .write_slice(&mut store, ptr, &[1, u64::MAX, 3])
+        .unwrap();
+    assert_eq!(
+        memory.read_slice(&store, ptr.slice(3)).unwrap(),
+        [1, u64::MAX, 3]
+    );
+    assert_eq!(memory.read_slice(&store, ptr.slice(0)).unwrap(), []);
+    assert_eq!(
+        memory.read_ptr(&store, ptr.add_offset(2).unwrap()).unwrap(),
+        3
+    );
+    let slice = WasmSlice::new(WasmPtr::<u8>::new(16), 2);
+    assert_eq!(slice.len(), 2);
+    assert_eq!(memory.read_slice(&store, slice).unwrap(), [1, 0]);
+}
+
+#[test]
+fn out_of_bounds_access() {
+    let (mut store, memory) = setup();
+    let last = WasmPtr::<u32>::new(65_532);
+    memory.write_ptr(&mut store, last, 42).unwrap();
+    let ptr = WasmPtr::<u32>::new(65_533);
+    assert!(matches!(
+        memory.read_ptr(&store, ptr),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    assert!(matches!(
+        memory.write_ptr(&mut store, ptr, 42),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    assert!(matches!(
+        memory.read_slice(&store, last.slice(2)),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    assert!(matches!(
+        memory.read_slice(&store, WasmPtr::<u64>::new(u32::MAX).slice(u32::MAX)),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    assert!(matches!(
+        WasmPtr::<u64>::new(u32::MAX - 4).add_offset(1),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+}
+
+#[test]
+fn read_strings() {
+    let (mut store, memory) = setup();
+    memory.write(&mut store, 0, b"hello\0world").unwrap();
+    let hello = WasmPtr::<u8>::new(0).slice(5);
+    assert_eq!(memory.read_str(&store, hello, 5).unwrap(), "hello");
+    assert!(matches!(
+        memory.read_str(&store, hello, 4),
+        Err(MemoryError::StringTooLong { limit: 4 })
+    ));
+    assert_eq!(
+        memory.read_c_str(&store, WasmPtr::new(0), 5).unwrap(),
+        "hello"
+    );
+    assert!(matches!(
+        memory.read_c_str(&store, WasmPtr::new(0), 4),
+        Err(MemoryError::StringTooLong { limit: 4 })
+    ));
+    memory.write(&mut store, 65_534, b"ab").unwrap();
+    assert!(matches!(
+        memory.read_c_str(&store, WasmPtr::new(65_534), 10),
+        Err(MemoryError::OutOfBoundsAccess)
+    ));
+    memory.write(&mut store, 0, &[0xFF]).unwrap();
+    assert!(matches!(
+        memory.read_str(&store, hello, 5),
+        Err(MemoryError::InvalidUtf8)
+    ));
+}
+
+#[test]
+fn guest_memory() {
+    let (mut store, memory) = setup();
+    let mut guest = GuestMemory::new(memory.data_mut(&mut store));
+    let ptr = WasmPtr::<i32>::new(4);
+    guest.write_slice(ptr, &[-1, -2]).unwrap();
+    assert_eq!(guest.read_ptr(ptr.add_offset(1).unwrap()).unwrap(), -2);
+    guest.write_ptr(ptr, 7).unwrap();
+    assert_eq!(guest.read_slice(ptr.slice(2)).unwrap(), [7, -2]);
+    guest.write(12, b"text\0").unwrap();
+    assert_eq!(guest.read_c_str(WasmPtr::new(12), 4).unwrap(), "text");
+    let text = WasmPtr::<u8>::new(12).slice(4);
+    assert_eq!(guest.read_str(text, 4).unwrap(), "text");
+    assert!(matches!(
+        guest.read_str(text, 3),
+        Err(MemoryError::StringTooLong { limit: 3 })
+    ));
+}
+
+#[test]
+fn host_functions_with_pointers() {
+    let wat = r#"
+        (module
+            (import "env" "sum" (func $sum (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 8) "\01\00\00\00\02\00\00\00\03\00\00\00")
+            (func (export "sum") (result i32)
+                (call $sum (i32.const 8) (i32.const 3))
+            )
+        )
+    "#;
+    let engine = Engine::default();
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, 0_u32);
+    let mut linker = <Linker<u32>>::new(&engine);
+    linker
+        .func_wrap(
+            "env",
+            "sum",
+            |mut caller: Caller<u32>, ptr: WasmPtr<u32>, len: u32| -> Result<u32, Trap> {
+                let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+                    return Err(Trap::new("missing memory export"));
+                };
+                let values = caller.guest_memory(memory).read_slice(ptr.slice(len))?;
+                let sum = values.into_iter().sum();
+                let (mut guest, calls) = caller.guest_memory_and_data(memory);
+                guest.write_ptr(WasmPtr::new(0), sum)?;
+                *calls += 1;
+                Ok(sum)
+            },
+        )
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let sum = instance.get_typed_func::<(), u32>(&store, "sum").unwrap();
+    assert_eq!(sum.call(&mut store, ()).unwrap(), 6);
+    assert_eq!(*store.data(), 1);
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    assert_eq!(memory.read_ptr(&store, WasmPtr::<u32>::new(0)).unwrap(), 6);
+}
@@ -1,7 +1,7 @@
 use super::{TestDescriptor, TestError, TestProfile, TestSpan};
-use anyhow::Result;
 use std::collections::HashMap;
 use wasmi::{
+    core::{ValueType, F32, F64},
     Config,
     Engine,
     Extern,
@@ -18,10 +18,9 @@ use wasmi::{
     TableType,
     Value,
 };
-use wasmi_core::{ValueType, F32, F64};
 use wast::token::{Id, Span};
 
-/// The context of a single Wasm test spec suite run.
+/// The context of a single `.wast` script run.
 #[derive(Debug)]
 pub struct TestContext<'a> {
     /// The `wasmi` engine used for executing functions used during the test.
@@ -30,19 +29,19 @@ pub struct TestContext<'a> {
     linker: Linker<()>,
     /// The store to hold all runtime data during the test.
     store: Store<()>,
-    /// The list of all encountered Wasm modules belonging to the test.
+    /// The list of all encountered Wasm modules belonging to the script.
     modules: Vec<Module>,
     /// The list of all instantiated modules.
     instances: HashMap<String, Instance>,
     /// The last touched module instance.
     last_instance: Option<Instance>,
-    /// Profiling during the Wasm spec test run.
+    /// Profiling during the `.wast` script run.
     profile: TestProfile,
     /// Intermediate results buffer that can be reused for calling Wasm functions.
     results: Vec<Value>,
-    /// The descriptor of the test.
+    /// The descriptor of the `.wast` script.
     ///
-    /// Useful for printing better debug messages in case of failure.
+    /// Useful for printing better messages in case of failure.
     descriptor: &'a TestDescriptor,
 }
 
@@ -116,13 +115,8 @@ impl<'a> TestContext<'a> {
 }
 
 impl TestContext<'_> {
-    /// Returns the file path of the associated `.wast` test file.
-    fn test_path(&self) -> &str {
-        self.descriptor.path()
-    }
-
-    /// Returns the [`TestDescriptor`] of the test context.
-    pub fn spanned(&self, span: Span) -> TestSpan {
+    /// Returns the [`TestSpan`] of `span` within the `.wast` script.
+    pub fn spanned(&self, span: Span) -> TestSpan<'_> {
         self.descriptor.spanned(span)
     }
 
@@ -146,23 +140,23 @@ impl TestContext<'_> {
         &mut self.profile
     }
 
+    /// Consumes the [`TestContext`] and returns its test profile.
+    pub fn into_profile(self) -> TestProfile {
+        self.profile
+    }
+
     /// Compiles the Wasm module and stores it into the [`TestContext`].
     ///
     /// # Errors
     ///
-    /// If creating the [`Module`] fails.
+    /// - If encoding the `.wast` module into `.wasm` fails.
+    /// - If creating or instantiating the [`Module`] fails.
     pub fn compile_and_instantiate(
         &mut self,
         mut module: wast::core::Module,
     ) -> Result<Instance, TestError> {
         let module_name = module.id.map(|id| id.name());
-        let wasm = module.encode().unwrap_or_else(|error| {
-            panic!(
-                "encountered unexpected failure to encode `.wast` module into `.wasm`:{}: {}",
-                self.test_path(),
-                error
-            )
-        });
+        let wasm = module.encode().map_err(TestError::Wast)?;
         let module = Module::new(self.engine(), &wasm[..])?;
         let instance_pre = self.linker.instantiate(&mut self.store, &module)?;
         let instance = instance_pre.start(&mut self.store)?;
@@ -203,25 +197,22 @@ impl TestContext<'_> {
     }
 
     /// Registers the given [`Instance`] with the given `name` and sets it as the last instance.
-    pub fn register_instance(&mut self, name: &str, instance: Instance) {
-        if self.instances.get(name).is_some() {
+    ///
+    /// # Errors
+    ///
+    /// If the exports of the [`Instance`] cannot be defined under `name` in the linker.
+    pub fn register_instance(&mut self, name: &str, instance: Instance) -> Result<(), TestError> {
+        if self.instances.contains_key(name) {
             // Already registered the instance.
-            return;
+            return Ok(());
         }
         self.instances.insert(name.to_string(), instance);
         for export in instance.exports(&self.store) {
             self.linker
-                .define(name, export.name(), export.clone().into_extern())
-                .unwrap_or_else(|error| {
-                    let field_name = export.name();
-                    let export = export.clone().into_extern();
-                    panic!(
-                        "failed to define export {name}::{field_name}: \
-                        {export:?}: {error}",
-                    )
-                });
+                .define(name, export.name(), export.into_extern())?;
         }
         self.last_instance = Some(instance);
+        Ok(())
     }
 
     /// Invokes the [`Func`] identified by `func_name` in [`Instance`] identified by `module_name`.
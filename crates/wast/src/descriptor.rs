@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Error};
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::Path,
+};
+use wast::token::Span;
+
+/// The desciptor of a `.wast` script run.
+#[derive(Debug)]
+pub struct TestDescriptor {
+    /// The path of the `.wast` script file.
+    path: String,
+    /// The contents of the `.wast` script file.
+    file: String,
+}
+
+impl TestDescriptor {
+    /// Creates a new [`TestDescriptor`] for the `.wast` script file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// If the `.wast` script file cannot properly be read.
+    pub fn new(path: &Path) -> Result<Self, Error> {
+        let file = fs::read_to_string(path)
+            .map_err(|error| anyhow!("failed to read `.wast` file {path:?}: {error}"))?;
+        Ok(Self::from_source(path.display().to_string(), file))
+    }
+
+    /// Creates a new [`TestDescriptor`] from the `.wast` script `file` located at `path`.
+    pub fn from_source(path: impl Into<String>, file: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            file: file.into(),
+        }
+    }
+
+    /// Returns the path of the `.wast` script file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the contents of the `.wast` script file.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Creates a [`TestSpan`] which can be used to print the location within the `.wast` script file.
+    pub fn spanned(&self, span: Span) -> TestSpan<'_> {
+        TestSpan {
+            path: self.path(),
+            contents: self.file(),
+            span,
+        }
+    }
+}
+
+/// Useful for printing the location where the `.wast` parse is located.
+#[derive(Debug)]
+pub struct TestSpan<'a> {
+    /// The file path of the `.wast` script.
+    path: &'a str,
+    /// The file contents of the `.wast` script.
+    contents: &'a str,
+    /// The line and column within the `.wast` script file.
+    span: Span,
+}
+
+impl Display for TestSpan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Note: `wast` reports zero-based lines and columns.
+        let (line, col) = self.span.linecol_in(self.contents);
+        write!(f, "{}:{}:{}", self.path, line + 1, col + 1)
+    }
+}
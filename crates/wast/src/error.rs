@@ -1,10 +1,11 @@
 use std::{error::Error, fmt, fmt::Display};
 use wasmi::Error as WasmiError;
 
-/// Errors that may occur upon Wasm spec test suite execution.
+/// Errors that may occur upon `.wast` script execution.
 #[derive(Debug)]
 pub enum TestError {
     Wasmi(WasmiError),
+    Wast(wast::Error),
     InstanceNotRegistered {
         name: String,
     },
@@ -17,6 +18,9 @@ pub enum TestError {
         module_name: Option<String>,
         global_name: String,
     },
+    UnsupportedArgument {
+        func_name: String,
+    },
 }
 
 impl Error for TestError {}
@@ -45,7 +49,11 @@ impl Display for TestError {
                     "missing global variable exported as: {module_name:?}::{global_name}",
                 )
             }
+            Self::UnsupportedArgument { func_name } => {
+                write!(f, "encountered unsupported argument for func: {func_name}")
+            }
             Self::Wasmi(wasmi_error) => Display::fmt(wasmi_error, f),
+            Self::Wast(wast_error) => Display::fmt(wast_error, f),
         }
     }
 }
@@ -0,0 +1,21 @@
+//! Runner for `.wast` script files.
+//!
+//! Executes all directives of a `.wast` script and reports
+//! for each of them whether it passed, failed or was skipped.
+//!
+//! This is shared by the `wast` subcommand of the `wasmi` CLI
+//! and the Wasm spec test suite of `wasmi`.
+
+mod context;
+mod descriptor;
+mod error;
+mod profile;
+mod run;
+
+pub use self::{
+    context::TestContext,
+    descriptor::{TestDescriptor, TestSpan},
+    error::TestError,
+    profile::TestProfile,
+    run::{run_wast, DirectiveOutcome, DirectiveReport, WastReport},
+};
@@ -0,0 +1,169 @@
+use std::fmt::{self, Display};
+
+/// Test profiles collected during a `.wast` script run.
+#[derive(Debug, Default, Clone)]
+pub struct TestProfile {
+    /// The total amount of executed `.wast` directives.
+    directives: usize,
+    /// The amount of `.wast` directives that passed.
+    passed: usize,
+    /// The amount of `.wast` directives that failed.
+    failed: usize,
+    /// The amount of `.wast` directives that were skipped.
+    skipped: usize,
+    /// The amount of executed `Module` directives.
+    module: usize,
+    /// The amount of executed `QuoteModule` directives.
+    quote_module: usize,
+    /// The amount of executed `AssertMalformed` directives.
+    assert_malformed: usize,
+    /// The amount of executed `AssertInvalid` directives.
+    assert_invalid: usize,
+    /// The amount of executed `Register` directives.
+    register: usize,
+    /// The amount of executed `Invoke` directives.
+    invoke: usize,
+    /// The amount of executed `AssertTrap` directives.
+    assert_trap: usize,
+    /// The amount of executed `AssertReturn` directives.
+    assert_return: usize,
+    /// The amount of executed `AssertExhaustion` directives.
+    assert_exhaustion: usize,
+    /// The amount of executed `AssertUnlinkable` directives.
+    assert_unlinkable: usize,
+    /// The amount of executed `AssertException` directives.
+    assert_exception: usize,
+}
+
+impl TestProfile {
+    /// Bumps the amount of directives.
+    pub fn bump_directives(&mut self) {
+        self.directives += 1;
+    }
+
+    /// Bumps the amount of passed directives.
+    pub fn bump_passed(&mut self) {
+        self.passed += 1;
+    }
+
+    /// Bumps the amount of failed directives.
+    pub fn bump_failed(&mut self) {
+        self.failed += 1;
+    }
+
+    /// Bumps the amount of skipped directives.
+    pub fn bump_skipped(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Returns the total amount of executed directives.
+    pub fn directives(&self) -> usize {
+        self.directives
+    }
+
+    /// Returns the amount of failed directives.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Adds all counters of `other` to the counters of `self`.
+    pub fn merge(&mut self, other: &Self) {
+        self.directives += other.directives;
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+        self.module += other.module;
+        self.quote_module += other.quote_module;
+        self.assert_malformed += other.assert_malformed;
+        self.assert_invalid += other.assert_invalid;
+        self.register += other.register;
+        self.invoke += other.invoke;
+        self.assert_trap += other.assert_trap;
+        self.assert_return += other.assert_return;
+        self.assert_exhaustion += other.assert_exhaustion;
+        self.assert_unlinkable += other.assert_unlinkable;
+        self.assert_exception += other.assert_exception;
+    }
+
+    /// Bumps the amount of `Module` directives.
+    pub fn bump_module(&mut self) {
+        self.module += 1;
+    }
+
+    /// Bumps the amount of `QuoteModule` directives.
+    pub fn bump_quote_module(&mut self) {
+        self.quote_module += 1;
+    }
+
+    /// Bumps the amount of `AssertMalformed` directives.
+    pub fn bump_assert_malformed(&mut self) {
+        self.assert_malformed += 1;
+    }
+
+    /// Bumps the amount of `AssertInvalid` directives.
+    pub fn bump_assert_invalid(&mut self) {
+        self.assert_invalid += 1;
+    }
+
+    /// Bumps the amount of `Register` directives.
+    pub fn bump_register(&mut self) {
+        self.register += 1;
+    }
+
+    /// Bumps the amount of `Invoke` directives.
+    pub fn bump_invoke(&mut self) {
+        self.invoke += 1;
+    }
+
+    /// Bumps the amount of `AssertTrap` directives.
+    pub fn bump_assert_trap(&mut self) {
+        self.assert_trap += 1;
+    }
+
+    /// Bumps the amount of `AssertReturn` directives.
+    pub fn bump_assert_return(&mut self) {
+        self.assert_return += 1;
+    }
+
+    /// Bumps the amount of `AssertExhaustion` directives.
+    pub fn bump_assert_exhaustion(&mut self) {
+        self.assert_exhaustion += 1;
+    }
+
+    /// Bumps the amount of `AssertUnlinkable` directives.
+    pub fn bump_assert_unlinkable(&mut self) {
+        self.assert_unlinkable += 1;
+    }
+
+    /// Bumps the amount of `AssertException` directives.
+    pub fn bump_assert_exception(&mut self) {
+        self.assert_exception += 1;
+    }
+}
+
+impl Display for TestProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "directives: {} ({} passed, {} failed, {} skipped)",
+            self.directives, self.passed, self.failed, self.skipped
+        )?;
+        let kinds = [
+            ("module", self.module),
+            ("quote_module", self.quote_module),
+            ("assert_malformed", self.assert_malformed),
+            ("assert_invalid", self.assert_invalid),
+            ("register", self.register),
+            ("invoke", self.invoke),
+            ("assert_trap", self.assert_trap),
+            ("assert_return", self.assert_return),
+            ("assert_exhaustion", self.assert_exhaustion),
+            ("assert_unlinkable", self.assert_unlinkable),
+            ("assert_exception", self.assert_exception),
+        ];
+        for (kind, amount) in kinds.into_iter().filter(|(_, amount)| *amount != 0) {
+            writeln!(f, "  {kind}: {amount}")?;
+        }
+        Ok(())
+    }
+}
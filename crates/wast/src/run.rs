@@ -0,0 +1,421 @@
+use super::{TestContext, TestDescriptor, TestError, TestProfile};
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use wasmi::{
+    core::{F32, F64},
+    Config,
+    ExternRef,
+    FuncRef,
+    Store,
+    Value,
+};
+use wast::{
+    core::{HeapType, NanPattern, WastArgCore, WastRetCore},
+    lexer::Lexer,
+    parser::ParseBuffer,
+    QuoteWat,
+    WastArg,
+    WastDirective,
+    WastExecute,
+    WastInvoke,
+    WastRet,
+    Wat,
+};
+
+/// The outcome of executing a single `.wast` directive.
+#[derive(Debug)]
+pub enum DirectiveOutcome {
+    /// The directive behaved as expected.
+    Passed,
+    /// The directive did not behave as expected.
+    Failed(Error),
+    /// The directive is not supported by `wasmi` and was not executed.
+    Skipped,
+}
+
+impl From<Result<()>> for DirectiveOutcome {
+    fn from(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => Self::Passed,
+            Err(error) => Self::Failed(error),
+        }
+    }
+}
+
+/// The report of a single executed `.wast` directive.
+#[derive(Debug)]
+pub struct DirectiveReport {
+    /// The `path:line:col` location of the directive within the `.wast` script.
+    location: String,
+    /// The kind of the directive, e.g. `assert_return`.
+    kind: &'static str,
+    /// The outcome of executing the directive.
+    outcome: DirectiveOutcome,
+}
+
+impl DirectiveReport {
+    /// Returns the `path:line:col` location of the directive within the `.wast` script.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns the kind of the directive, e.g. `assert_return`.
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// Returns the outcome of executing the directive.
+    pub fn outcome(&self) -> &DirectiveOutcome {
+        &self.outcome
+    }
+}
+
+/// The report of a `.wast` script run.
+#[derive(Debug)]
+pub struct WastReport {
+    /// The reports of all directives in the order of their execution.
+    directives: Vec<DirectiveReport>,
+    /// The profile collected during the `.wast` script run.
+    profile: TestProfile,
+}
+
+impl WastReport {
+    /// Returns the reports of all directives in the order of their execution.
+    pub fn directives(&self) -> &[DirectiveReport] {
+        &self.directives
+    }
+
+    /// Returns the profile collected during the `.wast` script run.
+    pub fn profile(&self) -> &TestProfile {
+        &self.profile
+    }
+}
+
+/// Runs all directives of the `.wast` script described by `test`.
+///
+/// # Note
+///
+/// Directives that fail do not stop the run but are reported as failed.
+///
+/// # Errors
+///
+/// If the `.wast` script cannot be parsed.
+pub fn run_wast(test: &TestDescriptor, config: Config) -> Result<WastReport> {
+    let mut lexer = Lexer::new(test.file());
+    lexer.allow_confusing_unicode(true);
+    let parse_buffer = ParseBuffer::new_with_lexer(lexer)
+        .map_err(|error| anyhow!("failed to create ParseBuffer for {}: {error}", test.path()))?;
+    let wast = wast::parser::parse::<wast::Wast>(&parse_buffer)
+        .map_err(|error| anyhow!("failed to parse `.wast` file {}: {error}", test.path()))?;
+    let mut context = TestContext::new(test, config);
+    let mut directives = Vec::with_capacity(wast.directives.len());
+    for directive in wast.directives {
+        let location = context.spanned(directive.span()).to_string();
+        let kind = directive_kind(&directive);
+        context.profile().bump_directives();
+        let outcome = execute_directive(&mut context, directive);
+        match &outcome {
+            DirectiveOutcome::Passed => context.profile().bump_passed(),
+            DirectiveOutcome::Failed(_) => context.profile().bump_failed(),
+            DirectiveOutcome::Skipped => context.profile().bump_skipped(),
+        }
+        directives.push(DirectiveReport {
+            location,
+            kind,
+            outcome,
+        });
+    }
+    Ok(WastReport {
+        directives,
+        profile: context.into_profile(),
+    })
+}
+
+/// Returns the name of the kind of the `directive` as used in `.wast` scripts.
+fn directive_kind(directive: &WastDirective) -> &'static str {
+    match directive {
+        WastDirective::Wat(QuoteWat::Wat(_)) => "module",
+        WastDirective::Wat(_) => "module quote",
+        WastDirective::AssertMalformed { .. } => "assert_malformed",
+        WastDirective::AssertInvalid { .. } => "assert_invalid",
+        WastDirective::Register { .. } => "register",
+        WastDirective::Invoke(_) => "invoke",
+        WastDirective::AssertTrap { .. } => "assert_trap",
+        WastDirective::AssertReturn { .. } => "assert_return",
+        WastDirective::AssertExhaustion { .. } => "assert_exhaustion",
+        WastDirective::AssertUnlinkable { .. } => "assert_unlinkable",
+        WastDirective::AssertException { .. } => "assert_exception",
+    }
+}
+
+/// Executes the `directive` and returns its [`DirectiveOutcome`].
+fn execute_directive(context: &mut TestContext, directive: WastDirective) -> DirectiveOutcome {
+    match directive {
+        WastDirective::Wat(QuoteWat::Wat(Wat::Module(module))) => {
+            context.profile().bump_module();
+            module_compilation_succeeds(context, module).into()
+        }
+        WastDirective::Wat(_) => {
+            // Quoted modules and components are not supported by `wasmi`.
+            context.profile().bump_quote_module();
+            DirectiveOutcome::Skipped
+        }
+        WastDirective::AssertMalformed {
+            module: QuoteWat::Wat(Wat::Module(module)),
+            message,
+            ..
+        } => {
+            context.profile().bump_assert_malformed();
+            module_compilation_fails(context, module, message).into()
+        }
+        WastDirective::AssertMalformed { .. } => {
+            context.profile().bump_assert_malformed();
+            DirectiveOutcome::Skipped
+        }
+        WastDirective::AssertInvalid {
+            module, message, ..
+        } => {
+            context.profile().bump_assert_invalid();
+            match extract_module(module) {
+                Some(module) => module_compilation_fails(context, module, message).into(),
+                None => DirectiveOutcome::Skipped,
+            }
+        }
+        WastDirective::Register { name, module, .. } => {
+            context.profile().bump_register();
+            register(context, name, module.map(|id| id.name())).into()
+        }
+        WastDirective::Invoke(invoke) => {
+            context.profile().bump_invoke();
+            execute_wast_invoke(context, invoke)
+                .map(|_| ())
+                .map_err(|error| anyhow!("failed to invoke: {error}"))
+                .into()
+        }
+        WastDirective::AssertTrap { exec, message, .. } => {
+            context.profile().bump_assert_trap();
+            match execute_wast_execute(context, exec) {
+                Ok(results) => DirectiveOutcome::Failed(anyhow!(
+                    "expected to trap with message '{message}' but succeeded with: {results:?}"
+                )),
+                Err(error) => assert_trap(error, message).into(),
+            }
+        }
+        WastDirective::AssertReturn {
+            exec,
+            results: expected,
+            ..
+        } => {
+            context.profile().bump_assert_return();
+            execute_wast_execute(context, exec)
+                .map_err(|error| anyhow!("failed to execute `assert_return`: {error}"))
+                .and_then(|results| assert_results(context.store(), &results, &expected))
+                .into()
+        }
+        WastDirective::AssertExhaustion { call, message, .. } => {
+            context.profile().bump_assert_exhaustion();
+            match execute_wast_invoke(context, call) {
+                Ok(results) => DirectiveOutcome::Failed(anyhow!(
+                    "expected to fail due to resource exhaustion '{message}' but succeeded with: {results:?}"
+                )),
+                Err(error) => assert_trap(error, message).into(),
+            }
+        }
+        WastDirective::AssertUnlinkable {
+            module: Wat::Module(module),
+            message,
+            ..
+        } => {
+            context.profile().bump_assert_unlinkable();
+            module_compilation_fails(context, module, message).into()
+        }
+        WastDirective::AssertUnlinkable { .. } => {
+            context.profile().bump_assert_unlinkable();
+            DirectiveOutcome::Skipped
+        }
+        WastDirective::AssertException { exec, .. } => {
+            context.profile().bump_assert_exception();
+            match execute_wast_execute(context, exec) {
+                Ok(results) => DirectiveOutcome::Failed(anyhow!(
+                    "expected to fail due to exception but succeeded with: {results:?}"
+                )),
+                Err(_) => DirectiveOutcome::Passed,
+            }
+        }
+    }
+}
+
+/// Registers the instance named `module_name` or the last instance under `name`.
+fn register(context: &mut TestContext, name: &str, module_name: Option<&str>) -> Result<()> {
+    let instance = context
+        .instance_by_name_or_last(module_name)
+        .map_err(|error| anyhow!("failed to load module: {error}"))?;
+    context
+        .register_instance(name, instance)
+        .map_err(|error| anyhow!("failed to register module as {name:?}: {error}"))
+}
+
+/// Asserts that the `error` is a trap with the expected `message`.
+///
+/// # Errors
+///
+/// - If the `error` is not a trap.
+/// - If the trap message of the `error` is not as expected.
+fn assert_trap(error: TestError, message: &str) -> Result<()> {
+    match error {
+        TestError::Wasmi(error) => {
+            ensure!(
+                error.to_string().contains(message),
+                "the directive trapped as expected but with an unexpected message\n\t\
+                    expected: {message}\n\t\
+                    encountered: {error}",
+            );
+            Ok(())
+        }
+        unexpected => bail!(
+            "encountered unexpected error: \n\t\
+                found: '{unexpected}'\n\t\
+                expected: trap with message '{message}'",
+        ),
+    }
+}
+
+/// Asserts that `results` match the `expected` values.
+///
+/// # Errors
+///
+/// If any of the `results` does not match its `expected` value.
+fn assert_results(store: &Store<()>, results: &[Value], expected: &[WastRet]) -> Result<()> {
+    ensure!(
+        results.len() == expected.len(),
+        "expected {} results but found {}",
+        expected.len(),
+        results.len()
+    );
+    for (result, expected) in results.iter().zip(expected) {
+        let expected = match expected {
+            WastRet::Core(expected) => expected,
+            WastRet::Component(expected) => bail!(
+                "`wasmi` does not support the Wasm `component-model` proposal but found {expected:?}"
+            ),
+        };
+        let matches = match (result, expected) {
+            (Value::I32(result), WastRetCore::I32(expected)) => result == expected,
+            (Value::I64(result), WastRetCore::I64(expected)) => result == expected,
+            (Value::F32(result), WastRetCore::F32(expected)) => match expected {
+                NanPattern::CanonicalNan | NanPattern::ArithmeticNan => result.is_nan(),
+                NanPattern::Value(expected) => result.to_bits() == expected.bits,
+            },
+            (Value::F64(result), WastRetCore::F64(expected)) => match expected {
+                NanPattern::CanonicalNan | NanPattern::ArithmeticNan => result.is_nan(),
+                NanPattern::Value(expected) => result.to_bits() == expected.bits,
+            },
+            (Value::FuncRef(funcref), WastRetCore::RefNull(Some(HeapType::Func))) => {
+                funcref.is_null()
+            }
+            (Value::ExternRef(externref), WastRetCore::RefNull(Some(HeapType::Extern))) => {
+                externref.is_null()
+            }
+            (Value::ExternRef(externref), WastRetCore::RefExtern(expected)) => externref
+                .data(store)
+                .and_then(|data| data.downcast_ref::<u32>())
+                .map(|value| value == expected)
+                .unwrap_or(false),
+            _ => false,
+        };
+        ensure!(
+            matches,
+            "encountered mismatch in evaluation. expected {expected:?} but found {result:?}",
+        );
+    }
+    Ok(())
+}
+
+fn extract_module(quote_wat: QuoteWat) -> Option<wast::core::Module> {
+    match quote_wat {
+        QuoteWat::Wat(Wat::Module(module)) => Some(module),
+        QuoteWat::Wat(Wat::Component(_))
+        | QuoteWat::QuoteModule(_, _)
+        | QuoteWat::QuoteComponent(_, _) => {
+            // Checks based on quoted `.wat` modules and components
+            // are not supported by `wasmi` at the moment.
+            None
+        }
+    }
+}
+
+fn module_compilation_succeeds(
+    context: &mut TestContext,
+    module: wast::core::Module,
+) -> Result<()> {
+    context
+        .compile_and_instantiate(module)
+        .map(|_| ())
+        .map_err(|error| anyhow!("failed to instantiate module but should have succeeded: {error}"))
+}
+
+fn module_compilation_fails(
+    context: &mut TestContext,
+    module: wast::core::Module,
+    expected_message: &str,
+) -> Result<()> {
+    ensure!(
+        context.compile_and_instantiate(module).is_err(),
+        "succeeded to instantiate module but should have failed with: {expected_message}",
+    );
+    Ok(())
+}
+
+fn execute_wast_execute(
+    context: &mut TestContext,
+    execute: WastExecute,
+) -> Result<Vec<Value>, TestError> {
+    match execute {
+        WastExecute::Invoke(invoke) => execute_wast_invoke(context, invoke),
+        WastExecute::Wat(Wat::Module(module)) => {
+            context.compile_and_instantiate(module).map(|_| Vec::new())
+        }
+        WastExecute::Wat(Wat::Component(_)) => {
+            // Wasmi currently does not support the Wasm component model.
+            Ok(vec![])
+        }
+        WastExecute::Get { module, global } => context
+            .get_global(module, global)
+            .map(|result| vec![result]),
+    }
+}
+
+fn execute_wast_invoke(
+    context: &mut TestContext,
+    invoke: WastInvoke,
+) -> Result<Vec<Value>, TestError> {
+    let module_name = invoke.module.map(|id| id.name());
+    let field_name = invoke.name;
+    let mut args = <Vec<Value>>::new();
+    for arg in invoke.args {
+        let value = match arg {
+            WastArg::Core(arg) => value(context.store_mut(), &arg),
+            WastArg::Component(_) => None,
+        };
+        let value = value.ok_or_else(|| TestError::UnsupportedArgument {
+            func_name: field_name.to_string(),
+        })?;
+        args.push(value);
+    }
+    context
+        .invoke(module_name, field_name, &args)
+        .map(|results| results.to_vec())
+}
+
+/// Converts the [`WastArgCore`] into a [`wasmi::Value`] if possible.
+fn value(ctx: &mut Store<()>, value: &WastArgCore) -> Option<Value> {
+    Some(match value {
+        WastArgCore::I32(arg) => Value::I32(*arg),
+        WastArgCore::I64(arg) => Value::I64(*arg),
+        WastArgCore::F32(arg) => Value::F32(F32::from_bits(arg.bits)),
+        WastArgCore::F64(arg) => Value::F64(F64::from_bits(arg.bits)),
+        WastArgCore::RefNull(HeapType::Func) => Value::FuncRef(FuncRef::null()),
+        WastArgCore::RefNull(HeapType::Extern) => Value::ExternRef(ExternRef::null()),
+        WastArgCore::RefExtern(value) => Value::ExternRef(ExternRef::new(ctx, *value)),
+        _ => return None,
+    })
+}